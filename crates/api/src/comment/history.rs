@@ -0,0 +1,37 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  comment::{GetCommentHistory, GetCommentHistoryResponse},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::source::comment::CommentEdit;
+use lemmy_db_views::structs::CommentView;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetCommentHistory {
+  type Response = GetCommentHistoryResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<GetCommentHistoryResponse, LemmyError> {
+    let data: &GetCommentHistory = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let comment_view = CommentView::read(context.pool(), data.comment_id, None).await?;
+    if local_user_view.person.id != comment_view.creator.id {
+      is_mod_or_admin(
+        context.pool(),
+        local_user_view.person.id,
+        comment_view.community.id,
+      )
+      .await?;
+    }
+
+    let edits = CommentEdit::list_for_comment(context.pool(), data.comment_id).await?;
+    Ok(GetCommentHistoryResponse { edits })
+  }
+}
@@ -7,7 +7,7 @@ use lemmy_utils::{error::LemmyError, utils::slurs::check_slurs};
 mod comment;
 mod comment_report;
 mod community;
-mod local_user;
+pub mod local_user;
 mod post;
 mod post_report;
 mod private_message;
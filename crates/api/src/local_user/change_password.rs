@@ -4,10 +4,10 @@ use bcrypt::verify;
 use lemmy_api_common::{
   context::LemmyContext,
   person::{ChangePassword, LoginResponse},
-  utils::{local_user_view_from_jwt, password_length_check},
+  utils::{generate_login_token, local_user_view_from_jwt, password_length_check},
 };
-use lemmy_db_schema::source::local_user::LocalUser;
-use lemmy_utils::{claims::Claims, error::LemmyError};
+use lemmy_db_schema::source::{local_user::LocalUser, login_token::LoginToken};
+use lemmy_utils::error::LemmyError;
 
 #[async_trait::async_trait(?Send)]
 impl Perform for ChangePassword {
@@ -40,16 +40,12 @@ impl Perform for ChangePassword {
     let updated_local_user =
       LocalUser::update_password(context.pool(), local_user_id, &new_password).await?;
 
+    // Log out all other sessions
+    LoginToken::invalidate_all(context.pool(), local_user_id).await?;
+
     // Return the jwt
     Ok(LoginResponse {
-      jwt: Some(
-        Claims::jwt(
-          updated_local_user.id.0,
-          &context.secret().jwt_secret,
-          &context.settings().hostname,
-        )?
-        .into(),
-      ),
+      jwt: Some(generate_login_token(updated_local_user.id, None, context).await?),
       verify_email_sent: false,
       registration_created: false,
     })
@@ -0,0 +1,58 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{FollowPerson, FollowPersonResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::{
+  source::person::{PersonFollower, PersonFollowerForm},
+  traits::Followable,
+};
+use lemmy_db_views_actor::structs::PersonView;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for FollowPerson {
+  type Response = FollowPersonResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<FollowPersonResponse, LemmyError> {
+    let data: &FollowPerson = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let target_id = data.person_id;
+    let person_id = local_user_view.person.id;
+
+    if target_id == person_id {
+      return Err(LemmyError::from_message("cant_follow_yourself"));
+    }
+
+    let person_view = PersonView::read(context.pool(), target_id).await?;
+    let person_follower_form = PersonFollowerForm {
+      person_id: target_id,
+      follower_id: person_id,
+      pending: false,
+    };
+
+    // Follows of remote persons are stored as pending when the activity is sent
+    if person_view.person.local && data.follow {
+      PersonFollower::follow(context.pool(), &person_follower_form)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "person_follower_already_exists"))?;
+    }
+    if !data.follow {
+      PersonFollower::unfollow(context.pool(), &person_follower_form)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "person_follower_already_exists"))?;
+    }
+
+    Ok(FollowPersonResponse {
+      person_view,
+      followed: data.follow,
+    })
+  }
+}
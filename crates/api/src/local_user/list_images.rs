@@ -0,0 +1,34 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{ListMyImages, ListMyImagesResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_views::local_image_view::LocalImageQuery;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListMyImages {
+  type Response = ListMyImagesResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<ListMyImagesResponse, LemmyError> {
+    let data: &ListMyImages = self;
+    let local_user_view = local_user_view_from_jwt(data.auth.as_ref(), context).await?;
+
+    let images = LocalImageQuery::builder()
+      .pool(context.pool())
+      .local_user_id(Some(local_user_view.local_user.id))
+      .page(data.page)
+      .limit(data.limit)
+      .build()
+      .list()
+      .await?;
+
+    Ok(ListMyImagesResponse { images })
+  }
+}
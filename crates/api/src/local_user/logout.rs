@@ -0,0 +1,25 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{Logout, LogoutResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::login_token::LoginToken;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for Logout {
+  type Response = LogoutResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<LogoutResponse, LemmyError> {
+    let data: &Logout = self;
+    // Make sure the token is valid, so that it can't be used to probe for other tokens
+    local_user_view_from_jwt(data.auth.as_ref(), context).await?;
+
+    LoginToken::invalidate(context.pool(), data.auth.as_ref()).await?;
+
+    Ok(LogoutResponse {})
+  }
+}
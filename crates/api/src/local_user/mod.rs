@@ -3,9 +3,13 @@ mod ban_person;
 mod block;
 mod change_password;
 mod change_password_after_reset;
+mod follow;
 mod get_captcha;
 mod list_banned;
-mod login;
+mod list_images;
+mod list_logins;
+pub mod login;
+mod logout;
 mod notifications;
 mod report_count;
 mod reset_password;
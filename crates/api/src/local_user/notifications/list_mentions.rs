@@ -2,6 +2,7 @@ use crate::Perform;
 use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImageLinks,
   person::{GetPersonMentions, GetPersonMentionsResponse},
   utils::local_user_view_from_jwt,
 };
@@ -40,6 +41,8 @@ impl Perform for GetPersonMentions {
       .list()
       .await?;
 
-    Ok(GetPersonMentionsResponse { mentions })
+    let mut res = GetPersonMentionsResponse { mentions };
+    res.proxy_image_links();
+    Ok(res)
   }
 }
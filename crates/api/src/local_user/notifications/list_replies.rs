@@ -2,6 +2,7 @@ use crate::Perform;
 use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImageLinks,
   person::{GetReplies, GetRepliesResponse},
   utils::local_user_view_from_jwt,
 };
@@ -37,6 +38,8 @@ impl Perform for GetReplies {
       .list()
       .await?;
 
-    Ok(GetRepliesResponse { replies })
+    let mut res = GetRepliesResponse { replies };
+    res.proxy_image_links();
+    Ok(res)
   }
 }
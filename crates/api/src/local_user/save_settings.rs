@@ -16,13 +16,14 @@ use lemmy_db_schema::{
 };
 use lemmy_db_views::structs::SiteView;
 use lemmy_utils::{
-  claims::Claims,
   error::LemmyError,
   utils::validation::{
     build_totp_2fa,
+    encryption_public_key_length_check,
     generate_totp_2fa_secret,
     is_valid_bio_field,
     is_valid_display_name,
+    is_valid_image_url,
     is_valid_matrix_id,
   },
 };
@@ -39,9 +40,14 @@ impl Perform for SaveUserSettings {
 
     let avatar = diesel_option_overwrite_to_url(&data.avatar)?;
     let banner = diesel_option_overwrite_to_url(&data.banner)?;
+    let also_known_as = diesel_option_overwrite_to_url(&data.also_known_as)?;
+    for url in [&avatar, &banner].into_iter().flatten().flatten() {
+      is_valid_image_url(url)?;
+    }
     let bio = diesel_option_overwrite(&data.bio);
     let display_name = diesel_option_overwrite(&data.display_name);
     let matrix_user_id = diesel_option_overwrite(&data.matrix_user_id);
+    let encryption_public_key = diesel_option_overwrite(&data.encryption_public_key);
     let email_deref = data.email.as_deref().map(str::to_lowercase);
     let email = diesel_option_overwrite(&email_deref);
 
@@ -76,6 +82,10 @@ impl Perform for SaveUserSettings {
       is_valid_matrix_id(matrix_user_id)?;
     }
 
+    if let Some(Some(encryption_public_key)) = &encryption_public_key {
+      encryption_public_key_length_check(encryption_public_key)?;
+    }
+
     let local_user_id = local_user_view.local_user.id;
     let person_id = local_user_view.person.id;
     let default_listing_type = data.default_listing_type;
@@ -88,6 +98,8 @@ impl Perform for SaveUserSettings {
       .bot_account(data.bot_account)
       .avatar(avatar)
       .banner(banner)
+      .also_known_as(also_known_as)
+      .encryption_public_key(encryption_public_key)
       .build();
 
     Person::update(context.pool(), person_id, &person_form)
@@ -130,31 +142,21 @@ impl Perform for SaveUserSettings {
       .build();
 
     let local_user_res = LocalUser::update(context.pool(), local_user_id, &local_user_form).await;
-    let updated_local_user = match local_user_res {
-      Ok(u) => u,
-      Err(e) => {
-        let err_type = if e.to_string()
-          == "duplicate key value violates unique constraint \"local_user_email_key\""
-        {
-          "email_already_exists"
-        } else {
-          "user_already_exists"
-        };
-
-        return Err(LemmyError::from_error_message(e, err_type));
-      }
-    };
+    if let Err(e) = local_user_res {
+      let err_type = if e.to_string()
+        == "duplicate key value violates unique constraint \"local_user_email_key\""
+      {
+        "email_already_exists"
+      } else {
+        "user_already_exists"
+      };
+
+      return Err(LemmyError::from_error_message(e, err_type));
+    }
 
-    // Return the jwt
+    // The current login stays valid, so return the same jwt
     Ok(LoginResponse {
-      jwt: Some(
-        Claims::jwt(
-          updated_local_user.id.0,
-          &context.secret().jwt_secret,
-          &context.settings().hostname,
-        )?
-        .into(),
-      ),
+      jwt: Some(data.auth.clone()),
       verify_email_sent: false,
       registration_created: false,
     })
@@ -0,0 +1,34 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{GetPostHistory, GetPostHistoryResponse},
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::post::{Post, PostEdit},
+  traits::Crud,
+};
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetPostHistory {
+  type Response = GetPostHistoryResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<GetPostHistoryResponse, LemmyError> {
+    let data: &GetPostHistory = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let post = Post::read(context.pool(), data.post_id).await?;
+    if !Post::is_post_creator(local_user_view.person.id, post.creator_id) {
+      is_mod_or_admin(context.pool(), local_user_view.person.id, post.community_id).await?;
+    }
+
+    let edits = PostEdit::list_for_post(context.pool(), post.id).await?;
+    Ok(GetPostHistoryResponse { edits })
+  }
+}
@@ -1,6 +1,8 @@
 mod feature;
 mod get_link_metadata;
+mod history;
 mod like;
 mod lock;
 mod mark_read;
 mod save;
+mod vote_poll;
@@ -0,0 +1,89 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  build_response::build_post_response,
+  context::LemmyContext,
+  post::{PostResponse, VotePoll},
+  utils::{
+    check_community_ban,
+    check_community_deleted_or_removed,
+    check_post_deleted_or_removed,
+    local_user_view_from_jwt,
+    mark_post_as_read,
+  },
+};
+use lemmy_db_schema::{
+  source::{
+    poll::{Poll, PollOption, PollVote},
+    post::Post,
+  },
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for VotePoll {
+  type Response = PostResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<PostResponse, LemmyError> {
+    let data: &VotePoll = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let post_id = data.post_id;
+    let post = Post::read(context.pool(), post_id).await?;
+    let person_id = local_user_view.person.id;
+    // Poll votes aren't federated in either direction: a vote on a remote poll would never be
+    // counted by the instance of the poll, and votes from remote users on local polls aren't
+    // received. Only local users can vote in local polls.
+    if !post.local {
+      return Err(LemmyError::from_message("cant_vote_in_remote_poll"));
+    }
+
+    check_community_ban(person_id, post.community_id, context.pool()).await?;
+    check_community_deleted_or_removed(post.community_id, context.pool()).await?;
+    check_post_deleted_or_removed(&post)?;
+
+    // Like comments, votes close with the post
+    if post.locked {
+      return Err(LemmyError::from_message("locked"));
+    }
+    if post.scheduled_publish_time.is_some() {
+      return Err(LemmyError::from_message("post_is_scheduled"));
+    }
+
+    let poll = Poll::read(context.pool(), post_id)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_poll"))?;
+    if poll.end_time.map(|t| t <= naive_now()).unwrap_or(false) {
+      return Err(LemmyError::from_message("poll_ended"));
+    }
+
+    let mut option_ids = vec![];
+    for option_id in &data.option_ids {
+      if !option_ids.contains(option_id) {
+        option_ids.push(*option_id);
+      }
+    }
+    if !poll.multiple_choice && option_ids.len() > 1 {
+      return Err(LemmyError::from_message("poll_is_single_choice"));
+    }
+    let options = PollOption::list_for_post(context.pool(), post_id).await?;
+    if !option_ids
+      .iter()
+      .all(|id| options.iter().any(|o| o.id == *id))
+    {
+      return Err(LemmyError::from_message("poll_option_not_in_poll"));
+    }
+
+    PollVote::update(context.pool(), person_id, post_id, option_ids)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_vote_in_poll"))?;
+
+    // Mark the post as read
+    mark_post_as_read(person_id, post_id, context.pool()).await?;
+
+    build_post_response(context, post.community_id, person_id, post_id).await
+  }
+}
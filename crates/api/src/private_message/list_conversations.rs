@@ -0,0 +1,34 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  private_message::{ListConversations, ListConversationsResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_views::conversation_view::ConversationQuery;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListConversations {
+  type Response = ListConversationsResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<ListConversationsResponse, LemmyError> {
+    let data: &ListConversations = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let conversations = ConversationQuery::builder()
+      .pool(context.pool())
+      .person_id(local_user_view.person.id)
+      .page(data.page)
+      .limit(data.limit)
+      .build()
+      .list()
+      .await?;
+
+    Ok(ListConversationsResponse { conversations })
+  }
+}
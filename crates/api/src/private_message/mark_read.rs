@@ -1,12 +1,13 @@
 use crate::Perform;
 use actix_web::web::Data;
+use chrono::Duration;
 use lemmy_api_common::{
   context::LemmyContext,
   private_message::{MarkPrivateMessageAsRead, PrivateMessageResponse},
   utils::local_user_view_from_jwt,
 };
 use lemmy_db_schema::{
-  source::private_message::{PrivateMessage, PrivateMessageUpdateForm},
+  source::private_message::{ConversationParticipant, PrivateMessage, PrivateMessageUpdateForm},
   traits::Crud,
 };
 use lemmy_db_views::structs::PrivateMessageView;
@@ -24,25 +25,52 @@ impl Perform for MarkPrivateMessageAsRead {
     let data: &MarkPrivateMessageAsRead = self;
     let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
 
-    // Checking permissions
+    // Checking permissions, any recipient in the conversation can mark it as read
+    let person_id = local_user_view.person.id;
     let private_message_id = data.private_message_id;
     let orig_private_message = PrivateMessage::read(context.pool(), private_message_id).await?;
-    if local_user_view.person.id != orig_private_message.recipient_id {
+    if person_id == orig_private_message.creator_id {
       return Err(LemmyError::from_message("couldnt_update_private_message"));
     }
+    let participant = ConversationParticipant::read(
+      context.pool(),
+      orig_private_message.conversation_id,
+      person_id,
+    )
+    .await
+    .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_private_message"))?;
 
-    // Doing the update
-    let private_message_id = data.private_message_id;
+    // Doing the update. Marking a message as read also marks the older messages in the
+    // conversation as read, and marking it unread does the same for the newer ones.
     let read = data.read;
-    PrivateMessage::update(
+    let published = orig_private_message.published;
+    let last_read = if read {
+      participant.last_read.max(Some(published))
+    } else {
+      participant
+        .last_read
+        .map(|l| l.min(published - Duration::microseconds(1)))
+    };
+    ConversationParticipant::update_last_read(
       context.pool(),
-      private_message_id,
-      &PrivateMessageUpdateForm::builder().read(Some(read)).build(),
+      orig_private_message.conversation_id,
+      person_id,
+      last_read,
     )
     .await
     .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_private_message"))?;
+    if person_id == orig_private_message.recipient_id {
+      PrivateMessage::update(
+        context.pool(),
+        private_message_id,
+        &PrivateMessageUpdateForm::builder().read(Some(read)).build(),
+      )
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_private_message"))?;
+    }
 
-    let view = PrivateMessageView::read(context.pool(), private_message_id).await?;
+    let mut view = PrivateMessageView::read(context.pool(), private_message_id).await?;
+    view.private_message.read = read;
     Ok(PrivateMessageResponse {
       private_message_view: view,
     })
@@ -1 +1,2 @@
+mod list_conversations;
 mod mark_read;
@@ -0,0 +1,51 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{BlockInstance, BlockInstanceResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::{
+  source::instance_block::{InstanceBlock, InstanceBlockForm},
+  traits::Blockable,
+};
+use lemmy_db_views::structs::SiteView;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for BlockInstance {
+  type Response = BlockInstanceResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<BlockInstanceResponse, LemmyError> {
+    let data: &BlockInstance = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let site_view = SiteView::read_local(context.pool()).await?;
+    if data.instance_id == site_view.site.instance_id {
+      return Err(LemmyError::from_message("cant_block_local_instance"));
+    }
+
+    let instance_block_form = InstanceBlockForm {
+      local_user_id: local_user_view.local_user.id,
+      instance_id: data.instance_id,
+    };
+
+    if data.block {
+      InstanceBlock::block(context.pool(), &instance_block_form)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "instance_block_already_exists"))?;
+    } else {
+      InstanceBlock::unblock(context.pool(), &instance_block_form)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "instance_block_not_found"))?;
+    }
+
+    Ok(BlockInstanceResponse {
+      blocked: data.block,
+    })
+  }
+}
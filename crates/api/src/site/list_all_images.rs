@@ -0,0 +1,34 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{ListAllImages, ListAllImagesResponse},
+  utils::{is_admin, local_user_view_from_jwt},
+};
+use lemmy_db_views::local_image_view::LocalImageQuery;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListAllImages {
+  type Response = ListAllImagesResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<ListAllImagesResponse, LemmyError> {
+    let data: &ListAllImages = self;
+    let local_user_view = local_user_view_from_jwt(data.auth.as_ref(), context).await?;
+    is_admin(&local_user_view)?;
+
+    let images = LocalImageQuery::builder()
+      .pool(context.pool())
+      .page(data.page)
+      .limit(data.limit)
+      .build()
+      .list()
+      .await?;
+
+    Ok(ListAllImagesResponse { images })
+  }
+}
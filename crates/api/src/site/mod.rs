@@ -1,5 +1,7 @@
+mod block;
 mod federated_instances;
 mod leave_admin;
+mod list_all_images;
 mod mod_log;
 mod purge;
 mod registration_applications;
@@ -1,6 +1,7 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
   newtypes::{CommentId, CommentReportId, CommunityId, LanguageId, LocalUserId, PostId},
+  source::comment::CommentEdit,
   CommentSortType,
   ListingType,
 };
@@ -142,6 +143,24 @@ pub struct GetCommentsResponse {
   pub comments: Vec<CommentView>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the previous versions of an edited comment. Only the creator, mods and admins can see
+/// them.
+pub struct GetCommentHistory {
+  pub comment_id: CommentId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The previous versions of a comment, oldest first.
+pub struct GetCommentHistoryResponse {
+  pub edits: Vec<CommentEdit>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
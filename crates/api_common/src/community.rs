@@ -1,7 +1,11 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
-  newtypes::{CommunityId, LanguageId, PersonId},
-  source::site::Site,
+  newtypes::{CommunityId, CommunityPostTagId, CommunityRecurringPostId, LanguageId, PersonId},
+  source::{
+    community_post_tag::CommunityPostTag,
+    community_recurring_post::CommunityRecurringPost,
+    site::Site,
+  },
   ListingType,
   SortType,
 };
@@ -33,6 +37,8 @@ pub struct GetCommunityResponse {
   pub site: Option<Site>,
   pub moderators: Vec<CommunityModeratorView>,
   pub discussion_languages: Vec<LanguageId>,
+  /// The tags which can be added to posts in the community.
+  pub post_tags: Vec<CommunityPostTag>,
 }
 
 #[skip_serializing_none]
@@ -232,3 +238,126 @@ pub struct TransferCommunity {
   pub person_id: PersonId,
   pub auth: Sensitive<String>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Create a post tag for a community (only doable by mods).
+pub struct CreateCommunityPostTag {
+  pub community_id: CommunityId,
+  pub name: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Rename a community post tag (only doable by mods).
+pub struct EditCommunityPostTag {
+  pub id: CommunityPostTagId,
+  pub name: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete a community post tag, which also removes it from all posts (only doable by mods).
+pub struct DeleteCommunityPostTag {
+  pub id: CommunityPostTagId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A response for a community post tag.
+pub struct CommunityPostTagResponse {
+  pub post_tag: CommunityPostTag,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for deleting a community post tag.
+pub struct DeleteCommunityPostTagResponse {
+  pub id: CommunityPostTagId,
+  pub success: bool,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Create a template for posts which are created regularly in a community (only doable by mods).
+///
+/// The title can contain the placeholders `{date}`, `{year}`, `{month}`, `{month_name}`, `{day}`
+/// and `{week}`. The schedule has the cron format `minute hour day-of-month month day-of-week`, in
+/// UTC.
+pub struct CreateCommunityRecurringPost {
+  pub community_id: CommunityId,
+  pub name: String,
+  pub body: Option<String>,
+  pub schedule: String,
+  /// Feature each new post in the community, and unfeature the previous one.
+  pub featured: Option<bool>,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Edit a recurring post template (only doable by mods).
+pub struct EditCommunityRecurringPost {
+  pub id: CommunityRecurringPostId,
+  pub name: Option<String>,
+  pub body: Option<String>,
+  pub schedule: Option<String>,
+  pub featured: Option<bool>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete a recurring post template. Posts which were already created are kept (only doable by
+/// mods).
+pub struct DeleteCommunityRecurringPost {
+  pub id: CommunityRecurringPostId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the recurring post templates of a community (only doable by mods).
+pub struct ListCommunityRecurringPosts {
+  pub community_id: CommunityId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A response for a recurring post template.
+pub struct CommunityRecurringPostResponse {
+  pub recurring_post: CommunityRecurringPost,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The recurring post templates of a community.
+pub struct ListCommunityRecurringPostsResponse {
+  pub recurring_posts: Vec<CommunityRecurringPost>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for deleting a recurring post template.
+pub struct DeleteCommunityRecurringPostResponse {
+  pub id: CommunityRecurringPostId,
+  pub success: bool,
+}
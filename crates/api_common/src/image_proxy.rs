@@ -0,0 +1,219 @@
+use crate::{
+  comment::GetCommentsResponse,
+  community::{GetCommunityResponse, ListCommunitiesResponse},
+  person::{GetPersonDetailsResponse, GetPersonMentionsResponse, GetRepliesResponse},
+  post::{GetPostResponse, GetPostsResponse},
+  private_message::PrivateMessagesResponse,
+  site::{ResolveObjectResponse, SearchResponse},
+};
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::{community::Community, person::Person, post::Post},
+};
+use lemmy_db_views::structs::{CommentView, PostView, PrivateMessageView};
+use lemmy_db_views_actor::structs::{
+  CommentReplyView,
+  CommunityModeratorView,
+  CommunityView,
+  PersonMentionView,
+  PersonView,
+};
+use lemmy_utils::utils::image_proxy::proxy_image_link;
+
+/// Rewrites the image links of API responses to the image proxy, if it is enabled.
+///
+/// This is done for the responses of read-only endpoints, right before they are returned. Others
+/// also send the objects to other instances, which must get the original links.
+///
+/// Only the typed image fields (avatars, banners, icons and thumbnails) of objects which were
+/// received from other instances are rewritten, because their links were recorded on receipt and
+/// the image proxy doesn't serve any others. Markdown is returned as it was written, so that it
+/// can still be edited and quoted.
+pub trait ProxyImageLinks {
+  fn proxy_image_links(&mut self);
+}
+
+fn proxy_link(link: &mut Option<DbUrl>) {
+  if let Some(original) = link.take() {
+    *link = Some(proxy_image_link(original.into()).into());
+  }
+}
+
+impl ProxyImageLinks for Person {
+  fn proxy_image_links(&mut self) {
+    if !self.local {
+      proxy_link(&mut self.avatar);
+      proxy_link(&mut self.banner);
+    }
+  }
+}
+
+impl ProxyImageLinks for Community {
+  fn proxy_image_links(&mut self) {
+    if !self.local {
+      proxy_link(&mut self.icon);
+      proxy_link(&mut self.banner);
+    }
+  }
+}
+
+impl ProxyImageLinks for Post {
+  fn proxy_image_links(&mut self) {
+    if !self.local {
+      proxy_link(&mut self.thumbnail_url);
+    }
+  }
+}
+
+impl<T: ProxyImageLinks> ProxyImageLinks for Vec<T> {
+  fn proxy_image_links(&mut self) {
+    self.iter_mut().for_each(ProxyImageLinks::proxy_image_links);
+  }
+}
+
+impl<T: ProxyImageLinks> ProxyImageLinks for Option<T> {
+  fn proxy_image_links(&mut self) {
+    if let Some(inner) = self {
+      inner.proxy_image_links();
+    }
+  }
+}
+
+impl ProxyImageLinks for PostView {
+  fn proxy_image_links(&mut self) {
+    self.post.proxy_image_links();
+    self.creator.proxy_image_links();
+    self.community.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for CommentView {
+  fn proxy_image_links(&mut self) {
+    self.creator.proxy_image_links();
+    self.post.proxy_image_links();
+    self.community.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for CommentReplyView {
+  fn proxy_image_links(&mut self) {
+    self.creator.proxy_image_links();
+    self.post.proxy_image_links();
+    self.community.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for PersonMentionView {
+  fn proxy_image_links(&mut self) {
+    self.creator.proxy_image_links();
+    self.post.proxy_image_links();
+    self.community.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for PrivateMessageView {
+  fn proxy_image_links(&mut self) {
+    self.creator.proxy_image_links();
+    self.recipient.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for CommunityView {
+  fn proxy_image_links(&mut self) {
+    self.community.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for CommunityModeratorView {
+  fn proxy_image_links(&mut self) {
+    self.community.proxy_image_links();
+    self.moderator.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for PersonView {
+  fn proxy_image_links(&mut self) {
+    self.person.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for GetPostResponse {
+  fn proxy_image_links(&mut self) {
+    self.post_view.proxy_image_links();
+    self.community_view.proxy_image_links();
+    self.moderators.proxy_image_links();
+    self.cross_posts.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for GetPostsResponse {
+  fn proxy_image_links(&mut self) {
+    self.posts.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for GetCommentsResponse {
+  fn proxy_image_links(&mut self) {
+    self.comments.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for GetCommunityResponse {
+  fn proxy_image_links(&mut self) {
+    self.community_view.proxy_image_links();
+    self.moderators.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for ListCommunitiesResponse {
+  fn proxy_image_links(&mut self) {
+    self.communities.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for GetPersonDetailsResponse {
+  fn proxy_image_links(&mut self) {
+    self.person_view.proxy_image_links();
+    self.comments.proxy_image_links();
+    self.posts.proxy_image_links();
+    self.moderates.proxy_image_links();
+    self.followers.proxy_image_links();
+    self.following.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for GetRepliesResponse {
+  fn proxy_image_links(&mut self) {
+    self.replies.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for GetPersonMentionsResponse {
+  fn proxy_image_links(&mut self) {
+    self.mentions.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for PrivateMessagesResponse {
+  fn proxy_image_links(&mut self) {
+    self.private_messages.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for SearchResponse {
+  fn proxy_image_links(&mut self) {
+    self.comments.proxy_image_links();
+    self.posts.proxy_image_links();
+    self.communities.proxy_image_links();
+    self.users.proxy_image_links();
+  }
+}
+
+impl ProxyImageLinks for ResolveObjectResponse {
+  fn proxy_image_links(&mut self) {
+    self.comment.proxy_image_links();
+    self.post.proxy_image_links();
+    self.community.proxy_image_links();
+    self.person.proxy_image_links();
+  }
+}
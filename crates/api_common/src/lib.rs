@@ -5,6 +5,8 @@ pub mod community;
 #[cfg(feature = "full")]
 pub mod context;
 pub mod custom_emoji;
+#[cfg(feature = "full")]
+pub mod image_proxy;
 pub mod person;
 pub mod post;
 pub mod private_message;
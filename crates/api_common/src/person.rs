@@ -1,11 +1,26 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
-  newtypes::{CommentReplyId, CommunityId, LanguageId, PersonId, PersonMentionId},
+  newtypes::{
+    CommentReplyId,
+    CommunityId,
+    DbUrl,
+    KeywordFilterId,
+    LanguageId,
+    ModNoteId,
+    PersonId,
+    PersonMentionId,
+  },
+  source::{
+    local_user_keyword_filter::LocalUserKeywordFilter,
+    login_token::LoginToken,
+    person::Person,
+  },
   CommentSortType,
+  KeywordFilterAction,
   ListingType,
   SortType,
 };
-use lemmy_db_views::structs::{CommentView, PostView};
+use lemmy_db_views::structs::{CommentView, LocalImageView, ModNoteView, PostView};
 use lemmy_db_views_actor::structs::{
   CommentReplyView,
   CommunityModeratorView,
@@ -126,6 +141,11 @@ pub struct SaveUserSettings {
   pub show_new_post_notifs: Option<bool>,
   /// A list of languages you are able to see discussion in.
   pub discussion_languages: Option<Vec<LanguageId>>,
+  /// The actor id of another account of yours, which is allowed to move to this one.
+  pub also_known_as: Option<String>,
+  /// A public key which other clients use to encrypt private messages to you. It is generated by
+  /// your client, which keeps the private key. An empty string removes it.
+  pub encryption_public_key: Option<String>,
   /// Generates a TOTP / 2-factor authentication token.
   ///
   /// None leaves it as is, true will generate or regenerate it, false clears it out.
@@ -144,6 +164,86 @@ pub struct ChangePassword {
   pub auth: Sensitive<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Exports your profile, settings, follows, blocks and saved items.
+pub struct ExportSettings {
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A backup of your user settings, which can be imported on this or another instance.
+///
+/// Communities, users, posts and comments are referenced by their activitypub id.
+pub struct UserSettingsBackup {
+  pub display_name: Option<String>,
+  pub bio: Option<String>,
+  pub avatar: Option<DbUrl>,
+  pub banner: Option<DbUrl>,
+  pub matrix_user_id: Option<String>,
+  pub bot_account: Option<bool>,
+  pub show_nsfw: Option<bool>,
+  pub show_scores: Option<bool>,
+  pub theme: Option<String>,
+  pub default_sort_type: Option<SortType>,
+  pub default_listing_type: Option<ListingType>,
+  pub interface_language: Option<String>,
+  pub show_avatars: Option<bool>,
+  pub send_notifications_to_email: Option<bool>,
+  pub show_bot_accounts: Option<bool>,
+  pub show_read_posts: Option<bool>,
+  pub show_new_post_notifs: Option<bool>,
+  /// Language codes, as the numeric ids differ between instances.
+  #[serde(default)]
+  pub discussion_languages: Vec<String>,
+  #[serde(default)]
+  pub followed_communities: Vec<DbUrl>,
+  #[serde(default)]
+  pub blocked_communities: Vec<DbUrl>,
+  #[serde(default)]
+  pub blocked_users: Vec<DbUrl>,
+  #[serde(default)]
+  pub saved_posts: Vec<DbUrl>,
+  #[serde(default)]
+  pub saved_comments: Vec<DbUrl>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Applies a settings backup to your account.
+///
+/// Profile and settings are applied right away, follows, blocks and saved items are fetched in the
+/// background afterwards.
+pub struct ImportSettings {
+  #[serde(flatten)]
+  pub backup: UserSettingsBackup,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response of a settings import.
+pub struct ImportSettingsResponse {
+  pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Downloads everything you wrote, as a JSON-lines archive.
+///
+/// Each line is an object with a `type` field: `profile`, `post`, `comment`, `private_message`
+/// or `image`. Image lines reference the files which you uploaded to this instance.
+pub struct ExportArchive {
+  pub auth: Sensitive<String>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -158,6 +258,55 @@ pub struct LoginResponse {
   pub verify_email_sent: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Logs out the current session, invalidating its token.
+pub struct Logout {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response of a logout.
+pub struct LogoutResponse {}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Lists the active login sessions of your user.
+pub struct ListLogins {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The active login sessions of your user.
+pub struct ListLoginsResponse {
+  pub logins: Vec<LoginToken>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Lists the images you uploaded.
+pub struct ListMyImages {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The images you uploaded, newest first.
+pub struct ListMyImagesResponse {
+  pub images: Vec<LocalImageView>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -186,6 +335,10 @@ pub struct GetPersonDetailsResponse {
   pub comments: Vec<CommentView>,
   pub posts: Vec<PostView>,
   pub moderates: Vec<CommunityModeratorView>,
+  /// Paged like posts and comments, newest follows first.
+  pub followers: Vec<Person>,
+  /// Paged like posts and comments, newest follows first.
+  pub following: Vec<Person>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -274,6 +427,45 @@ pub struct BlockPersonResponse {
   pub blocked: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Moves your account to another one, which needs to list this account in `also_known_as`.
+///
+/// Your follows of communities are transferred to the new account.
+pub struct MoveAccount {
+  /// Example: dessalines@xyz.tld
+  pub target: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for moving your account, containing the new account.
+pub struct MoveAccountResponse {
+  pub person_view: PersonView,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Follow a person, to see their posts and comments in the `Followed` listing.
+pub struct FollowPerson {
+  pub person_id: PersonId,
+  pub follow: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for following a person.
+pub struct FollowPersonResponse {
+  pub person_view: PersonView,
+  pub followed: bool,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -445,3 +637,129 @@ pub struct VerifyEmail {
 #[cfg_attr(feature = "full", ts(export))]
 /// A response to verifying your email.
 pub struct VerifyEmailResponse {}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Filter posts and comments containing a keyword out of your listings.
+pub struct CreateKeywordFilter {
+  pub keyword: String,
+  /// Defaults to hiding matching content.
+  pub action: Option<KeywordFilterAction>,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Edit one of your keyword filters.
+pub struct EditKeywordFilter {
+  pub id: KeywordFilterId,
+  pub keyword: Option<String>,
+  pub action: Option<KeywordFilterAction>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete one of your keyword filters.
+pub struct DeleteKeywordFilter {
+  pub id: KeywordFilterId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List your keyword filters.
+pub struct ListKeywordFilters {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A response for a keyword filter.
+pub struct KeywordFilterResponse {
+  pub keyword_filter: LocalUserKeywordFilter,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for deleting a keyword filter.
+pub struct DeleteKeywordFilterResponse {
+  pub id: KeywordFilterId,
+  pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Your keyword filters.
+pub struct ListKeywordFiltersResponse {
+  pub keyword_filters: Vec<LocalUserKeywordFilter>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Add a note about a user, which only moderators and admins can see.
+pub struct CreateModNote {
+  pub person_id: PersonId,
+  /// The community which the note is about. Only admins can add site-wide notes, without a
+  /// community.
+  pub community_id: Option<CommunityId>,
+  pub content: String,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the notes about a user. Without a community, admins get all notes, and moderators those of
+/// the communities they moderate.
+pub struct ListModNotes {
+  pub person_id: PersonId,
+  pub community_id: Option<CommunityId>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete a note about a user.
+pub struct DeleteModNote {
+  pub id: ModNoteId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A response for a mod note.
+pub struct ModNoteResponse {
+  pub mod_note_view: ModNoteView,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The notes about a user.
+pub struct ListModNotesResponse {
+  pub mod_notes: Vec<ModNoteView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for deleting a mod note.
+pub struct DeleteModNoteResponse {
+  pub id: ModNoteId,
+  pub success: bool,
+}
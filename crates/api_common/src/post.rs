@@ -1,6 +1,16 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PostId, PostReportId},
+  newtypes::{
+    CommentId,
+    CommunityId,
+    CommunityPostTagId,
+    DbUrl,
+    LanguageId,
+    PollOptionId,
+    PostId,
+    PostReportId,
+  },
+  source::post::PostEdit,
   ListingType,
   PostFeatureType,
   SortType,
@@ -29,6 +39,44 @@ pub struct CreatePost {
   pub honeypot: Option<String>,
   pub nsfw: Option<bool>,
   pub language_id: Option<LanguageId>,
+  /// Tags of the community which apply to the post.
+  pub tags: Option<Vec<CommunityPostTagId>>,
+  /// Makes the post a poll.
+  pub poll: Option<CreatePoll>,
+  /// A unix timestamp in the future when the post will be published. Until then, it is only
+  /// visible to its creator.
+  pub scheduled_publish_time: Option<i64>,
+  /// Lock the post. Only for moderators.
+  pub locked: Option<bool>,
+  /// Feature the post in its community. Only for moderators.
+  pub featured: Option<bool>,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A poll which is created together with a post.
+pub struct CreatePoll {
+  pub options: Vec<String>,
+  /// Allow voting for more than one option.
+  pub multiple_choice: Option<bool>,
+  /// A unix timestamp after which no more votes are accepted.
+  pub end_time: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Vote in a poll. An empty list of options removes the vote.
+///
+/// Poll votes aren't federated in either direction, so only local users can vote, and only in polls
+/// of local posts. Other instances see the vote counts through the federated poll, and votes sent
+/// by remote software (such as Mastodon's `Note` replies to a `Question`) are rejected.
+pub struct VotePoll {
+  pub post_id: PostId,
+  pub option_ids: Vec<PollOptionId>,
   pub auth: Sensitive<String>,
 }
 
@@ -62,6 +110,23 @@ pub struct GetPostResponse {
   pub cross_posts: Vec<PostView>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the previous versions of an edited post. Only the creator, mods and admins can see them.
+pub struct GetPostHistory {
+  pub post_id: PostId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The previous versions of a post, oldest first.
+pub struct GetPostHistoryResponse {
+  pub edits: Vec<PostEdit>,
+}
+
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -75,6 +140,8 @@ pub struct GetPosts {
   pub community_id: Option<CommunityId>,
   pub community_name: Option<String>,
   pub saved_only: Option<bool>,
+  /// Only posts with this tag.
+  pub tag_id: Option<CommunityPostTagId>,
   pub auth: Option<Sensitive<String>>,
 }
 
@@ -111,6 +178,8 @@ pub struct EditPost {
   pub body: Option<String>,
   pub nsfw: Option<bool>,
   pub language_id: Option<LanguageId>,
+  /// Replaces the tags of the post, if given.
+  pub tags: Option<Vec<CommunityPostTagId>>,
   pub auth: Sensitive<String>,
 }
 
@@ -255,4 +324,6 @@ pub struct SiteMetadata {
   pub description: Option<String>,
   pub(crate) image: Option<DbUrl>,
   pub embed_video_url: Option<DbUrl>,
+  /// The MIME type of the url itself, like `text/html` or `video/mp4`.
+  pub content_type: Option<String>,
 }
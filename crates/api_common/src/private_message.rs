@@ -1,11 +1,17 @@
 use crate::sensitive::Sensitive;
-use lemmy_db_schema::newtypes::{PersonId, PrivateMessageId, PrivateMessageReportId};
-use lemmy_db_views::structs::{PrivateMessageReportView, PrivateMessageView};
+use lemmy_db_schema::newtypes::{
+  ConversationId,
+  PersonId,
+  PrivateMessageId,
+  PrivateMessageReportId,
+};
+use lemmy_db_views::structs::{ConversationView, PrivateMessageReportView, PrivateMessageView};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
 use ts_rs::TS;
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -13,9 +19,16 @@ use ts_rs::TS;
 pub struct CreatePrivateMessage {
   pub content: String,
   pub recipient_id: PersonId,
+  /// Further recipients, for a group conversation.
+  pub additional_recipient_ids: Option<Vec<PersonId>>,
+  /// The message encrypted by the client for the recipients, using their
+  /// `encryption_public_key`. Then `content` should only be a notice for clients without
+  /// encryption support.
+  pub encrypted_content: Option<String>,
   pub auth: Sensitive<String>,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -23,6 +36,8 @@ pub struct CreatePrivateMessage {
 pub struct EditPrivateMessage {
   pub private_message_id: PrivateMessageId,
   pub content: String,
+  /// The new encrypted message. Leaving it out turns the message into a plaintext one.
+  pub encrypted_content: Option<String>,
   pub auth: Sensitive<String>,
 }
 
@@ -53,6 +68,8 @@ pub struct MarkPrivateMessageAsRead {
 /// Get your private messages.
 pub struct GetPrivateMessages {
   pub unread_only: Option<bool>,
+  /// Only get the messages of a single conversation.
+  pub conversation_id: Option<ConversationId>,
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub auth: Sensitive<String>,
@@ -66,6 +83,25 @@ pub struct PrivateMessagesResponse {
   pub private_messages: Vec<PrivateMessageView>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List your private message conversations, with the latest ones first.
+pub struct ListConversations {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The conversations response.
+pub struct ListConversationsResponse {
+  pub conversations: Vec<ConversationView>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
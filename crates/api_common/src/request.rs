@@ -4,10 +4,12 @@ use lemmy_db_schema::newtypes::DbUrl;
 use lemmy_utils::{
   error::LemmyError,
   settings::structs::Settings,
+  utils::validation::is_valid_content_type,
   version::VERSION,
   REQWEST_TIMEOUT,
 };
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use reqwest::header::CONTENT_TYPE;
 use reqwest_middleware::ClientWithMiddleware;
 use serde::Deserialize;
 use tracing::info;
@@ -22,16 +24,52 @@ pub async fn fetch_site_metadata(
 ) -> Result<SiteMetadata, LemmyError> {
   info!("Fetching site metadata for url: {}", url);
   let response = client.get(url.as_str()).send().await?;
+  let content_type = response
+    .headers()
+    .get(CONTENT_TYPE)
+    .and_then(|h| h.to_str().ok())
+    .map(content_type_essence)
+    .filter(|t| is_valid_content_type(t).is_ok());
+
+  // Media files don't have any tags, and can be too large to read
+  if let Some(content_type) = &content_type {
+    if is_media_content_type(content_type) || content_type.starts_with("audio/") {
+      return Ok(SiteMetadata {
+        title: None,
+        description: None,
+        image: None,
+        embed_video_url: None,
+        content_type: Some(content_type.clone()),
+      });
+    }
+  }
 
   // Can't use .text() here, because it only checks the content header, not the actual bytes
   // https://github.com/LemmyNet/lemmy/issues/1964
   let html_bytes = response.bytes().await.map_err(LemmyError::from)?.to_vec();
 
-  let tags = html_to_site_metadata(&html_bytes, url)?;
+  let mut tags = html_to_site_metadata(&html_bytes, url)?;
+  tags.content_type = content_type;
 
   Ok(tags)
 }
 
+/// The MIME type of a content type header without its parameters, like `text/html` for
+/// `text/html; charset=utf-8`.
+fn content_type_essence(content_type: &str) -> String {
+  content_type
+    .split(';')
+    .next()
+    .unwrap_or_default()
+    .trim()
+    .to_lowercase()
+}
+
+/// Images and videos, which pictrs can make thumbnails for.
+fn is_media_content_type(content_type: &str) -> bool {
+  content_type.starts_with("image/") || content_type.starts_with("video/")
+}
+
 fn html_to_site_metadata(html_bytes: &[u8], url: &Url) -> Result<SiteMetadata, LemmyError> {
   let html = String::from_utf8_lossy(html_bytes);
 
@@ -95,6 +133,7 @@ fn html_to_site_metadata(html_bytes: &[u8], url: &Url) -> Result<SiteMetadata, L
     description: og_description.or(page_description),
     image: og_image.map(Into::into),
     embed_video_url: og_embed_url.map(Into::into),
+    content_type: None,
   })
 }
 
@@ -109,6 +148,25 @@ pub(crate) struct PictrsFile {
   file: String,
   #[allow(dead_code)]
   delete_token: String,
+  details: Option<PictrsFileDetails>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct PictrsFileDetails {
+  content_type: String,
+}
+
+impl PictrsFile {
+  /// The path of the thumbnail below `/pictrs/image/`. Videos are served as they are, so pictrs
+  /// is asked for a still image of them instead.
+  fn thumbnail_path(&self) -> String {
+    match &self.details {
+      Some(details) if details.content_type.starts_with("video/") => {
+        format!("{}?format=jpg", self.file)
+      }
+      _ => self.file.clone(),
+    }
+  }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -123,7 +181,7 @@ pub(crate) async fn fetch_pictrs(
   image_url: &Url,
 ) -> Result<PictrsResponse, LemmyError> {
   let pictrs_config = settings.pictrs_config()?;
-  is_image_content_type(client, image_url).await?;
+  check_media_content_type(client, image_url).await?;
 
   let fetch_url = format!(
     "{}image/download?url={}",
@@ -157,7 +215,7 @@ pub async fn purge_image_from_pictrs(
   image_url: &Url,
 ) -> Result<(), LemmyError> {
   let pictrs_config = settings.pictrs_config()?;
-  is_image_content_type(client, image_url).await?;
+  check_media_content_type(client, image_url).await?;
 
   let alias = image_url
     .path_segments()
@@ -186,6 +244,28 @@ pub async fn purge_image_from_pictrs(
   }
 }
 
+/// Deletes an image which was uploaded through lemmy from pictrs, using its delete token. Unlike
+/// purging, this doesn't need the pictrs api key.
+pub async fn delete_image_from_pictrs(
+  client: &ClientWithMiddleware,
+  settings: &Settings,
+  alias: &str,
+  delete_token: &str,
+) -> Result<(), LemmyError> {
+  let pictrs_config = settings.pictrs_config()?;
+  let url = format!(
+    "{}image/delete/{}/{}",
+    pictrs_config.url, &delete_token, &alias
+  );
+  client
+    .get(&url)
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await?
+    .error_for_status()?;
+  Ok(())
+}
+
 /// Both are options, since the URL might be either an html page, or an image
 /// Returns the SiteMetadata, and a Pictrs URL, if there is a picture associated
 #[tracing::instrument(skip_all)]
@@ -201,8 +281,12 @@ pub async fn fetch_site_data(
       // Warning, this may ignore SSL errors
       let metadata_option = fetch_site_metadata(client, url).await.ok();
 
-      let missing_pictrs_file =
-        |r: PictrsResponse| r.files.first().expect("missing pictrs file").file.clone();
+      let missing_pictrs_file = |r: PictrsResponse| {
+        r.files
+          .first()
+          .expect("missing pictrs file")
+          .thumbnail_path()
+      };
 
       // Fetch pictrs thumbnail
       let pictrs_hash = match &metadata_option {
@@ -243,18 +327,20 @@ pub async fn fetch_site_data(
 }
 
 #[tracing::instrument(skip_all)]
-async fn is_image_content_type(client: &ClientWithMiddleware, url: &Url) -> Result<(), LemmyError> {
+async fn check_media_content_type(
+  client: &ClientWithMiddleware,
+  url: &Url,
+) -> Result<(), LemmyError> {
   let response = client.get(url.as_str()).send().await?;
-  if response
+  let content_type = response
     .headers()
-    .get("Content-Type")
+    .get(CONTENT_TYPE)
     .ok_or_else(|| LemmyError::from_message("No Content-Type header"))?
-    .to_str()?
-    .starts_with("image/")
-  {
+    .to_str()?;
+  if is_media_content_type(&content_type_essence(content_type)) {
     Ok(())
   } else {
-    Err(LemmyError::from_message("Not an image type."))
+    Err(LemmyError::from_message("Not an image or video type."))
   }
 }
 
@@ -270,6 +356,7 @@ pub fn build_user_agent(settings: &Settings) -> String {
 mod tests {
   use crate::request::{
     build_user_agent,
+    content_type_essence,
     fetch_site_metadata,
     html_to_site_metadata,
     SiteMetadata,
@@ -300,11 +387,21 @@ mod tests {
             .into()
         ),
         embed_video_url: None,
+        content_type: Some("text/html".to_string()),
       },
       sample_res
     );
   }
 
+  #[test]
+  fn test_content_type_essence() {
+    assert_eq!(
+      "text/html",
+      content_type_essence("text/html; charset=UTF-8")
+    );
+    assert_eq!("video/mp4", content_type_essence("Video/MP4"));
+  }
+
   // #[test]
   // fn test_pictshare() {
   //   let res = fetch_pictshare("https://upload.wikimedia.org/wikipedia/en/2/27/The_Mandalorian_logo.jpg");
@@ -1,6 +1,6 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommunityId, LanguageId, PersonId, PostId},
+  newtypes::{CommentId, CommunityId, InstanceId, LanguageId, PersonId, PostId},
   source::{instance::Instance, language::Language, tagline::Tagline},
   ListingType,
   ModlogActionType,
@@ -11,6 +11,7 @@ use lemmy_db_schema::{
 use lemmy_db_views::structs::{
   CommentView,
   CustomEmojiView,
+  LocalImageView,
   LocalUserView,
   PostView,
   RegistrationApplicationView,
@@ -21,6 +22,7 @@ use lemmy_db_views_actor::structs::{
   CommunityFollowerView,
   CommunityModeratorView,
   CommunityView,
+  InstanceBlockView,
   PersonBlockView,
   PersonView,
 };
@@ -52,11 +54,15 @@ use ts_rs::TS;
 #[cfg_attr(feature = "full", ts(export))]
 /// Searches the site, given a query string, and some optional filters.
 pub struct Search {
+  /// The query can contain the operators `author:`, `community:`, `instance:`, `url:`,
+  /// `nsfw:yes|no`, `before:YYYY-MM-DD` and `after:YYYY-MM-DD`.
   pub q: String,
   pub community_id: Option<CommunityId>,
   pub community_name: Option<String>,
   pub creator_id: Option<PersonId>,
   pub type_: Option<SearchType>,
+  /// Posts and comments are ordered by relevance first, the sort type only orders equally relevant
+  /// results.
   pub sort: Option<SortType>,
   pub listing_type: Option<ListingType>,
   pub page: Option<i64>,
@@ -262,6 +268,18 @@ pub struct EditSite {
   pub registration_mode: Option<RegistrationMode>,
   /// Whether to email admins for new reports.
   pub reports_email_admins: Option<bool>,
+  /// The maximum size of uploaded images in bytes. 0 removes the limit.
+  pub image_upload_max_bytes: Option<i32>,
+  /// The maximum width and height of uploaded images in pixels. 0 removes the limit.
+  pub image_upload_max_dimension: Option<i32>,
+  /// A comma separated list of MIME types which can be uploaded, like `image/png,image/jpeg`. An
+  /// empty string allows all types.
+  pub image_upload_allowed_mime_types: Option<String>,
+  /// An external service which decides whether to accept, reject or flag uploaded images. It
+  /// receives each image in a POST request and answers with
+  /// `{"action": "accept" | "reject" | "flag", "reason": ...}`. Uploads are rejected while it
+  /// can't be reached. An empty string removes it.
+  pub image_upload_classifier_url: Option<String>,
   pub auth: Sensitive<String>,
 }
 
@@ -329,6 +347,7 @@ pub struct MyUserInfo {
   pub follows: Vec<CommunityFollowerView>,
   pub moderates: Vec<CommunityModeratorView>,
   pub community_blocks: Vec<CommunityBlockView>,
+  pub instance_blocks: Vec<InstanceBlockView>,
   pub person_blocks: Vec<PersonBlockView>,
   pub discussion_languages: Vec<LanguageId>,
 }
@@ -341,6 +360,24 @@ pub struct LeaveAdmin {
   pub auth: Sensitive<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Block an instance, which hides all communities of that instance and their content.
+pub struct BlockInstance {
+  pub instance_id: InstanceId,
+  pub block: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The block instance response.
+pub struct BlockInstanceResponse {
+  pub blocked: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -403,6 +440,25 @@ pub struct PurgeItemResponse {
   pub success: bool,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Lists the images uploaded by all local users. Only for admins.
+pub struct ListAllImages {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The uploaded images, newest first.
+pub struct ListAllImagesResponse {
+  pub images: Vec<LocalImageView>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
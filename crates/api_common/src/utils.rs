@@ -1,9 +1,10 @@
 use crate::{
   context::LemmyContext,
-  request::purge_image_from_pictrs,
+  request::{delete_image_from_pictrs, purge_image_from_pictrs},
   sensitive::Sensitive,
   site::FederatedInstances,
 };
+use actix_web::{http::header::USER_AGENT, HttpRequest};
 use anyhow::Context;
 use chrono::NaiveDateTime;
 use futures::try_join;
@@ -15,8 +16,10 @@ use lemmy_db_schema::{
     community::{Community, CommunityModerator, CommunityUpdateForm},
     email_verification::{EmailVerification, EmailVerificationForm},
     instance::Instance,
+    local_image::LocalImage,
     local_site::LocalSite,
     local_site_rate_limit::LocalSiteRateLimit,
+    login_token::LoginToken,
     password_reset_request::PasswordResetRequest,
     person::{Person, PersonUpdateForm},
     person_block::PersonBlock,
@@ -24,7 +27,7 @@ use lemmy_db_schema::{
     registration_application::RegistrationApplication,
   },
   traits::{Crud, Readable},
-  utils::DbPool,
+  utils::{DbPool, FETCH_LIMIT_MAX},
   RegistrationMode,
 };
 use lemmy_db_views::{comment_view::CommentQuery, structs::LocalUserView};
@@ -142,6 +145,7 @@ pub async fn local_user_view_from_jwt(
     .map_err(|e| e.with_message("not_logged_in"))?
     .claims;
   let local_user_id = LocalUserId(claims.sub);
+  check_login_token(local_user_id, jwt, context.pool()).await?;
   let local_user_view = LocalUserView::read(context.pool(), local_user_id).await?;
   check_user_valid(
     local_user_view.person.banned,
@@ -162,6 +166,47 @@ pub async fn local_user_view_from_jwt_opt(
   local_user_view_from_jwt(jwt?, context).await.ok()
 }
 
+/// Issues a new jwt for the user and stores it as a login token, so that the session can be
+/// listed and invalidated later. The client ip and user agent are only known for actual logins.
+#[tracing::instrument(skip_all)]
+pub async fn generate_login_token(
+  local_user_id: LocalUserId,
+  req: Option<&HttpRequest>,
+  context: &LemmyContext,
+) -> Result<Sensitive<String>, LemmyError> {
+  let jwt = Claims::jwt(
+    local_user_id.0,
+    &context.secret().jwt_secret,
+    &context.settings().hostname,
+  )?;
+  let ip = req.and_then(|r| {
+    r.connection_info()
+      .realip_remote_addr()
+      .map(ToString::to_string)
+  });
+  let user_agent = req
+    .and_then(|r| r.headers().get(USER_AGENT))
+    .and_then(|h| h.to_str().ok())
+    .map(ToString::to_string);
+  LoginToken::create(context.pool(), &jwt, local_user_id, ip, user_agent).await?;
+  Ok(jwt.into())
+}
+
+/// Checks that the token was issued by this instance and hasn't been invalidated by logout.
+#[tracing::instrument(skip_all)]
+pub async fn check_login_token(
+  local_user_id: LocalUserId,
+  jwt: &str,
+  pool: &DbPool,
+) -> Result<(), LemmyError> {
+  let valid = LoginToken::validate(pool, local_user_id, jwt).await?;
+  if valid {
+    Ok(())
+  } else {
+    Err(LemmyError::from_message("not_logged_in"))
+  }
+}
+
 /// Checks if user's token was issued before user's password reset.
 pub fn check_validator_time(
   validator_time: &NaiveDateTime,
@@ -543,6 +588,33 @@ pub async fn purge_image_posts_for_person(
   Ok(())
 }
 
+/// Deletes all images which a local user uploaded, from pictrs and from the database.
+pub async fn delete_local_images_for_person(
+  person_id: PersonId,
+  pool: &DbPool,
+  settings: &Settings,
+  client: &ClientWithMiddleware,
+) -> Result<(), LemmyError> {
+  // Deleted images disappear from the list, so the first page always has the next ones
+  loop {
+    let images = LocalImage::list_for_person(pool, person_id, None, Some(FETCH_LIMIT_MAX)).await?;
+    if images.is_empty() {
+      return Ok(());
+    }
+    for image in images {
+      delete_image_from_pictrs(
+        client,
+        settings,
+        &image.pictrs_alias,
+        &image.pictrs_delete_token,
+      )
+      .await
+      .ok();
+      LocalImage::delete_by_alias(pool, &image.pictrs_alias).await?;
+    }
+  }
+}
+
 pub async fn purge_image_posts_for_community(
   banned_community_id: CommunityId,
   pool: &DbPool,
@@ -714,6 +786,9 @@ pub async fn delete_user_account(
   // Purge image posts
   purge_image_posts_for_person(person_id, pool, settings, client).await?;
 
+  // Delete all other uploads
+  delete_local_images_for_person(person_id, pool, settings, client).await?;
+
   // Leave communities they mod
   CommunityModerator::leave_all_communities(pool, person_id).await?;
 
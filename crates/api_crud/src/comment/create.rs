@@ -65,6 +65,11 @@ impl PerformCrud for CreateComment {
       return Err(LemmyError::from_message("locked"));
     }
 
+    // Scheduled posts can't be commented on before they are published
+    if post.scheduled_publish_time.is_some() {
+      return Err(LemmyError::from_message("post_is_scheduled"));
+    }
+
     // Fetch the parent, if it exists
     let parent_opt = if let Some(parent_id) = data.parent_id {
       Comment::read(context.pool(), parent_id).await.ok()
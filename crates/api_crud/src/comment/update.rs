@@ -9,7 +9,7 @@ use lemmy_api_common::{
 use lemmy_db_schema::{
   source::{
     actor_language::CommunityLanguage,
-    comment::{Comment, CommentUpdateForm},
+    comment::{Comment, CommentEdit, CommentUpdateForm},
     local_site::LocalSite,
   },
   traits::Crud,
@@ -75,6 +75,9 @@ impl PerformCrud for EditComment {
     let updated_comment = Comment::update(context.pool(), comment_id, &form)
       .await
       .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_comment"))?;
+    CommentEdit::create_for_update(context.pool(), &orig_comment.comment, &updated_comment)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_comment"))?;
 
     // Do the mentions / recipients
     let updated_comment_content = updated_comment.content.clone();
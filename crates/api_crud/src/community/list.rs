@@ -3,6 +3,7 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   community::{ListCommunities, ListCommunitiesResponse},
   context::LemmyContext,
+  image_proxy::ProxyImageLinks,
   utils::{check_private_instance, is_admin, local_user_view_from_jwt_opt},
 };
 use lemmy_db_schema::source::local_site::LocalSite;
@@ -45,6 +46,8 @@ impl PerformCrud for ListCommunities {
       .await?;
 
     // Return the jwt
-    Ok(ListCommunitiesResponse { communities })
+    let mut res = ListCommunitiesResponse { communities };
+    res.proxy_image_links();
+    Ok(res)
   }
 }
@@ -0,0 +1,73 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  community::{CommunityPostTagResponse, CreateCommunityPostTag},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_site_to_slur_regex, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    community_post_tag::{
+      CommunityPostTag,
+      CommunityPostTagInsertForm,
+      CommunityPostTagUpdateForm,
+    },
+    local_site::LocalSite,
+  },
+  traits::Crud,
+};
+use lemmy_utils::{
+  error::LemmyError,
+  utils::{slurs::check_slurs, validation::post_tag_name_length_check},
+};
+use url::Url;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreateCommunityPostTag {
+  type Response = CommunityPostTagResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<CommunityPostTagResponse, LemmyError> {
+    let data: &CreateCommunityPostTag = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    let local_site = LocalSite::read(context.pool()).await?;
+
+    is_mod_or_admin(context.pool(), local_user_view.person.id, data.community_id).await?;
+    let community = Community::read(context.pool(), data.community_id).await?;
+    // Tags of remote communities are defined by their own instance
+    if !community.local {
+      return Err(LemmyError::from_message(
+        "post_tags_only_for_local_communities",
+      ));
+    }
+
+    let name = data.name.trim().to_string();
+    post_tag_name_length_check(&name)?;
+    check_slurs(&name, &local_site_to_slur_regex(&local_site))?;
+
+    let form = CommunityPostTagInsertForm {
+      ap_id: None,
+      community_id: community.id,
+      name,
+      updated: None,
+    };
+    let inserted_tag = CommunityPostTag::create(context.pool(), &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "post_tag_already_exists"))?;
+
+    let ap_id = Url::parse(&format!("{}/tag/{}", community.actor_id, inserted_tag.id))?;
+    let form = CommunityPostTagUpdateForm {
+      ap_id: Some(ap_id.into()),
+      ..Default::default()
+    };
+    let post_tag = CommunityPostTag::update(context.pool(), inserted_tag.id, &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_post_tag"))?;
+
+    Ok(CommunityPostTagResponse { post_tag })
+  }
+}
@@ -0,0 +1,37 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  community::{DeleteCommunityPostTag, DeleteCommunityPostTagResponse},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{source::community_post_tag::CommunityPostTag, traits::Crud};
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteCommunityPostTag {
+  type Response = DeleteCommunityPostTagResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<DeleteCommunityPostTagResponse, LemmyError> {
+    let data: &DeleteCommunityPostTag = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let orig_tag = CommunityPostTag::read(context.pool(), data.id).await?;
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      orig_tag.community_id,
+    )
+    .await?;
+
+    CommunityPostTag::delete(context.pool(), data.id).await?;
+    Ok(DeleteCommunityPostTagResponse {
+      id: data.id,
+      success: true,
+    })
+  }
+}
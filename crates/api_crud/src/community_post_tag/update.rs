@@ -0,0 +1,57 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  community::{CommunityPostTagResponse, EditCommunityPostTag},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_site_to_slur_regex, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{
+    community_post_tag::{CommunityPostTag, CommunityPostTagUpdateForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_utils::{
+  error::LemmyError,
+  utils::{slurs::check_slurs, validation::post_tag_name_length_check},
+};
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for EditCommunityPostTag {
+  type Response = CommunityPostTagResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<CommunityPostTagResponse, LemmyError> {
+    let data: &EditCommunityPostTag = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    let local_site = LocalSite::read(context.pool()).await?;
+
+    let orig_tag = CommunityPostTag::read(context.pool(), data.id).await?;
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      orig_tag.community_id,
+    )
+    .await?;
+
+    let name = data.name.trim().to_string();
+    post_tag_name_length_check(&name)?;
+    check_slurs(&name, &local_site_to_slur_regex(&local_site))?;
+
+    let form = CommunityPostTagUpdateForm {
+      name: Some(name),
+      updated: Some(Some(naive_now())),
+      ..Default::default()
+    };
+    let post_tag = CommunityPostTag::update(context.pool(), data.id, &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "post_tag_already_exists"))?;
+
+    Ok(CommunityPostTagResponse { post_tag })
+  }
+}
@@ -0,0 +1,73 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  community::{CommunityRecurringPostResponse, CreateCommunityRecurringPost},
+  context::LemmyContext,
+  utils::{
+    check_community_deleted_or_removed,
+    is_mod_or_admin,
+    local_site_to_slur_regex,
+    local_user_view_from_jwt,
+  },
+};
+use lemmy_db_schema::{
+  source::{
+    community_recurring_post::{CommunityRecurringPost, CommunityRecurringPostInsertForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_utils::{
+  error::LemmyError,
+  utils::{
+    schedule::Schedule,
+    slurs::{check_slurs, check_slurs_opt},
+    validation::{is_valid_body_field, is_valid_recurring_post_title},
+  },
+};
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreateCommunityRecurringPost {
+  type Response = CommunityRecurringPostResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<CommunityRecurringPostResponse, LemmyError> {
+    let data: &CreateCommunityRecurringPost = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    let local_site = LocalSite::read(context.pool()).await?;
+
+    is_mod_or_admin(context.pool(), local_user_view.person.id, data.community_id).await?;
+    check_community_deleted_or_removed(data.community_id, context.pool()).await?;
+
+    let name = data.name.trim().to_string();
+    let slur_regex = local_site_to_slur_regex(&local_site);
+    check_slurs(&name, &slur_regex)?;
+    check_slurs_opt(&data.body, &slur_regex)?;
+    is_valid_recurring_post_title(&name)?;
+    is_valid_body_field(&data.body, true)?;
+
+    let schedule = data.schedule.trim().to_string();
+    let next_publish_time = Schedule::parse(&schedule)?
+      .next_after(naive_now())
+      .ok_or_else(|| LemmyError::from_message("invalid_schedule"))?;
+
+    let form = CommunityRecurringPostInsertForm {
+      community_id: data.community_id,
+      creator_id: local_user_view.person.id,
+      name,
+      body: data.body.clone(),
+      schedule,
+      featured: data.featured.unwrap_or(false),
+      next_publish_time: Some(next_publish_time),
+    };
+    let recurring_post = CommunityRecurringPost::create(context.pool(), &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_recurring_post"))?;
+
+    Ok(CommunityRecurringPostResponse { recurring_post })
+  }
+}
@@ -0,0 +1,37 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  community::{DeleteCommunityRecurringPost, DeleteCommunityRecurringPostResponse},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{source::community_recurring_post::CommunityRecurringPost, traits::Crud};
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteCommunityRecurringPost {
+  type Response = DeleteCommunityRecurringPostResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<DeleteCommunityRecurringPostResponse, LemmyError> {
+    let data: &DeleteCommunityRecurringPost = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let orig_recurring_post = CommunityRecurringPost::read(context.pool(), data.id).await?;
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      orig_recurring_post.community_id,
+    )
+    .await?;
+
+    CommunityRecurringPost::delete(context.pool(), data.id).await?;
+    Ok(DeleteCommunityRecurringPostResponse {
+      id: data.id,
+      success: true,
+    })
+  }
+}
@@ -0,0 +1,28 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  community::{ListCommunityRecurringPosts, ListCommunityRecurringPostsResponse},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::source::community_recurring_post::CommunityRecurringPost;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ListCommunityRecurringPosts {
+  type Response = ListCommunityRecurringPostsResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<ListCommunityRecurringPostsResponse, LemmyError> {
+    let data: &ListCommunityRecurringPosts = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    is_mod_or_admin(context.pool(), local_user_view.person.id, data.community_id).await?;
+
+    let recurring_posts =
+      CommunityRecurringPost::list_for_community(context.pool(), data.community_id).await?;
+    Ok(ListCommunityRecurringPostsResponse { recurring_posts })
+  }
+}
@@ -0,0 +1,81 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  community::{CommunityRecurringPostResponse, EditCommunityRecurringPost},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_site_to_slur_regex, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{
+    community_recurring_post::{CommunityRecurringPost, CommunityRecurringPostUpdateForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+  utils::{diesel_option_overwrite, naive_now},
+};
+use lemmy_utils::{
+  error::LemmyError,
+  utils::{
+    schedule::Schedule,
+    slurs::check_slurs_opt,
+    validation::{is_valid_body_field, is_valid_recurring_post_title},
+  },
+};
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for EditCommunityRecurringPost {
+  type Response = CommunityRecurringPostResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<CommunityRecurringPostResponse, LemmyError> {
+    let data: &EditCommunityRecurringPost = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    let local_site = LocalSite::read(context.pool()).await?;
+
+    let orig_recurring_post = CommunityRecurringPost::read(context.pool(), data.id).await?;
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      orig_recurring_post.community_id,
+    )
+    .await?;
+
+    let name = data.name.as_ref().map(|n| n.trim().to_string());
+    let slur_regex = local_site_to_slur_regex(&local_site);
+    check_slurs_opt(&name, &slur_regex)?;
+    check_slurs_opt(&data.body, &slur_regex)?;
+    if let Some(name) = &name {
+      is_valid_recurring_post_title(name)?;
+    }
+    is_valid_body_field(&data.body, true)?;
+
+    // Changing the schedule also moves the next post to the new schedule
+    let schedule = data.schedule.as_ref().map(|s| s.trim().to_string());
+    let next_publish_time = match &schedule {
+      Some(schedule) => Some(
+        Schedule::parse(schedule)?
+          .next_after(naive_now())
+          .ok_or_else(|| LemmyError::from_message("invalid_schedule"))?,
+      ),
+      None => None,
+    };
+
+    let form = CommunityRecurringPostUpdateForm {
+      name,
+      body: diesel_option_overwrite(&data.body),
+      schedule,
+      featured: data.featured,
+      next_publish_time: next_publish_time.map(Some),
+      updated: Some(Some(naive_now())),
+      ..Default::default()
+    };
+    let recurring_post = CommunityRecurringPost::update(context.pool(), data.id, &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_recurring_post"))?;
+
+    Ok(CommunityRecurringPostResponse { recurring_post })
+  }
+}
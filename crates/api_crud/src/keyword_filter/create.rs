@@ -0,0 +1,49 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{CreateKeywordFilter, KeywordFilterResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::{
+  source::local_user_keyword_filter::{LocalUserKeywordFilter, LocalUserKeywordFilterInsertForm},
+  traits::Crud,
+};
+use lemmy_utils::{error::LemmyError, utils::validation::keyword_filter_length_check};
+
+/// Each filter adds conditions to every post and comment listing of the user.
+const MAX_KEYWORD_FILTERS: usize = 100;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreateKeywordFilter {
+  type Response = KeywordFilterResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<KeywordFilterResponse, LemmyError> {
+    let data: &CreateKeywordFilter = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    let local_user_id = local_user_view.local_user.id;
+
+    let keyword = data.keyword.trim().to_lowercase();
+    keyword_filter_length_check(&keyword)?;
+
+    let filters = LocalUserKeywordFilter::list(context.pool(), local_user_id).await?;
+    if filters.len() >= MAX_KEYWORD_FILTERS {
+      return Err(LemmyError::from_message("too_many_keyword_filters"));
+    }
+
+    let form = LocalUserKeywordFilterInsertForm {
+      local_user_id,
+      keyword,
+      action: data.action,
+    };
+    let keyword_filter = LocalUserKeywordFilter::create(context.pool(), &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "keyword_filter_already_exists"))?;
+
+    Ok(KeywordFilterResponse { keyword_filter })
+  }
+}
@@ -0,0 +1,34 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{DeleteKeywordFilter, DeleteKeywordFilterResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::{source::local_user_keyword_filter::LocalUserKeywordFilter, traits::Crud};
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteKeywordFilter {
+  type Response = DeleteKeywordFilterResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<DeleteKeywordFilterResponse, LemmyError> {
+    let data: &DeleteKeywordFilter = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let orig_filter = LocalUserKeywordFilter::read(context.pool(), data.id).await?;
+    if orig_filter.local_user_id != local_user_view.local_user.id {
+      return Err(LemmyError::from_message("no_keyword_filter_edit_allowed"));
+    }
+
+    LocalUserKeywordFilter::delete(context.pool(), data.id).await?;
+    Ok(DeleteKeywordFilterResponse {
+      id: data.id,
+      success: true,
+    })
+  }
+}
@@ -0,0 +1,27 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{ListKeywordFilters, ListKeywordFiltersResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::local_user_keyword_filter::LocalUserKeywordFilter;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ListKeywordFilters {
+  type Response = ListKeywordFiltersResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<ListKeywordFiltersResponse, LemmyError> {
+    let data: &ListKeywordFilters = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let keyword_filters =
+      LocalUserKeywordFilter::list(context.pool(), local_user_view.local_user.id).await?;
+    Ok(ListKeywordFiltersResponse { keyword_filters })
+  }
+}
@@ -0,0 +1,48 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{EditKeywordFilter, KeywordFilterResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::{
+  source::local_user_keyword_filter::{LocalUserKeywordFilter, LocalUserKeywordFilterUpdateForm},
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_utils::{error::LemmyError, utils::validation::keyword_filter_length_check};
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for EditKeywordFilter {
+  type Response = KeywordFilterResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<KeywordFilterResponse, LemmyError> {
+    let data: &EditKeywordFilter = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let orig_filter = LocalUserKeywordFilter::read(context.pool(), data.id).await?;
+    if orig_filter.local_user_id != local_user_view.local_user.id {
+      return Err(LemmyError::from_message("no_keyword_filter_edit_allowed"));
+    }
+
+    let keyword = data.keyword.as_ref().map(|k| k.trim().to_lowercase());
+    if let Some(keyword) = &keyword {
+      keyword_filter_length_check(keyword)?;
+    }
+
+    let form = LocalUserKeywordFilterUpdateForm {
+      keyword,
+      action: data.action,
+      updated: Some(Some(naive_now())),
+    };
+    let keyword_filter = LocalUserKeywordFilter::update(context.pool(), data.id, &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "keyword_filter_already_exists"))?;
+
+    Ok(KeywordFilterResponse { keyword_filter })
+  }
+}
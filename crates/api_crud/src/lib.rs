@@ -4,11 +4,15 @@ use lemmy_utils::error::LemmyError;
 
 mod comment;
 mod community;
+mod community_post_tag;
+mod community_recurring_post;
 mod custom_emoji;
+mod keyword_filter;
+mod mod_note;
 mod post;
 mod private_message;
 mod site;
-mod user;
+pub mod user;
 
 #[async_trait::async_trait(?Send)]
 pub trait PerformCrud {
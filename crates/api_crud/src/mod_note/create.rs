@@ -0,0 +1,48 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{CreateModNote, ModNoteResponse},
+  utils::{is_mod_or_admin_opt, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{
+    mod_note::{ModNote, ModNoteForm},
+    person::Person,
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::ModNoteView;
+use lemmy_utils::{error::LemmyError, utils::validation::mod_note_length_check};
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreateModNote {
+  type Response = ModNoteResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<ModNoteResponse, LemmyError> {
+    let data: &CreateModNote = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    // Community notes are for its moderators, site-wide notes for admins
+    is_mod_or_admin_opt(context.pool(), Some(&local_user_view), data.community_id).await?;
+
+    let content = data.content.trim().to_string();
+    mod_note_length_check(&content)?;
+    // Make sure the person exists
+    Person::read(context.pool(), data.person_id).await?;
+
+    let form = ModNoteForm {
+      target_person_id: data.person_id,
+      community_id: data.community_id,
+      author_id: local_user_view.person.id,
+      content,
+    };
+    let mod_note = ModNote::create(context.pool(), &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_mod_note"))?;
+
+    let mod_note_view = ModNoteView::read(context.pool(), mod_note.id).await?;
+    Ok(ModNoteResponse { mod_note_view })
+  }
+}
@@ -0,0 +1,41 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{DeleteModNote, DeleteModNoteResponse},
+  utils::{is_mod_or_admin_opt, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{source::mod_note::ModNote, traits::Crud};
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteModNote {
+  type Response = DeleteModNoteResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<DeleteModNoteResponse, LemmyError> {
+    let data: &DeleteModNote = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    // The author can always delete their note, otherwise the same permissions as for creating it
+    // are needed
+    let orig_note = ModNote::read(context.pool(), data.id).await?;
+    if orig_note.author_id != local_user_view.person.id {
+      is_mod_or_admin_opt(
+        context.pool(),
+        Some(&local_user_view),
+        orig_note.community_id,
+      )
+      .await?;
+    }
+
+    ModNote::delete(context.pool(), data.id).await?;
+    Ok(DeleteModNoteResponse {
+      id: data.id,
+      success: true,
+    })
+  }
+}
@@ -0,0 +1,48 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{ListModNotes, ListModNotesResponse},
+  utils::{is_mod_or_admin_opt, local_user_view_from_jwt},
+};
+use lemmy_db_views::structs::ModNoteView;
+use lemmy_db_views_actor::structs::CommunityModeratorView;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ListModNotes {
+  type Response = ListModNotesResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<ListModNotesResponse, LemmyError> {
+    let data: &ListModNotes = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    let person_id = local_user_view.person.id;
+
+    if data.community_id.is_some() || local_user_view.person.admin {
+      is_mod_or_admin_opt(context.pool(), Some(&local_user_view), data.community_id).await?;
+      let mod_notes =
+        ModNoteView::list_for_person(context.pool(), data.person_id, data.community_id).await?;
+      return Ok(ListModNotesResponse { mod_notes });
+    }
+
+    // Moderators only get the notes of the communities they moderate
+    let moderated: Vec<_> = CommunityModeratorView::for_person(context.pool(), person_id)
+      .await?
+      .into_iter()
+      .map(|m| m.community.id)
+      .collect();
+    if moderated.is_empty() {
+      return Err(LemmyError::from_message("not_a_mod_or_admin"));
+    }
+    let mod_notes = ModNoteView::list_for_person(context.pool(), data.person_id, None)
+      .await?
+      .into_iter()
+      .filter(|n| matches!(&n.community, Some(c) if moderated.contains(&c.id)))
+      .collect();
+    Ok(ListModNotesResponse { mod_notes })
+  }
+}
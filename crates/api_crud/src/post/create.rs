@@ -10,6 +10,7 @@ use lemmy_api_common::{
     check_community_deleted_or_removed,
     generate_local_apub_endpoint,
     honeypot_check,
+    is_mod_or_admin,
     local_site_to_slur_regex,
     local_user_view_from_jwt,
     mark_post_as_read,
@@ -21,17 +22,27 @@ use lemmy_db_schema::{
   source::{
     actor_language::CommunityLanguage,
     community::Community,
+    community_post_tag::{CommunityPostTag, PostTag},
     local_site::LocalSite,
+    moderator::{ModFeaturePost, ModFeaturePostForm, ModLockPost, ModLockPostForm},
+    poll::Poll,
     post::{Post, PostInsertForm, PostLike, PostLikeForm, PostUpdateForm},
   },
   traits::{Crud, Likeable},
+  utils::naive_now,
 };
 use lemmy_db_views_actor::structs::CommunityView;
 use lemmy_utils::{
   error::LemmyError,
   utils::{
     slurs::{check_slurs, check_slurs_opt},
-    validation::{clean_url_params, is_valid_body_field, is_valid_post_title},
+    time::naive_from_unix,
+    validation::{
+      clean_url_params,
+      is_valid_body_field,
+      is_valid_poll_options,
+      is_valid_post_title,
+    },
   },
 };
 use tracing::{warn, Instrument};
@@ -58,10 +69,33 @@ impl PerformCrud for CreatePost {
 
     is_valid_post_title(&data.name)?;
     is_valid_body_field(&data.body, true)?;
+    if let Some(poll) = &data.poll {
+      is_valid_poll_options(&poll.options)?;
+      for option in &poll.options {
+        check_slurs(option, &slur_regex)?;
+      }
+      let end_time = poll.end_time.map(naive_from_unix);
+      if end_time.map(|t| t <= naive_now()).unwrap_or(false) {
+        return Err(LemmyError::from_message("poll_end_time_in_past"));
+      }
+    }
 
     check_community_ban(local_user_view.person.id, data.community_id, context.pool()).await?;
     check_community_deleted_or_removed(data.community_id, context.pool()).await?;
 
+    let scheduled_publish_time = data.scheduled_publish_time.map(naive_from_unix);
+    if scheduled_publish_time
+      .map(|t| t <= naive_now())
+      .unwrap_or(false)
+    {
+      return Err(LemmyError::from_message("scheduled_publish_time_in_past"));
+    }
+    let locked = data.locked.unwrap_or(false);
+    let featured = data.featured.unwrap_or(false);
+    if locked || featured {
+      is_mod_or_admin(context.pool(), local_user_view.person.id, data.community_id).await?;
+    }
+
     let community_id = data.community_id;
     let community = Community::read(context.pool(), community_id).await?;
     if community.posting_restricted_to_mods {
@@ -80,8 +114,8 @@ impl PerformCrud for CreatePost {
     // Fetch post links and pictrs cached image
     let (metadata_res, thumbnail_url) =
       fetch_site_data(context.client(), context.settings(), data_url).await;
-    let (embed_title, embed_description, embed_video_url) = metadata_res
-      .map(|u| (u.title, u.description, u.embed_video_url))
+    let (embed_title, embed_description, embed_video_url, url_content_type) = metadata_res
+      .map(|u| (u.title, u.description, u.embed_video_url, u.content_type))
       .unwrap_or_default();
 
     let language_id = match data.language_id {
@@ -93,6 +127,9 @@ impl PerformCrud for CreatePost {
     CommunityLanguage::is_allowed_community_language(context.pool(), language_id, community_id)
       .await?;
 
+    let tags = data.tags.clone().unwrap_or_default();
+    CommunityPostTag::check_community(context.pool(), &tags, community_id).await?;
+
     let post_form = PostInsertForm::builder()
       .name(data.name.trim().to_owned())
       .url(url)
@@ -103,11 +140,29 @@ impl PerformCrud for CreatePost {
       .embed_title(embed_title)
       .embed_description(embed_description)
       .embed_video_url(embed_video_url)
+      .url_content_type(url_content_type)
       .language_id(language_id)
       .thumbnail_url(thumbnail_url)
+      .locked(Some(locked))
+      .featured_community(Some(featured))
+      .scheduled_publish_time(scheduled_publish_time)
       .build();
 
-    let inserted_post = match Post::create(context.pool(), &post_form).await {
+    let inserted_post = match &data.poll {
+      Some(poll) => {
+        let option_names = poll.options.iter().map(|o| o.trim().to_owned()).collect();
+        Poll::create_with_post(
+          context.pool(),
+          &post_form,
+          poll.multiple_choice.unwrap_or(false),
+          poll.end_time.map(naive_from_unix),
+          option_names,
+        )
+        .await
+      }
+      None => Post::create(context.pool(), &post_form).await,
+    };
+    let inserted_post = match inserted_post {
       Ok(post) => post,
       Err(e) => {
         let err_type = if e.to_string() == "value too long for type character varying(200)" {
@@ -135,6 +190,28 @@ impl PerformCrud for CreatePost {
     .await
     .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_post"))?;
 
+    PostTag::update(context.pool(), inserted_post_id, tags)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_post"))?;
+
+    if locked {
+      let form = ModLockPostForm {
+        mod_person_id: local_user_view.person.id,
+        post_id: inserted_post_id,
+        locked: Some(true),
+      };
+      ModLockPost::create(context.pool(), &form).await?;
+    }
+    if featured {
+      let form = ModFeaturePostForm {
+        mod_person_id: local_user_view.person.id,
+        post_id: inserted_post_id,
+        featured: true,
+        is_featured_community: true,
+      };
+      ModFeaturePost::create(context.pool(), &form).await?;
+    }
+
     // They like their own post by default
     let person_id = local_user_view.person.id;
     let post_id = inserted_post.id;
@@ -151,7 +228,12 @@ impl PerformCrud for CreatePost {
     // Mark the post as read
     mark_post_as_read(person_id, post_id, context.pool()).await?;
 
-    if let Some(url) = &updated_post.url {
+    // Scheduled posts can't be seen by the linked site yet
+    let webmention_url = updated_post
+      .url
+      .as_ref()
+      .filter(|_| updated_post.scheduled_publish_time.is_none());
+    if let Some(url) = webmention_url {
       let mut webmention =
         Webmention::new::<Url>(updated_post.ap_id.clone().into(), url.clone().into())?;
       webmention.set_checked(true);
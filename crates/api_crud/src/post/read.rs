@@ -2,6 +2,7 @@ use crate::PerformCrud;
 use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImageLinks,
   post::{GetPost, GetPostResponse},
   utils::{
     check_private_instance,
@@ -106,11 +107,13 @@ impl PerformCrud for GetPost {
     };
 
     // Return the jwt
-    Ok(GetPostResponse {
+    let mut res = GetPostResponse {
       post_view,
       community_view,
       moderators,
       cross_posts,
-    })
+    };
+    res.proxy_image_links();
+    Ok(res)
   }
 }
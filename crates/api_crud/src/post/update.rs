@@ -10,8 +10,9 @@ use lemmy_api_common::{
 use lemmy_db_schema::{
   source::{
     actor_language::CommunityLanguage,
+    community_post_tag::{CommunityPostTag, PostTag},
     local_site::LocalSite,
-    post::{Post, PostUpdateForm},
+    post::{Post, PostEdit, PostUpdateForm},
   },
   traits::Crud,
   utils::{diesel_option_overwrite, naive_now},
@@ -70,8 +71,15 @@ impl PerformCrud for EditPost {
     let data_url = data.url.as_ref();
     let (metadata_res, thumbnail_url) =
       fetch_site_data(context.client(), context.settings(), data_url).await;
-    let (embed_title, embed_description, embed_video_url) = metadata_res
-      .map(|u| (Some(u.title), Some(u.description), Some(u.embed_video_url)))
+    let (embed_title, embed_description, embed_video_url, url_content_type) = metadata_res
+      .map(|u| {
+        (
+          Some(u.title),
+          Some(u.description),
+          Some(u.embed_video_url),
+          Some(u.content_type),
+        )
+      })
       .unwrap_or_default();
 
     let language_id = self.language_id;
@@ -82,6 +90,10 @@ impl PerformCrud for EditPost {
     )
     .await?;
 
+    if let Some(tags) = &data.tags {
+      CommunityPostTag::check_community(context.pool(), tags, orig_post.community_id).await?;
+    }
+
     let post_form = PostUpdateForm::builder()
       .name(data.name.clone())
       .url(url)
@@ -90,21 +102,33 @@ impl PerformCrud for EditPost {
       .embed_title(embed_title)
       .embed_description(embed_description)
       .embed_video_url(embed_video_url)
+      .url_content_type(url_content_type)
       .language_id(data.language_id)
       .thumbnail_url(Some(thumbnail_url))
       .updated(Some(Some(naive_now())))
       .build();
 
     let post_id = data.post_id;
-    let res = Post::update(context.pool(), post_id, &post_form).await;
-    if let Err(e) = res {
-      let err_type = if e.to_string() == "value too long for type character varying(200)" {
-        "post_title_too_long"
-      } else {
-        "couldnt_update_post"
-      };
-
-      return Err(LemmyError::from_error_message(e, err_type));
+    let updated_post = match Post::update(context.pool(), post_id, &post_form).await {
+      Ok(post) => post,
+      Err(e) => {
+        let err_type = if e.to_string() == "value too long for type character varying(200)" {
+          "post_title_too_long"
+        } else {
+          "couldnt_update_post"
+        };
+
+        return Err(LemmyError::from_error_message(e, err_type));
+      }
+    };
+    PostEdit::create_for_update(context.pool(), &orig_post, &updated_post)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_post"))?;
+
+    if let Some(tags) = &data.tags {
+      PostTag::update(context.pool(), post_id, tags.clone())
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_post"))?;
     }
 
     build_post_response(
@@ -16,16 +16,28 @@ use lemmy_api_common::{
 use lemmy_db_schema::{
   source::{
     local_site::LocalSite,
-    private_message::{PrivateMessage, PrivateMessageInsertForm, PrivateMessageUpdateForm},
+    person::Person,
+    private_message::{
+      Conversation,
+      PrivateMessage,
+      PrivateMessageInsertForm,
+      PrivateMessageUpdateForm,
+    },
   },
   traits::Crud,
 };
 use lemmy_db_views::structs::{LocalUserView, PrivateMessageView};
 use lemmy_utils::{
   error::LemmyError,
-  utils::{slurs::remove_slurs, validation::is_valid_body_field},
+  utils::{
+    slurs::remove_slurs,
+    validation::{encrypted_content_length_check, is_valid_body_field},
+  },
 };
 
+/// The most people a single private message can be sent to.
+const MAX_PRIVATE_MESSAGE_RECIPIENTS: usize = 20;
+
 #[async_trait::async_trait(?Send)]
 impl PerformCrud for CreatePrivateMessage {
   type Response = PrivateMessageResponse;
@@ -45,12 +57,43 @@ impl PerformCrud for CreatePrivateMessage {
     );
     is_valid_body_field(&Some(content_slurs_removed.clone()), false)?;
 
-    check_person_block(local_user_view.person.id, data.recipient_id, context.pool()).await?;
+    // The first recipient is stored with the message, the others only in the conversation
+    let mut recipient_ids = vec![data.recipient_id];
+    for recipient_id in data.additional_recipient_ids.iter().flatten() {
+      if !recipient_ids.contains(recipient_id) && *recipient_id != local_user_view.person.id {
+        recipient_ids.push(*recipient_id);
+      }
+    }
+    if recipient_ids.len() > MAX_PRIVATE_MESSAGE_RECIPIENTS {
+      return Err(LemmyError::from_message("too_many_recipients"));
+    }
+    for recipient_id in &recipient_ids {
+      check_person_block(local_user_view.person.id, *recipient_id, context.pool()).await?;
+    }
+
+    // Encrypted messages can only be sent to people whose clients published a key
+    if let Some(encrypted_content) = &data.encrypted_content {
+      encrypted_content_length_check(encrypted_content)?;
+      for recipient_id in &recipient_ids {
+        let recipient = Person::read(context.pool(), *recipient_id).await?;
+        if recipient.encryption_public_key.is_none() {
+          return Err(LemmyError::from_message("recipient_has_no_encryption_key"));
+        }
+      }
+    }
+
+    let mut participants = recipient_ids.clone();
+    participants.push(local_user_view.person.id);
+    let conversation = Conversation::read_or_create(context.pool(), &participants)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_private_message"))?;
 
     let private_message_form = PrivateMessageInsertForm::builder()
       .content(content_slurs_removed.clone())
       .creator_id(local_user_view.person.id)
       .recipient_id(data.recipient_id)
+      .conversation_id(conversation.id)
+      .encrypted_content(data.encrypted_content.clone())
       .build();
 
     let inserted_private_message =
@@ -80,13 +123,21 @@ impl PerformCrud for CreatePrivateMessage {
     )
     .await
     .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_private_message"))?;
+    Conversation::update_for_message(
+      context.pool(),
+      conversation.id,
+      inserted_private_message.published,
+    )
+    .await?;
 
     let view = PrivateMessageView::read(context.pool(), inserted_private_message.id).await?;
 
-    // Send email to the local recipient, if one exists
-    if view.recipient.local {
-      let recipient_id = data.recipient_id;
-      let local_recipient = LocalUserView::read_person(context.pool(), recipient_id).await?;
+    // Send email to the local recipients, remote ones have no local user
+    for recipient_id in recipient_ids {
+      let local_recipient = match LocalUserView::read_person(context.pool(), recipient_id).await {
+        Ok(local_recipient) => local_recipient,
+        Err(_) => continue,
+      };
       let lang = get_interface_language(&local_recipient);
       let inbox_link = format!("{}/inbox", context.settings().get_protocol_and_hostname());
       let sender_name = &local_user_view.person.name;
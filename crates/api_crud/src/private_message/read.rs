@@ -2,9 +2,11 @@ use crate::PerformCrud;
 use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImageLinks,
   private_message::{GetPrivateMessages, PrivateMessagesResponse},
   utils::local_user_view_from_jwt,
 };
+use lemmy_db_schema::source::private_message::ConversationParticipant;
 use lemmy_db_views::private_message_view::PrivateMessageQuery;
 use lemmy_utils::error::LemmyError;
 
@@ -21,12 +23,20 @@ impl PerformCrud for GetPrivateMessages {
     let local_user_view = local_user_view_from_jwt(data.auth.as_ref(), context).await?;
     let person_id = local_user_view.person.id;
 
+    // Only participants can read the messages of a conversation
+    if let Some(conversation_id) = data.conversation_id {
+      ConversationParticipant::read(context.pool(), conversation_id, person_id)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "not_a_conversation_participant"))?;
+    }
+
     let page = data.page;
     let limit = data.limit;
     let unread_only = data.unread_only;
-    let mut messages = PrivateMessageQuery::builder()
+    let messages = PrivateMessageQuery::builder()
       .pool(context.pool())
       .recipient_id(person_id)
+      .conversation_id(data.conversation_id)
       .page(page)
       .limit(limit)
       .unread_only(unread_only)
@@ -34,16 +44,10 @@ impl PerformCrud for GetPrivateMessages {
       .list()
       .await?;
 
-    // Messages sent by ourselves should be marked as read. The `read` column in database is only
-    // for the recipient, and shouldnt be exposed to sender.
-    messages.iter_mut().for_each(|pmv| {
-      if pmv.creator.id == person_id {
-        pmv.private_message.read = true
-      }
-    });
-
-    Ok(PrivateMessagesResponse {
+    let mut res = PrivateMessagesResponse {
       private_messages: messages,
-    })
+    };
+    res.proxy_image_links();
+    Ok(res)
   }
 }
@@ -16,7 +16,10 @@ use lemmy_db_schema::{
 use lemmy_db_views::structs::PrivateMessageView;
 use lemmy_utils::{
   error::LemmyError,
-  utils::{slurs::remove_slurs, validation::is_valid_body_field},
+  utils::{
+    slurs::remove_slurs,
+    validation::{encrypted_content_length_check, is_valid_body_field},
+  },
 };
 
 #[async_trait::async_trait(?Send)]
@@ -42,6 +45,9 @@ impl PerformCrud for EditPrivateMessage {
     // Doing the update
     let content_slurs_removed = remove_slurs(&data.content, &local_site_to_slur_regex(&local_site));
     is_valid_body_field(&Some(content_slurs_removed.clone()), false)?;
+    if let Some(encrypted_content) = &data.encrypted_content {
+      encrypted_content_length_check(encrypted_content)?;
+    }
 
     let private_message_id = data.private_message_id;
     PrivateMessage::update(
@@ -49,6 +55,7 @@ impl PerformCrud for EditPrivateMessage {
       private_message_id,
       &PrivateMessageUpdateForm::builder()
         .content(Some(content_slurs_removed))
+        .encrypted_content(Some(data.encrypted_content.clone()))
         .updated(Some(Some(naive_now())))
         .build(),
     )
@@ -521,6 +521,10 @@ mod tests {
       updated: None,
       registration_mode: site_registration_mode,
       reports_email_admins: false,
+      image_upload_max_bytes: None,
+      image_upload_max_dimension: None,
+      image_upload_allowed_mime_types: None,
+      image_upload_classifier_url: None,
     }
   }
 
@@ -4,7 +4,7 @@ use lemmy_api_common::{
   context::LemmyContext,
   sensitive::Sensitive,
   site::{GetSite, GetSiteResponse, MyUserInfo},
-  utils::{check_user_valid, check_validator_time},
+  utils::{check_login_token, check_user_valid, check_validator_time},
 };
 use lemmy_db_schema::{
   newtypes::LocalUserId,
@@ -19,6 +19,7 @@ use lemmy_db_views_actor::structs::{
   CommunityBlockView,
   CommunityFollowerView,
   CommunityModeratorView,
+  InstanceBlockView,
   PersonBlockView,
   PersonView,
 };
@@ -52,6 +53,10 @@ impl PerformCrud for GetSite {
         .await
         .map_err(|e| LemmyError::from_error_message(e, "system_err_login"))?;
 
+      let instance_blocks = InstanceBlockView::for_local_user(context.pool(), local_user_id)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "system_err_login"))?;
+
       let person_id = local_user_view.person.id;
       let person_blocks = PersonBlockView::for_person(context.pool(), person_id)
         .await
@@ -70,6 +75,7 @@ impl PerformCrud for GetSite {
         follows,
         moderates,
         community_blocks,
+        instance_blocks,
         person_blocks,
         discussion_languages,
       })
@@ -106,6 +112,9 @@ async fn local_user_settings_view_from_jwt_opt(
         .ok()?
         .claims;
       let local_user_id = LocalUserId(claims.sub);
+      check_login_token(local_user_id, jwt.as_ref(), context.pool())
+        .await
+        .ok()?;
       let local_user_view = LocalUserView::read(context.pool(), local_user_id)
         .await
         .ok()?;
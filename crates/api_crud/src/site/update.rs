@@ -37,6 +37,7 @@ use lemmy_utils::{
     },
   },
 };
+use url::Url;
 
 #[async_trait::async_trait(?Send)]
 impl PerformCrud for EditSite {
@@ -94,6 +95,14 @@ impl PerformCrud for EditSite {
       .captcha_enabled(data.captcha_enabled)
       .captcha_difficulty(data.captcha_difficulty.clone())
       .reports_email_admins(data.reports_email_admins)
+      .image_upload_max_bytes(image_upload_limit_overwrite(data.image_upload_max_bytes))
+      .image_upload_max_dimension(image_upload_limit_overwrite(
+        data.image_upload_max_dimension,
+      ))
+      .image_upload_allowed_mime_types(diesel_option_overwrite(
+        &data.image_upload_allowed_mime_types,
+      ))
+      .image_upload_classifier_url(diesel_option_overwrite(&data.image_upload_classifier_url))
       .build();
 
     let update_local_site = LocalSite::update(context.pool(), &local_site_form)
@@ -206,6 +215,18 @@ fn validate_update_payload(local_site: &LocalSite, edit_site: &EditSite) -> Lemm
   // Ensure that the sidebar has fewer than the max num characters...
   is_valid_body_field(&edit_site.sidebar, false)?;
 
+  // Negative limits make no sense, and 0 removes the limit
+  if edit_site.image_upload_max_bytes.unwrap_or(0) < 0
+    || edit_site.image_upload_max_dimension.unwrap_or(0) < 0
+  {
+    return Err(LemmyError::from_message("invalid_image_upload_limit"));
+  }
+  if let Some(classifier_url) = &edit_site.image_upload_classifier_url {
+    if !classifier_url.is_empty() {
+      Url::parse(classifier_url).map_err(|e| LemmyError::from_error_message(e, "invalid_url"))?;
+    }
+  }
+
   application_question_check(
     &local_site.application_question,
     &edit_site.application_question,
@@ -215,6 +236,11 @@ fn validate_update_payload(local_site: &LocalSite, edit_site: &EditSite) -> Lemm
   )
 }
 
+/// Like `diesel_option_overwrite`, but a limit of 0 is stored as null.
+fn image_upload_limit_overwrite(limit: Option<i32>) -> Option<Option<i32>> {
+  limit.map(|l| if l > 0 { Some(l) } else { None })
+}
+
 #[cfg(test)]
 mod tests {
   use crate::site::update::validate_update_payload;
@@ -518,6 +544,10 @@ mod tests {
       updated: None,
       registration_mode: site_registration_mode,
       reports_email_admins: false,
+      image_upload_max_bytes: None,
+      image_upload_max_dimension: None,
+      image_upload_allowed_mime_types: None,
+      image_upload_classifier_url: None,
     }
   }
 
@@ -576,6 +606,10 @@ mod tests {
       taglines: None,
       registration_mode: site_registration_mode,
       reports_email_admins: None,
+      image_upload_max_bytes: None,
+      image_upload_max_dimension: None,
+      image_upload_allowed_mime_types: None,
+      image_upload_classifier_url: None,
       auth: Default::default(),
     }
   }
@@ -7,6 +7,7 @@ use lemmy_api_common::{
   utils::{
     generate_inbox_url,
     generate_local_apub_endpoint,
+    generate_login_token,
     generate_shared_inbox_url,
     honeypot_check,
     local_site_to_slur_regex,
@@ -29,7 +30,6 @@ use lemmy_db_schema::{
 };
 use lemmy_db_views::structs::{LocalUserView, SiteView};
 use lemmy_utils::{
-  claims::Claims,
   error::LemmyError,
   utils::{
     slurs::{check_slurs, check_slurs_opt},
@@ -171,14 +171,7 @@ impl PerformCrud for Register {
     if !local_site.site_setup
       || (!require_registration_application && !local_site.require_email_verification)
     {
-      login_response.jwt = Some(
-        Claims::jwt(
-          inserted_local_user.id.0,
-          &context.secret().jwt_secret,
-          &context.settings().hostname,
-        )?
-        .into(),
-      );
+      login_response.jwt = Some(generate_login_token(inserted_local_user.id, None, context).await?);
     } else {
       if local_site.require_email_verification {
         let local_user_view = LocalUserView {
@@ -0,0 +1,219 @@
+use actix_web::{
+  http::header::{ContentDisposition, DispositionParam, DispositionType},
+  web::{Bytes, Data, Query},
+  HttpResponse,
+};
+use futures::stream;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::ExportArchive,
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::{
+  source::{local_image::LocalImage, local_user::LocalUser, person::Person},
+  utils::FETCH_LIMIT_MAX,
+  CommentSortType,
+  SortType,
+};
+use lemmy_db_views::{
+  comment_view::CommentQuery,
+  post_view::PostQuery,
+  private_message_view::PrivateMessageQuery,
+  structs::{CommentView, LocalUserView, PostView, PrivateMessageView},
+};
+use lemmy_utils::error::LemmyError;
+use serde::Serialize;
+
+/// A single line of the archive.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ArchiveItem<'a> {
+  Profile(&'a Person),
+  Post(&'a PostView),
+  Comment(&'a CommentView),
+  PrivateMessage(&'a PrivateMessageView),
+  Image {
+    url: String,
+    #[serde(flatten)]
+    image: &'a LocalImage,
+  },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Section {
+  Profile,
+  Posts,
+  Comments,
+  PrivateMessages,
+  Images,
+}
+
+impl Section {
+  fn next(self) -> Option<Section> {
+    match self {
+      Section::Profile => Some(Section::Posts),
+      Section::Posts => Some(Section::Comments),
+      Section::Comments => Some(Section::PrivateMessages),
+      Section::PrivateMessages => Some(Section::Images),
+      Section::Images => None,
+    }
+  }
+}
+
+/// Streams a JSON-lines archive of the user's profile, posts, comments, private messages and
+/// uploaded images.
+///
+/// Items are read one page at a time, so that the archive of large accounts is never held in
+/// memory completely.
+#[tracing::instrument(skip(context))]
+pub async fn export_archive(
+  data: Query<ExportArchive>,
+  context: Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let filename = format!("lemmy_archive_{}.jsonl", local_user_view.person.name);
+
+  let archive = ArchiveWriter {
+    context,
+    local_user_view,
+    section: Some(Section::Profile),
+    page: 1,
+  };
+  let body = stream::unfold(archive, |mut archive| async move {
+    match archive.next_chunk().await {
+      Ok(Some(chunk)) => Some((Ok(Bytes::from(chunk)), archive)),
+      Ok(None) => None,
+      Err(e) => {
+        // Abort the download, otherwise the user would get an incomplete archive without notice
+        archive.section = None;
+        Some((Err(actix_web::Error::from(e)), archive))
+      }
+    }
+  });
+
+  Ok(
+    HttpResponse::Ok()
+      .content_type("application/x-ndjson")
+      .insert_header(ContentDisposition {
+        disposition: DispositionType::Attachment,
+        parameters: vec![DispositionParam::Filename(filename)],
+      })
+      .streaming(body),
+  )
+}
+
+struct ArchiveWriter {
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+  section: Option<Section>,
+  page: i64,
+}
+
+impl ArchiveWriter {
+  /// Returns the lines for the next page of items, or None once the archive is complete.
+  async fn next_chunk(&mut self) -> Result<Option<Vec<u8>>, LemmyError> {
+    while let Some(section) = self.section {
+      let mut lines = vec![];
+      let count = self.write_page(section, &mut lines).await?;
+
+      if section == Section::Profile || count < FETCH_LIMIT_MAX as usize {
+        self.section = section.next();
+        self.page = 1;
+      } else {
+        self.page += 1;
+      }
+      if !lines.is_empty() {
+        return Ok(Some(lines));
+      }
+    }
+    Ok(None)
+  }
+
+  /// Writes the current page of the given section, and returns the number of items on it.
+  async fn write_page(&self, section: Section, lines: &mut Vec<u8>) -> Result<usize, LemmyError> {
+    let pool = self.context.pool();
+    let person = &self.local_user_view.person;
+    // The archive should contain everything, regardless of the content filters in the user's
+    // own settings. Blocks and keyword filters don't apply to the user's own posts.
+    let local_user = &self.local_user_view.local_user;
+    let local_user = LocalUser {
+      show_nsfw: true,
+      show_bot_accounts: true,
+      show_read_posts: true,
+      ..local_user.clone()
+    };
+
+    match section {
+      Section::Profile => {
+        write_line(lines, &ArchiveItem::Profile(person))?;
+        Ok(1)
+      }
+      Section::Posts => {
+        let posts = PostQuery::builder()
+          .pool(pool)
+          .sort(Some(SortType::Old))
+          .creator_id(Some(person.id))
+          .local_user(Some(&local_user))
+          .is_mod_or_admin(Some(true))
+          .page(Some(self.page))
+          .limit(Some(FETCH_LIMIT_MAX))
+          .build()
+          .list()
+          .await?;
+        for post_view in &posts {
+          write_line(lines, &ArchiveItem::Post(post_view))?;
+        }
+        Ok(posts.len())
+      }
+      Section::Comments => {
+        let comments = CommentQuery::builder()
+          .pool(pool)
+          .sort(Some(CommentSortType::Old))
+          .creator_id(Some(person.id))
+          .page(Some(self.page))
+          .limit(Some(FETCH_LIMIT_MAX))
+          .build()
+          .list()
+          .await?;
+        for comment_view in &comments {
+          write_line(lines, &ArchiveItem::Comment(comment_view))?;
+        }
+        Ok(comments.len())
+      }
+      Section::PrivateMessages => {
+        let messages = PrivateMessageQuery::builder()
+          .pool(pool)
+          .recipient_id(person.id)
+          .page(Some(self.page))
+          .limit(Some(FETCH_LIMIT_MAX))
+          .build()
+          .list()
+          .await?;
+        for message_view in &messages {
+          write_line(lines, &ArchiveItem::PrivateMessage(message_view))?;
+        }
+        Ok(messages.len())
+      }
+      Section::Images => {
+        let protocol_and_hostname = self.context.settings().get_protocol_and_hostname();
+        let images =
+          LocalImage::list_for_person(pool, person.id, Some(self.page), Some(FETCH_LIMIT_MAX))
+            .await?;
+        for image in &images {
+          let url = format!(
+            "{protocol_and_hostname}/pictrs/image/{}",
+            image.pictrs_alias
+          );
+          write_line(lines, &ArchiveItem::Image { url, image })?;
+        }
+        Ok(images.len())
+      }
+    }
+  }
+}
+
+fn write_line(lines: &mut Vec<u8>, item: &ArchiveItem) -> Result<(), LemmyError> {
+  serde_json::to_writer(&mut *lines, item)?;
+  lines.push(b'\n');
+  Ok(())
+}
@@ -1,2 +1,4 @@
 mod create;
 mod delete;
+pub mod export_archive;
+pub mod oauth;
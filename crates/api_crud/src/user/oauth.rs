@@ -0,0 +1,779 @@
+use activitypub_federation::http_signatures::generate_actor_keypair;
+use actix_web::{
+  cookie::{time::Duration, Cookie, SameSite},
+  http::header::LOCATION,
+  web::{Data, Path, Query},
+  HttpRequest,
+  HttpResponse,
+};
+use anyhow::anyhow;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use lemmy_api_common::{
+  context::LemmyContext,
+  utils::{
+    check_registration_application,
+    check_user_valid,
+    generate_inbox_url,
+    generate_local_apub_endpoint,
+    generate_login_token,
+    generate_shared_inbox_url,
+    local_site_to_slur_regex,
+    send_new_applicant_email_to_admins,
+    EndpointType,
+  },
+};
+use lemmy_db_schema::{
+  source::{
+    local_user::{LocalUser, LocalUserInsertForm},
+    oauth_account::{OAuthAccount, OAuthAccountInsertForm},
+    person::{Person, PersonInsertForm},
+    registration_application::{RegistrationApplication, RegistrationApplicationInsertForm},
+  },
+  traits::Crud,
+  RegistrationMode,
+};
+use lemmy_db_views::structs::{LocalUserView, SiteView};
+use lemmy_utils::{
+  error::LemmyError,
+  settings::structs::{OidcProviderConfig, Settings},
+  utils::{slurs::check_slurs, validation::is_valid_actor_name},
+  REQWEST_TIMEOUT,
+};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+/// Cookie which holds the random `state` parameter, to protect the callback against csrf.
+const OAUTH_STATE_COOKIE: &str = "oauth_state";
+/// Cookie which holds the `nonce` that the id token must contain, so that it can't be replayed.
+const OAUTH_NONCE_COOKIE: &str = "oauth_nonce";
+/// Cookie which holds the PKCE code verifier, so that only this browser can redeem the code.
+const OAUTH_CODE_VERIFIER_COOKIE: &str = "oauth_code_verifier";
+const OAUTH_COOKIE_PATH: &str = "/api/v3/oauth";
+
+#[derive(Deserialize)]
+pub struct OAuthCallback {
+  code: String,
+  state: String,
+}
+
+/// The relevant parts of the provider's `.well-known/openid-configuration`.
+#[derive(Deserialize)]
+struct ProviderMetadata {
+  issuer: String,
+  authorization_endpoint: Url,
+  token_endpoint: Url,
+  userinfo_endpoint: Url,
+  jwks_uri: Url,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+  access_token: String,
+  id_token: String,
+}
+
+/// The claims of the id token which aren't checked by [`Validation`] already.
+#[derive(Deserialize)]
+struct IdTokenClaims {
+  sub: String,
+  nonce: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JsonWebKeySet {
+  keys: Vec<JsonWebKey>,
+}
+
+/// A public key of the provider, only RSA keys are supported.
+#[derive(Deserialize)]
+struct JsonWebKey {
+  kty: String,
+  kid: Option<String>,
+  n: Option<String>,
+  e: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserInfo {
+  sub: String,
+  email: Option<String>,
+  #[serde(default)]
+  email_verified: bool,
+  preferred_username: Option<String>,
+}
+
+/// Redirects the browser to the login page of the given provider.
+#[tracing::instrument(skip(context))]
+pub async fn oauth_authorize(
+  provider_name: Path<String>,
+  context: Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  let provider = context.settings().oidc_provider(&provider_name)?;
+  let metadata = fetch_provider_metadata(context.client(), provider)
+    .await
+    .map_err(|e| e.with_message("oauth_login_failed"))?;
+
+  let state = random_token();
+  let nonce = random_token();
+  let code_verifier = format!("{}{}", random_token(), random_token());
+  let mut url = metadata.authorization_endpoint;
+  url
+    .query_pairs_mut()
+    .append_pair("response_type", "code")
+    .append_pair("client_id", &provider.client_id)
+    .append_pair(
+      "redirect_uri",
+      redirect_uri(context.settings(), provider).as_str(),
+    )
+    .append_pair("scope", &provider.scopes.join(" "))
+    .append_pair("state", &state)
+    .append_pair("nonce", &nonce)
+    .append_pair("code_challenge", &pkce_challenge(&code_verifier))
+    .append_pair("code_challenge_method", "S256");
+
+  let settings = context.settings();
+  Ok(
+    HttpResponse::Found()
+      .insert_header((LOCATION, url.as_str()))
+      .cookie(login_attempt_cookie(OAUTH_STATE_COOKIE, state, settings))
+      .cookie(login_attempt_cookie(OAUTH_NONCE_COOKIE, nonce, settings))
+      .cookie(login_attempt_cookie(
+        OAUTH_CODE_VERIFIER_COOKIE,
+        code_verifier,
+        settings,
+      ))
+      .finish(),
+  )
+}
+
+/// The provider redirects here after a successful login. The external account is linked to a
+/// local user (which is created first if necessary), and a login token is set as cookie.
+#[tracing::instrument(skip(params, req, context))]
+pub async fn oauth_callback(
+  provider_name: Path<String>,
+  params: Query<OAuthCallback>,
+  req: HttpRequest,
+  context: Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  let provider = context.settings().oidc_provider(&provider_name)?;
+
+  let cookie_value = |name| req.cookie(name).map(|c| c.value().to_string());
+  if cookie_value(OAUTH_STATE_COOKIE).as_deref() != Some(params.state.as_str()) {
+    return Err(LemmyError::from_message("oauth_invalid_state"));
+  }
+  let (nonce, code_verifier) = match (
+    cookie_value(OAUTH_NONCE_COOKIE),
+    cookie_value(OAUTH_CODE_VERIFIER_COOKIE),
+  ) {
+    (Some(nonce), Some(code_verifier)) => (nonce, code_verifier),
+    _ => return Err(LemmyError::from_message("oauth_invalid_state")),
+  };
+
+  let user_info = fetch_user_info(
+    context.client(),
+    provider,
+    &params.code,
+    &LoginAttempt {
+      nonce: &nonce,
+      code_verifier: &code_verifier,
+      redirect_uri: &redirect_uri(context.settings(), provider),
+    },
+  )
+  .await
+  .map_err(|e| e.with_message("oauth_login_failed"))?;
+
+  let site_view = SiteView::read_local(context.pool()).await?;
+  let local_user_view =
+    match OAuthAccount::read_by_subject(context.pool(), &provider.name, &user_info.sub).await {
+      Ok(oauth_account) => LocalUserView::read(context.pool(), oauth_account.local_user_id).await?,
+      Err(_) => link_or_register(provider, &user_info, &site_view, &context).await?,
+    };
+
+  // Same checks as for a password login. Two factor authentication is left to the provider.
+  check_user_valid(
+    local_user_view.person.banned,
+    local_user_view.person.ban_expires,
+    local_user_view.person.deleted,
+  )?;
+  if !local_user_view.person.admin
+    && site_view.local_site.require_email_verification
+    && !local_user_view.local_user.email_verified
+  {
+    return Err(LemmyError::from_message("email_not_verified"));
+  }
+  check_registration_application(&local_user_view, &site_view.local_site, context.pool()).await?;
+
+  let jwt = generate_login_token(local_user_view.local_user.id, Some(&req), &context).await?;
+  let jwt_cookie = Cookie::build("jwt", jwt.into_inner())
+    .path("/")
+    .secure(context.settings().tls_enabled)
+    .same_site(SameSite::Lax)
+    .finish();
+
+  Ok(
+    HttpResponse::Found()
+      .insert_header((LOCATION, "/"))
+      .cookie(jwt_cookie)
+      .cookie(login_attempt_cookie_removal(OAUTH_STATE_COOKIE))
+      .cookie(login_attempt_cookie_removal(OAUTH_NONCE_COOKIE))
+      .cookie(login_attempt_cookie_removal(OAUTH_CODE_VERIFIER_COOKIE))
+      .finish(),
+  )
+}
+
+/// Links the external account to the local user with the same verified email address if the
+/// provider is configured for it, or registers a new user if the provider allows it.
+async fn link_or_register(
+  provider: &OidcProviderConfig,
+  user_info: &UserInfo,
+  site_view: &SiteView,
+  context: &Data<LemmyContext>,
+) -> Result<LocalUserView, LemmyError> {
+  let local_site = &site_view.local_site;
+  let email = verified_email(user_info);
+
+  if let Some(email) = &email {
+    if let Ok(local_user_view) = LocalUserView::find_by_email(context.pool(), email).await {
+      if !may_link_by_email(provider, user_info) {
+        return Err(LemmyError::from_message("email_already_exists"));
+      }
+      link_account(provider, user_info, &local_user_view, context).await?;
+      return Ok(local_user_view);
+    }
+  }
+
+  if !provider.auto_register {
+    return Err(LemmyError::from_message("oauth_account_not_linked"));
+  }
+  if local_site.registration_mode == RegistrationMode::Closed {
+    return Err(LemmyError::from_message("registration_closed"));
+  }
+  if local_site.require_email_verification && email.is_none() {
+    return Err(LemmyError::from_message("email_required"));
+  }
+  let require_registration_application =
+    local_site.registration_mode == RegistrationMode::RequireApplication;
+
+  let username = username_from_user_info(user_info)
+    .ok_or_else(|| LemmyError::from_message("invalid_username"))?;
+  let slur_regex = local_site_to_slur_regex(local_site);
+  check_slurs(&username, &slur_regex)?;
+  is_valid_actor_name(&username, local_site.actor_name_max_length as usize)?;
+
+  let actor_keypair = generate_actor_keypair()?;
+  let actor_id = generate_local_apub_endpoint(
+    EndpointType::Person,
+    &username,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+
+  let person_form = PersonInsertForm::builder()
+    .name(username.clone())
+    .actor_id(Some(actor_id.clone()))
+    .private_key(Some(actor_keypair.private_key))
+    .public_key(actor_keypair.public_key)
+    .inbox_url(Some(generate_inbox_url(&actor_id)?))
+    .shared_inbox_url(Some(generate_shared_inbox_url(&actor_id)?))
+    .admin(Some(false))
+    .instance_id(site_view.site.instance_id)
+    .build();
+  let inserted_person = Person::create(context.pool(), &person_form)
+    .await
+    .map_err(|e| LemmyError::from_error_message(e, "user_already_exists"))?;
+
+  let local_user_form = LocalUserInsertForm::builder()
+    .person_id(inserted_person.id)
+    // Only a verified email is kept, see above
+    .email_verified(Some(email.is_some()))
+    .email(email)
+    // Random password, until the user sets one with a password reset
+    .password_encrypted(uuid::Uuid::new_v4().to_string())
+    .accepted_application(Some(!require_registration_application))
+    .build();
+  let inserted_local_user = match LocalUser::create(context.pool(), &local_user_form).await {
+    Ok(local_user) => local_user,
+    Err(e) => {
+      // Otherwise the name stays taken by a person who can't log in
+      Person::delete(context.pool(), inserted_person.id).await?;
+      return Err(LemmyError::from_error_message(e, "user_already_exists"));
+    }
+  };
+
+  if require_registration_application {
+    let form = RegistrationApplicationInsertForm {
+      local_user_id: inserted_local_user.id,
+      answer: format!("Registered via {}", provider.name),
+    };
+    RegistrationApplication::create(context.pool(), &form).await?;
+
+    if local_site.application_email_admins {
+      send_new_applicant_email_to_admins(&username, context.pool(), context.settings()).await?;
+    }
+  }
+
+  let local_user_view = LocalUserView::read(context.pool(), inserted_local_user.id).await?;
+  link_account(provider, user_info, &local_user_view, context).await?;
+  Ok(local_user_view)
+}
+
+async fn link_account(
+  provider: &OidcProviderConfig,
+  user_info: &UserInfo,
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let form = OAuthAccountInsertForm {
+    local_user_id: local_user_view.local_user.id,
+    provider: provider.name.clone(),
+    subject: user_info.sub.clone(),
+  };
+  OAuthAccount::create(context.pool(), &form).await?;
+  Ok(())
+}
+
+/// The email of the external account, if the provider verified it. Unverified emails could be
+/// used to take over existing accounts.
+fn verified_email(user_info: &UserInfo) -> Option<String> {
+  user_info
+    .email
+    .as_deref()
+    .filter(|_| user_info.email_verified)
+    .map(str::to_lowercase)
+}
+
+/// Whether the external account may be linked to an existing local user with the same email.
+fn may_link_by_email(provider: &OidcProviderConfig, user_info: &UserInfo) -> bool {
+  provider.link_by_email && verified_email(user_info).is_some()
+}
+
+/// Uses the preferred username, or the local part of the email, with all characters removed which
+/// are not allowed in actor names.
+fn username_from_user_info(user_info: &UserInfo) -> Option<String> {
+  let name = user_info
+    .preferred_username
+    .as_deref()
+    .or_else(|| user_info.email.as_deref().and_then(|e| e.split('@').next()))?;
+  let name = name
+    .chars()
+    .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+    .collect::<String>();
+  if name.is_empty() {
+    None
+  } else {
+    Some(name)
+  }
+}
+
+fn random_token() -> String {
+  uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// The PKCE code challenge for the `S256` method.
+fn pkce_challenge(code_verifier: &str) -> String {
+  base64::encode_config(Sha256::digest(code_verifier), base64::URL_SAFE_NO_PAD)
+}
+
+/// Holds a random value of the login attempt until the provider redirects back. Needs to be lax,
+/// so that it is sent along with that redirect.
+fn login_attempt_cookie(name: &'static str, value: String, settings: &Settings) -> Cookie<'static> {
+  Cookie::build(name, value)
+    .path(OAUTH_COOKIE_PATH)
+    .http_only(true)
+    .secure(settings.tls_enabled)
+    .same_site(SameSite::Lax)
+    .max_age(Duration::minutes(10))
+    .finish()
+}
+
+fn login_attempt_cookie_removal(name: &'static str) -> Cookie<'static> {
+  let mut removal = Cookie::new(name, "");
+  removal.set_path(OAUTH_COOKIE_PATH);
+  removal.make_removal();
+  removal
+}
+
+fn redirect_uri(settings: &Settings, provider: &OidcProviderConfig) -> String {
+  format!(
+    "{}{}/{}/callback",
+    settings.get_protocol_and_hostname(),
+    OAUTH_COOKIE_PATH,
+    provider.name
+  )
+}
+
+#[tracing::instrument(skip_all)]
+async fn fetch_provider_metadata(
+  client: &ClientWithMiddleware,
+  provider: &OidcProviderConfig,
+) -> Result<ProviderMetadata, LemmyError> {
+  let url = format!(
+    "{}/.well-known/openid-configuration",
+    provider.issuer.as_str().trim_end_matches('/')
+  );
+  let metadata = client
+    .get(url)
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await?
+    .error_for_status()?
+    .json::<ProviderMetadata>()
+    .await?;
+  if metadata.issuer.trim_end_matches('/') != provider.issuer.as_str().trim_end_matches('/') {
+    return Err(anyhow!("Provider metadata is for another issuer").into());
+  }
+  Ok(metadata)
+}
+
+/// The values which were generated for the login attempt, and sent to the provider.
+struct LoginAttempt<'a> {
+  nonce: &'a str,
+  code_verifier: &'a str,
+  redirect_uri: &'a str,
+}
+
+/// Exchanges the authorization code for an access token and an id token. The id token identifies
+/// the user, the access token is used to retrieve further information about them.
+#[tracing::instrument(skip_all)]
+async fn fetch_user_info(
+  client: &ClientWithMiddleware,
+  provider: &OidcProviderConfig,
+  code: &str,
+  attempt: &LoginAttempt<'_>,
+) -> Result<UserInfo, LemmyError> {
+  let metadata = fetch_provider_metadata(client, provider).await?;
+
+  let token = client
+    .post(metadata.token_endpoint.as_str())
+    .form(&[
+      ("grant_type", "authorization_code"),
+      ("code", code),
+      ("redirect_uri", attempt.redirect_uri),
+      ("client_id", &provider.client_id),
+      ("client_secret", &provider.client_secret),
+      ("code_verifier", attempt.code_verifier),
+    ])
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await?
+    .error_for_status()?
+    .json::<TokenResponse>()
+    .await?;
+  let claims = verify_id_token(client, provider, &metadata, &token.id_token, attempt.nonce).await?;
+
+  let user_info = client
+    .get(metadata.userinfo_endpoint.as_str())
+    .bearer_auth(token.access_token)
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await?
+    .error_for_status()?
+    .json::<UserInfo>()
+    .await?;
+  if user_info.sub != claims.sub {
+    return Err(anyhow!("User info is for another subject than the id token").into());
+  }
+  Ok(user_info)
+}
+
+/// Checks the signature of the id token with the keys of the provider, and that it was issued by
+/// the provider for this client and login attempt.
+async fn verify_id_token(
+  client: &ClientWithMiddleware,
+  provider: &OidcProviderConfig,
+  metadata: &ProviderMetadata,
+  id_token: &str,
+  nonce: &str,
+) -> Result<IdTokenClaims, LemmyError> {
+  let header = decode_header(id_token)?;
+  if !matches!(
+    header.alg,
+    Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512
+  ) {
+    return Err(anyhow!("Unsupported id token algorithm {:?}", header.alg).into());
+  }
+  let key_set = client
+    .get(metadata.jwks_uri.as_str())
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await?
+    .error_for_status()?
+    .json::<JsonWebKeySet>()
+    .await?;
+  let key = key_set
+    .keys
+    .iter()
+    .filter(|k| k.kty == "RSA")
+    .find(|k| header.kid.is_none() || k.kid == header.kid)
+    .ok_or_else(|| anyhow!("No key of the provider matches the id token"))?;
+  let decoding_key = match (&key.n, &key.e) {
+    (Some(n), Some(e)) => DecodingKey::from_rsa_components(n, e)?,
+    _ => return Err(anyhow!("Invalid key of the provider").into()),
+  };
+
+  let mut validation = Validation::new(header.alg);
+  validation.set_issuer(&[&metadata.issuer]);
+  validation.set_audience(&[&provider.client_id]);
+  let claims = decode::<IdTokenClaims>(id_token, &decoding_key, &validation)?.claims;
+  if claims.nonce.as_deref() != Some(nonce) {
+    return Err(anyhow!("Id token has the wrong nonce").into());
+  }
+  Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    fetch_user_info,
+    may_link_by_email,
+    pkce_challenge,
+    username_from_user_info,
+    LoginAttempt,
+    UserInfo,
+  };
+  use activitypub_federation::http_signatures::generate_actor_keypair;
+  use actix_web::{http::header::AUTHORIZATION, web, App, HttpRequest, HttpResponse, HttpServer};
+  use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+  use lemmy_utils::settings::structs::OidcProviderConfig;
+  use openssl::pkey::PKey;
+  use serde_json::json;
+  use std::collections::HashMap;
+  use url::Url;
+
+  const NONCE: &str = "a8e1c3f2";
+  const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+  /// Serves the endpoints of an OpenID Connect provider which are used during the callback. The
+  /// id token is signed with a new key, and issued for the nonce above.
+  fn mock_issuer() -> Url {
+    let keypair = generate_actor_keypair().unwrap();
+    let rsa = PKey::private_key_from_pem(keypair.private_key.as_bytes())
+      .unwrap()
+      .rsa()
+      .unwrap();
+    let encode_component = |c: Vec<u8>| base64::encode_config(c, base64::URL_SAFE_NO_PAD);
+    let jwks = json!({
+      "keys": [{
+        "kty": "RSA",
+        "kid": "key1",
+        "use": "sig",
+        "n": encode_component(rsa.n().to_vec()),
+        "e": encode_component(rsa.e().to_vec()),
+      }]
+    });
+    let private_key = keypair.private_key;
+
+    let server = HttpServer::new(move || {
+      let jwks = jwks.clone();
+      let private_key = private_key.clone();
+      App::new()
+        .route(
+          "/.well-known/openid-configuration",
+          web::get().to(|req: HttpRequest| async move {
+            let base = format!("http://{}", req.connection_info().host());
+            HttpResponse::Ok().json(json!({
+              "issuer": base,
+              "authorization_endpoint": format!("{base}/authorize"),
+              "token_endpoint": format!("{base}/token"),
+              "userinfo_endpoint": format!("{base}/userinfo"),
+              "jwks_uri": format!("{base}/jwks"),
+            }))
+          }),
+        )
+        .route(
+          "/jwks",
+          web::get().to(move || {
+            let jwks = jwks.clone();
+            async move { HttpResponse::Ok().json(jwks) }
+          }),
+        )
+        .route(
+          "/token",
+          web::post().to(
+            move |req: HttpRequest, form: web::Form<HashMap<String, String>>| {
+              let private_key = private_key.clone();
+              async move {
+                let field = |name: &str| form.get(name).map(String::as_str);
+                let valid = field("grant_type") == Some("authorization_code")
+                  && field("code") == Some("auth_code")
+                  && field("client_secret") == Some("secret")
+                  && field("code_verifier") == Some(CODE_VERIFIER);
+                if !valid {
+                  return HttpResponse::BadRequest().finish();
+                }
+                let claims = json!({
+                  "iss": format!("http://{}", req.connection_info().host()),
+                  "sub": "248289761001",
+                  "aud": "lemmy",
+                  "exp": chrono::Utc::now().timestamp() + 600,
+                  "nonce": NONCE,
+                });
+                let header = Header {
+                  kid: Some("key1".to_string()),
+                  ..Header::new(Algorithm::RS256)
+                };
+                let key = EncodingKey::from_rsa_pem(private_key.as_bytes()).unwrap();
+                HttpResponse::Ok().json(json!({
+                  "access_token": "access_token",
+                  "token_type": "Bearer",
+                  "id_token": encode(&header, &claims, &key).unwrap(),
+                }))
+              }
+            },
+          ),
+        )
+        .route(
+          "/userinfo",
+          web::get().to(|req: HttpRequest| async move {
+            match req.headers().get(AUTHORIZATION) {
+              Some(h) if h == "Bearer access_token" => HttpResponse::Ok().json(json!({
+                "sub": "248289761001",
+                "email": "Jane.Doe@example.com",
+                "email_verified": true,
+                "preferred_username": "jane.doe",
+              })),
+              _ => HttpResponse::Unauthorized().finish(),
+            }
+          }),
+        )
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))
+    .unwrap();
+    let addr = server.addrs()[0];
+    actix_web::rt::spawn(server.run());
+    Url::parse(&format!("http://{addr}")).unwrap()
+  }
+
+  fn provider(issuer: Url, client_id: &str, client_secret: &str) -> OidcProviderConfig {
+    OidcProviderConfig {
+      name: "mock".to_string(),
+      issuer,
+      client_id: client_id.to_string(),
+      client_secret: client_secret.to_string(),
+      ..Default::default()
+    }
+  }
+
+  #[actix_web::test]
+  async fn test_fetch_user_info() {
+    let issuer = mock_issuer();
+    let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build();
+    let attempt = LoginAttempt {
+      nonce: NONCE,
+      code_verifier: CODE_VERIFIER,
+      redirect_uri: "https://lemmy.tld/api/v3/oauth/mock/callback",
+    };
+
+    let user_info = fetch_user_info(
+      &client,
+      &provider(issuer.clone(), "lemmy", "secret"),
+      "auth_code",
+      &attempt,
+    )
+    .await
+    .unwrap();
+    assert_eq!("248289761001", user_info.sub);
+    assert_eq!(Some("Jane.Doe@example.com".to_string()), user_info.email);
+    assert!(user_info.email_verified);
+
+    let wrong_secret = fetch_user_info(
+      &client,
+      &provider(issuer.clone(), "lemmy", "wrong"),
+      "auth_code",
+      &attempt,
+    )
+    .await;
+    assert!(wrong_secret.is_err());
+
+    // The code can't be redeemed without the verifier of the login attempt
+    let wrong_verifier = LoginAttempt {
+      code_verifier: "other_verifier",
+      ..attempt
+    };
+    let wrong_verifier = fetch_user_info(
+      &client,
+      &provider(issuer.clone(), "lemmy", "secret"),
+      "auth_code",
+      &wrong_verifier,
+    )
+    .await;
+    assert!(wrong_verifier.is_err());
+
+    // Id tokens of other login attempts or other clients are rejected
+    let wrong_nonce = LoginAttempt {
+      nonce: "other_nonce",
+      ..attempt
+    };
+    let wrong_nonce = fetch_user_info(
+      &client,
+      &provider(issuer.clone(), "lemmy", "secret"),
+      "auth_code",
+      &wrong_nonce,
+    )
+    .await;
+    assert!(wrong_nonce.is_err());
+    let wrong_audience = fetch_user_info(
+      &client,
+      &provider(issuer, "other_client", "secret"),
+      "auth_code",
+      &attempt,
+    )
+    .await;
+    assert!(wrong_audience.is_err());
+  }
+
+  #[test]
+  fn test_pkce_challenge() {
+    // Example from RFC 7636, appendix B
+    assert_eq!(
+      "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM",
+      pkce_challenge(CODE_VERIFIER)
+    );
+  }
+
+  #[test]
+  fn test_may_link_by_email() {
+    let issuer = Url::parse("https://auth.example.com").unwrap();
+    let mut provider = provider(issuer, "lemmy", "secret");
+    let mut user_info = UserInfo {
+      sub: "1".to_string(),
+      email: Some("jane.doe@example.com".to_string()),
+      email_verified: true,
+      preferred_username: None,
+    };
+    // Linking by email is opt-in
+    assert!(!may_link_by_email(&provider, &user_info));
+
+    provider.link_by_email = true;
+    assert!(may_link_by_email(&provider, &user_info));
+
+    user_info.email_verified = false;
+    assert!(!may_link_by_email(&provider, &user_info));
+  }
+
+  #[test]
+  fn test_username_from_user_info() {
+    let mut user_info = UserInfo {
+      sub: "1".to_string(),
+      email: Some("jane.doe@example.com".to_string()),
+      email_verified: false,
+      preferred_username: Some("jane.doe".to_string()),
+    };
+    assert_eq!(
+      Some("janedoe".to_string()),
+      username_from_user_info(&user_info)
+    );
+
+    user_info.preferred_username = None;
+    user_info.email = Some("jane_doe@example.com".to_string());
+    assert_eq!(
+      Some("jane_doe".to_string()),
+      username_from_user_info(&user_info)
+    );
+
+    user_info.email = None;
+    assert_eq!(None, username_from_user_info(&user_info));
+  }
+}
@@ -9,6 +9,7 @@ use crate::{
   },
   activity_lists::AnnouncableActivities,
   insert_activity,
+  objects::{community::ApubCommunity, person::ApubPerson},
   protocol::{
     activities::community::lock_page::{LockPage, LockType, UndoLockPage},
     InCommunity,
@@ -18,7 +19,7 @@ use crate::{
 use activitypub_federation::{
   config::Data,
   kinds::{activity::UndoType, public},
-  traits::ActivityHandler,
+  traits::{ActivityHandler, Actor},
 };
 use lemmy_api_common::{
   context::LemmyContext,
@@ -102,32 +103,32 @@ impl ActivityHandler for UndoLockPage {
   }
 }
 
-#[async_trait::async_trait]
-impl SendActivity for LockPost {
-  type Response = PostResponse;
-
-  async fn send_activity(
-    request: &Self,
-    response: &Self::Response,
+impl LockPage {
+  /// Sends a lock, or an undo of the lock, for the post to its community.
+  #[tracing::instrument(skip_all)]
+  pub(crate) async fn send(
+    post: &Post,
+    actor: &ApubPerson,
+    locked: bool,
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
-    let local_user_view = local_user_view_from_jwt(&request.auth, context).await?;
+    let community: ApubCommunity = Community::read(context.pool(), post.community_id)
+      .await?
+      .into();
     let id = generate_activity_id(
       LockType::Lock,
       &context.settings().get_protocol_and_hostname(),
     )?;
-    let community_id = response.post_view.community.actor_id.clone();
-    let actor = local_user_view.person.actor_id.clone().into();
     let lock = LockPage {
-      actor,
+      actor: actor.id().into(),
       to: vec![public()],
-      object: response.post_view.post.ap_id.clone().into(),
-      cc: vec![community_id.clone().into()],
+      object: post.ap_id.clone().into(),
+      cc: vec![community.id()],
       kind: LockType::Lock,
       id,
-      audience: Some(community_id.into()),
+      audience: Some(community.id().into()),
     };
-    let activity = if request.locked {
+    let activity = if locked {
       AnnouncableActivities::LockPost(lock)
     } else {
       let id = generate_activity_id(
@@ -145,16 +146,26 @@ impl SendActivity for LockPost {
       };
       AnnouncableActivities::UndoLockPost(undo)
     };
-    let community = Community::read(context.pool(), response.post_view.community.id).await?;
-    send_activity_in_community(
-      activity,
+    send_activity_in_community(activity, actor, &community, vec![], true, context).await
+  }
+}
+
+#[async_trait::async_trait]
+impl SendActivity for LockPost {
+  type Response = PostResponse;
+
+  async fn send_activity(
+    request: &Self,
+    response: &Self::Response,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&request.auth, context).await?;
+    LockPage::send(
+      &response.post_view.post,
       &local_user_view.person.into(),
-      &community.into(),
-      vec![],
-      true,
+      request.locked,
       context,
     )
-    .await?;
-    Ok(())
+    .await
   }
 }
@@ -44,10 +44,10 @@ pub(crate) async fn send_activity_in_community(
   // send to user followers
   if !is_mod_action {
     inboxes.append(
-      &mut PersonFollower::list_followers(context.pool(), actor.id)
+      &mut PersonFollower::list_follower_inboxes(context.pool(), actor.id)
         .await?
         .into_iter()
-        .map(|p| ApubPerson(p).shared_inbox_or_inbox())
+        .map(Into::into)
         .collect(),
     );
   }
@@ -8,7 +8,7 @@ use crate::{
   },
   activity_lists::AnnouncableActivities,
   insert_activity,
-  objects::{community::ApubCommunity, person::ApubPerson},
+  objects::{community::ApubCommunity, person::ApubPerson, store_remote_image_links},
   protocol::{activities::community::update::UpdateCommunity, InCommunity},
   SendActivity,
 };
@@ -97,7 +97,16 @@ impl ActivityHandler for UpdateCommunity {
 
     let community_update_form = self.object.into_update_form();
 
-    Community::update(context.pool(), community.id, &community_update_form).await?;
+    let community = Community::update(context.pool(), community.id, &community_update_form).await?;
+    store_remote_image_links(
+      vec![
+        community.icon.clone().map(Into::into),
+        community.banner.clone().map(Into::into),
+      ],
+      &[&community.description],
+      context,
+    )
+    .await?;
     Ok(())
   }
 }
@@ -33,7 +33,7 @@ use lemmy_db_schema::{
   aggregates::structs::CommentAggregates,
   newtypes::PersonId,
   source::{
-    comment::{Comment, CommentLike, CommentLikeForm},
+    comment::{Comment, CommentEdit, CommentLike, CommentLikeForm},
     community::Community,
     person::Person,
     post::Post,
@@ -172,7 +172,7 @@ impl ActivityHandler for CreateOrUpdateNote {
     // send the activity, not the comment author.
     let existing_comment = self.object.id.dereference_local(context).await.ok();
     if let (Some(distinguished), Some(existing_comment)) =
-      (self.object.distinguished, existing_comment)
+      (self.object.distinguished, &existing_comment)
     {
       if distinguished != existing_comment.distinguished {
         let creator = self.actor.dereference(context).await?;
@@ -182,6 +182,9 @@ impl ActivityHandler for CreateOrUpdateNote {
     }
 
     let comment = ApubComment::from_json(self.object, context).await?;
+    if let Some(existing_comment) = existing_comment {
+      CommentEdit::create_for_update(context.pool(), &existing_comment.0, &comment.0).await?;
+    }
 
     // author likes their own comment by default
     let like_form = CommentLikeForm {
@@ -11,7 +11,11 @@ use crate::{
   insert_activity,
   objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
   protocol::{
-    activities::{create_or_update::page::CreateOrUpdatePage, CreateOrUpdateType},
+    activities::{
+      community::{collection_add::CollectionAdd, lock_page::LockPage},
+      create_or_update::page::CreateOrUpdatePage,
+      CreateOrUpdateType,
+    },
     InCommunity,
   },
   SendActivity,
@@ -32,7 +36,7 @@ use lemmy_db_schema::{
   source::{
     community::Community,
     person::Person,
-    post::{Post, PostLike, PostLikeForm},
+    post::{Post, PostEdit, PostLike, PostLikeForm},
   },
   traits::{Crud, Likeable},
 };
@@ -48,13 +52,12 @@ impl SendActivity for CreatePost {
     response: &Self::Response,
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
-    CreateOrUpdatePage::send(
-      &response.post_view.post,
-      response.post_view.creator.id,
-      CreateOrUpdateType::Create,
-      context,
-    )
-    .await
+    let post = &response.post_view.post;
+    // Scheduled posts are sent once they get published
+    if post.scheduled_publish_time.is_some() {
+      return Ok(());
+    }
+    send_new_post(post, context).await
   }
 }
 
@@ -67,6 +70,9 @@ impl SendActivity for EditPost {
     response: &Self::Response,
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
+    if response.post_view.post.scheduled_publish_time.is_some() {
+      return Ok(());
+    }
     CreateOrUpdatePage::send(
       &response.post_view.post,
       response.post_view.creator.id,
@@ -77,6 +83,37 @@ impl SendActivity for EditPost {
   }
 }
 
+/// Sends a newly published post to its community. New posts can't be locked or featured, so if a
+/// moderator did that on creation it is sent as separate activities.
+pub async fn send_new_post(post: &Post, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
+  let unlocked_post = Post {
+    locked: false,
+    ..post.clone()
+  };
+  CreateOrUpdatePage::send(
+    &unlocked_post,
+    post.creator_id,
+    CreateOrUpdateType::Create,
+    context,
+  )
+  .await?;
+
+  if post.locked || post.featured_community {
+    let person: ApubPerson = Person::read(context.pool(), post.creator_id).await?.into();
+    if post.locked {
+      LockPage::send(post, &person, true, context).await?;
+    }
+    if post.featured_community {
+      let community: ApubCommunity = Community::read(context.pool(), post.community_id)
+        .await?
+        .into();
+      let post: ApubPost = post.clone().into();
+      CollectionAdd::send_add_featured_post(&community, &post, &person, context).await?;
+    }
+  }
+  Ok(())
+}
+
 impl CreateOrUpdatePage {
   pub(crate) async fn new(
     post: ApubPost,
@@ -179,7 +216,11 @@ impl ActivityHandler for CreateOrUpdatePage {
   #[tracing::instrument(skip_all)]
   async fn receive(self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
     insert_activity(&self.id, &self, false, false, context).await?;
+    let existing_post = self.object.id.dereference_local(context).await.ok();
     let post = ApubPost::from_json(self.object, context).await?;
+    if let Some(existing_post) = existing_post {
+      PostEdit::create_for_update(context.pool(), &existing_post.0, &post.0).await?;
+    }
 
     // author likes their own post by default
     let like_form = PostLikeForm {
@@ -70,9 +70,9 @@ impl CreateOrUpdateChatMessage {
     kind: CreateOrUpdateType,
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
-    let recipient_id = private_message.recipient_id;
     let sender: ApubPerson = Person::read(context.pool(), sender_id).await?.into();
-    let recipient: ApubPerson = Person::read(context.pool(), recipient_id).await?.into();
+    let private_message = ApubPrivateMessage(private_message.clone());
+    let recipients = private_message.recipients(context).await?;
 
     let id = generate_activity_id(
       kind.clone(),
@@ -81,13 +81,14 @@ impl CreateOrUpdateChatMessage {
     let create_or_update = CreateOrUpdateChatMessage {
       id: id.clone(),
       actor: sender.id().into(),
-      to: [recipient.id().into()],
-      object: ApubPrivateMessage(private_message.clone())
-        .into_json(context)
-        .await?,
+      to: recipients.iter().map(|r| r.id().into()).collect(),
+      object: private_message.into_json(context).await?,
       kind,
     };
-    let inbox = vec![recipient.shared_inbox_or_inbox()];
+    let inbox = recipients
+      .iter()
+      .map(|r| r.shared_inbox_or_inbox())
+      .collect();
     send_lemmy_activity(context, create_or_update, &sender, inbox, true).await
   }
 }
@@ -109,7 +110,12 @@ impl ActivityHandler for CreateOrUpdateChatMessage {
   async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
     verify_person(&self.actor, context).await?;
     verify_domains_match(self.actor.inner(), self.object.id.inner())?;
-    verify_domains_match(self.to[0].inner(), self.object.to[0].inner())?;
+    if self.to.len() != self.object.to.len() {
+      return Err(LemmyError::from_message("invalid_recipients"));
+    }
+    for (to, object_to) in self.to.iter().zip(&self.object.to) {
+      verify_domains_match(to.inner(), object_to.inner())?;
+    }
     ApubPrivateMessage::verify(&self.object, self.actor.inner(), context).await?;
     Ok(())
   }
@@ -251,16 +251,24 @@ async fn send_apub_delete_private_message(
   deleted: bool,
   context: &Data<LemmyContext>,
 ) -> Result<(), LemmyError> {
-  let recipient_id = pm.recipient_id;
-  let recipient: ApubPerson = Person::read(context.pool(), recipient_id).await?.into();
+  let pm: ApubPrivateMessage = pm.into();
+  let recipients = pm.recipients(context).await?;
+  // Addressed to the first recipient, but delivered to everyone in the conversation
+  let recipient_id = recipients
+    .first()
+    .map(|r| r.id())
+    .ok_or_else(|| LemmyError::from_message("invalid_recipients"))?;
 
-  let deletable = DeletableObjects::PrivateMessage(pm.into());
-  let inbox = vec![recipient.shared_inbox_or_inbox()];
+  let deletable = DeletableObjects::PrivateMessage(pm);
+  let inbox = recipients
+    .iter()
+    .map(|r| r.shared_inbox_or_inbox())
+    .collect();
   if deleted {
-    let delete = Delete::new(actor, deletable, recipient.id(), None, None, context)?;
+    let delete = Delete::new(actor, deletable, recipient_id, None, None, context)?;
     send_lemmy_activity(context, delete, actor, inbox, true).await?;
   } else {
-    let undo = UndoDelete::new(actor, deletable, recipient.id(), None, None, context)?;
+    let undo = UndoDelete::new(actor, deletable, recipient_id, None, None, context)?;
     send_lemmy_activity(context, undo, actor, inbox, true).await?;
   };
   Ok(())
@@ -37,15 +37,15 @@ use lemmy_utils::error::LemmyError;
 use url::Url;
 
 impl Follow {
-  pub(in crate::activities::following) fn new(
+  pub(in crate::activities::following) fn new<T: Actor>(
     actor: &ApubPerson,
-    community: &ApubCommunity,
+    target: &T,
     context: &Data<LemmyContext>,
   ) -> Result<Follow, LemmyError> {
     Ok(Follow {
       actor: actor.id().into(),
-      object: community.id().into(),
-      to: Some([community.id().into()]),
+      object: target.id().into(),
+      to: Some([target.id().into()]),
       kind: FollowType::Follow,
       id: generate_activity_id(
         FollowType::Follow,
@@ -73,6 +73,26 @@ impl Follow {
     let inbox = vec![community.shared_inbox_or_inbox()];
     send_lemmy_activity(context, follow, actor, inbox, true).await
   }
+
+  #[tracing::instrument(skip_all)]
+  pub async fn send_to_person(
+    actor: &ApubPerson,
+    person: &ApubPerson,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let person_follower_form = PersonFollowerForm {
+      person_id: person.id,
+      follower_id: actor.id,
+      pending: true,
+    };
+    PersonFollower::follow(context.pool(), &person_follower_form)
+      .await
+      .ok();
+
+    let follow = Follow::new(actor, person, context)?;
+    let inbox = vec![person.shared_inbox_or_inbox()];
+    send_lemmy_activity(context, follow, actor, inbox, true).await
+  }
 }
 
 #[async_trait::async_trait]
@@ -1,5 +1,5 @@
 use crate::{
-  objects::community::ApubCommunity,
+  objects::{community::ApubCommunity, person::ApubPerson},
   protocol::activities::following::{follow::Follow, undo_follow::UndoFollow},
   SendActivity,
 };
@@ -7,13 +7,18 @@ use activitypub_federation::config::Data;
 use lemmy_api_common::{
   community::{CommunityResponse, FollowCommunity},
   context::LemmyContext,
+  person::{FollowPerson, FollowPersonResponse},
   utils::local_user_view_from_jwt,
 };
-use lemmy_db_schema::{source::community::Community, traits::Crud};
+use lemmy_db_schema::{
+  source::{community::Community, person::Person},
+  traits::Crud,
+};
 use lemmy_utils::error::LemmyError;
 
 pub mod accept;
 pub mod follow;
+pub mod move_person;
 pub mod undo_follow;
 
 #[async_trait::async_trait]
@@ -39,3 +44,27 @@ impl SendActivity for FollowCommunity {
     }
   }
 }
+
+#[async_trait::async_trait]
+impl SendActivity for FollowPerson {
+  type Response = FollowPersonResponse;
+
+  async fn send_activity(
+    request: &Self,
+    _response: &Self::Response,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&request.auth, context).await?;
+    let actor = local_user_view.person.into();
+    let person: ApubPerson = Person::read(context.pool(), request.person_id)
+      .await?
+      .into();
+    if person.local {
+      Ok(())
+    } else if request.follow {
+      Follow::send_to_person(&actor, &person, context).await
+    } else {
+      UndoFollow::send_to_person(&actor, &person, context).await
+    }
+  }
+}
@@ -0,0 +1,154 @@
+use crate::{
+  activities::{generate_activity_id, send_lemmy_activity, verify_person},
+  insert_activity,
+  objects::{community::ApubCommunity, person::ApubPerson},
+  protocol::{activities::following::move_person::MovePerson, objects::person::Person},
+  SendActivity,
+};
+use activitypub_federation::{
+  config::Data,
+  fetch::fetch_object_http,
+  kinds::activity::MoveType,
+  protocol::verification::verify_urls_match,
+  traits::{ActivityHandler, Actor},
+};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{MoveAccount, MoveAccountResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::{
+  source::{
+    community::CommunityFollower,
+    person::{Person as DbPerson, PersonUpdateForm},
+  },
+  traits::Crud,
+};
+use lemmy_db_views_actor::structs::CommunityFollowerView;
+use lemmy_utils::error::LemmyError;
+use url::Url;
+
+impl MovePerson {
+  /// Informs the remote communities which the person follows about the move.
+  #[tracing::instrument(skip_all)]
+  pub async fn send(
+    actor: &ApubPerson,
+    target: &ApubPerson,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let communities: Vec<ApubCommunity> =
+      CommunityFollowerView::for_person(context.pool(), actor.id)
+        .await?
+        .into_iter()
+        .map(|f| f.community.into())
+        .filter(|c: &ApubCommunity| !c.local)
+        .collect();
+    let mut inboxes: Vec<Url> = communities
+      .iter()
+      .map(|c| c.shared_inbox_or_inbox())
+      .collect();
+    inboxes.sort();
+    inboxes.dedup();
+
+    let move_ = MovePerson {
+      actor: actor.id().into(),
+      to: communities.iter().map(Actor::id).collect(),
+      object: actor.id().into(),
+      target: target.id().into(),
+      kind: MoveType::Move,
+      id: generate_activity_id(
+        MoveType::Move,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+    };
+    send_lemmy_activity(context, move_, actor, inboxes, true).await
+  }
+}
+
+#[async_trait::async_trait]
+impl SendActivity for MoveAccount {
+  type Response = MoveAccountResponse;
+
+  async fn send_activity(
+    request: &Self,
+    response: &Self::Response,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&request.auth, context).await?;
+    let target: ApubPerson = response.person_view.person.clone().into();
+    MovePerson::send(&local_user_view.person.into(), &target, context).await
+  }
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for MovePerson {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
+    verify_person(&self.actor, context).await?;
+    verify_urls_match(self.actor.inner(), self.object.inner())?;
+    let target = self.target.dereference(context).await?;
+    verify_move_target(self.actor.inner(), &target, context).await
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
+    insert_activity(&self.id, &self, false, true, context).await?;
+    let actor = self.actor.dereference(context).await?;
+    let target = self.target.dereference(context).await?;
+    move_person(&actor, &target, context).await
+  }
+}
+
+/// Checks that the new account lists the old one in `alsoKnownAs`. Otherwise anyone could take
+/// over the follows of another account.
+pub(crate) async fn verify_move_target(
+  actor_id: &Url,
+  target: &ApubPerson,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  if target.actor_id.inner() == actor_id {
+    return Err(LemmyError::from_message("cant_move_to_same_account"));
+  }
+  let also_known_as: Vec<Url> = if target.local {
+    target
+      .also_known_as
+      .clone()
+      .map(|a| vec![a.into()])
+      .unwrap_or_default()
+  } else {
+    // Only a single alias is stored in the database, so fetch the current list
+    let json: Person = fetch_object_http(target.actor_id.inner(), context).await?;
+    json.also_known_as.unwrap_or_default()
+  };
+  if also_known_as.contains(actor_id) {
+    Ok(())
+  } else {
+    Err(LemmyError::from_message("move_target_missing_alias"))
+  }
+}
+
+/// Marks the person as moved, and transfers their follows of local communities to the new
+/// account.
+pub(crate) async fn move_person(
+  actor: &ApubPerson,
+  target: &ApubPerson,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let form = PersonUpdateForm::builder()
+    .moved_to(Some(Some(target.actor_id.clone())))
+    .build();
+  DbPerson::update(context.pool(), actor.id, &form).await?;
+  CommunityFollower::transfer_local_follows(context.pool(), actor.id, target.id).await?;
+  Ok(())
+}
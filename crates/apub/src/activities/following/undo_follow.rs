@@ -43,6 +43,27 @@ impl UndoFollow {
     let inbox = vec![community.shared_inbox_or_inbox()];
     send_lemmy_activity(context, undo, actor, inbox, true).await
   }
+
+  #[tracing::instrument(skip_all)]
+  pub async fn send_to_person(
+    actor: &ApubPerson,
+    person: &ApubPerson,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let object = Follow::new(actor, person, context)?;
+    let undo = UndoFollow {
+      actor: actor.id().into(),
+      to: Some([person.id().into()]),
+      object,
+      kind: UndoType::Undo,
+      id: generate_activity_id(
+        UndoType::Undo,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+    };
+    let inbox = vec![person.shared_inbox_or_inbox()];
+    send_lemmy_activity(context, undo, actor, inbox, true).await
+  }
 }
 
 #[async_trait::async_trait]
@@ -1,10 +1,10 @@
 use crate::{
   insert_activity,
   objects::{community::ApubCommunity, person::ApubPerson},
+  send_queue::enqueue_activity,
   CONTEXT,
 };
 use activitypub_federation::{
-  activity_queue::send_activity,
   config::Data,
   fetch::object_id::ObjectId,
   kinds::public,
@@ -15,7 +15,10 @@ use anyhow::anyhow;
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{newtypes::CommunityId, source::community::Community};
 use lemmy_db_views_actor::structs::{CommunityPersonBanView, CommunityView};
-use lemmy_utils::error::LemmyError;
+use lemmy_utils::{
+  error::LemmyError,
+  metrics::{ACTIVITIES_SENT, ACTIVITY_DELIVERIES},
+};
 use serde::Serialize;
 use std::ops::Deref;
 use tracing::info;
@@ -161,8 +164,10 @@ where
   info!("Sending activity {}", activity.id().to_string());
   let activity = WithContext::new(activity, CONTEXT.deref().clone());
 
-  insert_activity(activity.id(), &activity, true, sensitive, data).await?;
-  send_activity(activity, actor, inbox, data).await?;
+  let inserted_activity = insert_activity(activity.id(), &activity, true, sensitive, data).await?;
+  ACTIVITIES_SENT.inc();
+  ACTIVITY_DELIVERIES.inc_by(inbox.len() as u64);
+  enqueue_activity(inserted_activity.id, inbox, data).await?;
 
   Ok(())
 }
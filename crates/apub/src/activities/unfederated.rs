@@ -5,6 +5,8 @@ use lemmy_api_common::{
     CommentResponse,
     DistinguishComment,
     GetComment,
+    GetCommentHistory,
+    GetCommentHistoryResponse,
     GetComments,
     GetCommentsResponse,
     ListCommentReports,
@@ -13,12 +15,24 @@ use lemmy_api_common::{
     SaveComment,
   },
   community::{
+    CommunityPostTagResponse,
+    CommunityRecurringPostResponse,
     CommunityResponse,
     CreateCommunity,
+    CreateCommunityPostTag,
+    CreateCommunityRecurringPost,
+    DeleteCommunityPostTag,
+    DeleteCommunityPostTagResponse,
+    DeleteCommunityRecurringPost,
+    DeleteCommunityRecurringPostResponse,
+    EditCommunityPostTag,
+    EditCommunityRecurringPost,
     GetCommunity,
     GetCommunityResponse,
     ListCommunities,
     ListCommunitiesResponse,
+    ListCommunityRecurringPosts,
+    ListCommunityRecurringPostsResponse,
     TransferCommunity,
   },
   custom_emoji::{
@@ -36,6 +50,14 @@ use lemmy_api_common::{
     BlockPersonResponse,
     ChangePassword,
     CommentReplyResponse,
+    CreateKeywordFilter,
+    CreateModNote,
+    DeleteKeywordFilter,
+    DeleteKeywordFilterResponse,
+    DeleteModNote,
+    DeleteModNoteResponse,
+    EditKeywordFilter,
+    ExportSettings,
     GetBannedPersons,
     GetCaptcha,
     GetCaptchaResponse,
@@ -49,22 +71,38 @@ use lemmy_api_common::{
     GetReportCountResponse,
     GetUnreadCount,
     GetUnreadCountResponse,
-    Login,
+    ImportSettings,
+    ImportSettingsResponse,
+    KeywordFilterResponse,
+    ListKeywordFilters,
+    ListKeywordFiltersResponse,
+    ListLogins,
+    ListLoginsResponse,
+    ListModNotes,
+    ListModNotesResponse,
+    ListMyImages,
+    ListMyImagesResponse,
     LoginResponse,
+    Logout,
+    LogoutResponse,
     MarkAllAsRead,
     MarkCommentReplyAsRead,
     MarkPersonMentionAsRead,
+    ModNoteResponse,
     PasswordChangeAfterReset,
     PasswordReset,
     PasswordResetResponse,
     PersonMentionResponse,
     Register,
     SaveUserSettings,
+    UserSettingsBackup,
     VerifyEmail,
     VerifyEmailResponse,
   },
   post::{
     GetPost,
+    GetPostHistory,
+    GetPostHistoryResponse,
     GetPostResponse,
     GetPosts,
     GetPostsResponse,
@@ -77,10 +115,13 @@ use lemmy_api_common::{
     PostResponse,
     ResolvePostReport,
     SavePost,
+    VotePoll,
   },
   private_message::{
     CreatePrivateMessageReport,
     GetPrivateMessages,
+    ListConversations,
+    ListConversationsResponse,
     ListPrivateMessageReports,
     ListPrivateMessageReportsResponse,
     MarkPrivateMessageAsRead,
@@ -91,6 +132,8 @@ use lemmy_api_common::{
   },
   site::{
     ApproveRegistrationApplication,
+    BlockInstance,
+    BlockInstanceResponse,
     CreateSite,
     EditSite,
     GetFederatedInstances,
@@ -102,6 +145,8 @@ use lemmy_api_common::{
     GetUnreadRegistrationApplicationCount,
     GetUnreadRegistrationApplicationCountResponse,
     LeaveAdmin,
+    ListAllImages,
+    ListAllImagesResponse,
     ListRegistrationApplications,
     ListRegistrationApplicationsResponse,
     PurgeComment,
@@ -130,6 +175,10 @@ impl SendActivity for GetPrivateMessages {
   type Response = PrivateMessagesResponse;
 }
 
+impl SendActivity for ListConversations {
+  type Response = ListConversationsResponse;
+}
+
 impl SendActivity for CreateSite {
   type Response = SiteResponse;
 }
@@ -162,6 +211,10 @@ impl SendActivity for GetPosts {
   type Response = GetPostsResponse;
 }
 
+impl SendActivity for GetPostHistory {
+  type Response = GetPostHistoryResponse;
+}
+
 impl SendActivity for GetComment {
   type Response = CommentResponse;
 }
@@ -170,8 +223,16 @@ impl SendActivity for GetComments {
   type Response = GetCommentsResponse;
 }
 
-impl SendActivity for Login {
-  type Response = LoginResponse;
+impl SendActivity for GetCommentHistory {
+  type Response = GetCommentHistoryResponse;
+}
+
+impl SendActivity for Logout {
+  type Response = LogoutResponse;
+}
+
+impl SendActivity for ListLogins {
+  type Response = ListLoginsResponse;
 }
 
 impl SendActivity for GetCaptcha {
@@ -190,6 +251,14 @@ impl SendActivity for GetUnreadRegistrationApplicationCount {
   type Response = GetUnreadRegistrationApplicationCountResponse;
 }
 
+impl SendActivity for ListMyImages {
+  type Response = ListMyImagesResponse;
+}
+
+impl SendActivity for ListAllImages {
+  type Response = ListAllImagesResponse;
+}
+
 impl SendActivity for ListRegistrationApplications {
   type Response = ListRegistrationApplicationsResponse;
 }
@@ -298,10 +367,42 @@ impl SendActivity for TransferCommunity {
   type Response = GetCommunityResponse;
 }
 
+impl SendActivity for CreateCommunityPostTag {
+  type Response = CommunityPostTagResponse;
+}
+
+impl SendActivity for EditCommunityPostTag {
+  type Response = CommunityPostTagResponse;
+}
+
+impl SendActivity for DeleteCommunityPostTag {
+  type Response = DeleteCommunityPostTagResponse;
+}
+
+impl SendActivity for CreateCommunityRecurringPost {
+  type Response = CommunityRecurringPostResponse;
+}
+
+impl SendActivity for EditCommunityRecurringPost {
+  type Response = CommunityRecurringPostResponse;
+}
+
+impl SendActivity for DeleteCommunityRecurringPost {
+  type Response = DeleteCommunityRecurringPostResponse;
+}
+
+impl SendActivity for ListCommunityRecurringPosts {
+  type Response = ListCommunityRecurringPostsResponse;
+}
+
 impl SendActivity for LeaveAdmin {
   type Response = GetSiteResponse;
 }
 
+impl SendActivity for BlockInstance {
+  type Response = BlockInstanceResponse;
+}
+
 impl SendActivity for MarkPostAsRead {
   type Response = PostResponse;
 }
@@ -310,6 +411,10 @@ impl SendActivity for SavePost {
   type Response = PostResponse;
 }
 
+impl SendActivity for VotePoll {
+  type Response = PostResponse;
+}
+
 impl SendActivity for ListPostReports {
   type Response = ListPostReportsResponse;
 }
@@ -353,3 +458,39 @@ impl SendActivity for DeleteCustomEmoji {
 impl SendActivity for GetFederatedInstances {
   type Response = GetFederatedInstancesResponse;
 }
+
+impl SendActivity for ExportSettings {
+  type Response = UserSettingsBackup;
+}
+
+impl SendActivity for ImportSettings {
+  type Response = ImportSettingsResponse;
+}
+
+impl SendActivity for CreateKeywordFilter {
+  type Response = KeywordFilterResponse;
+}
+
+impl SendActivity for EditKeywordFilter {
+  type Response = KeywordFilterResponse;
+}
+
+impl SendActivity for DeleteKeywordFilter {
+  type Response = DeleteKeywordFilterResponse;
+}
+
+impl SendActivity for ListKeywordFilters {
+  type Response = ListKeywordFiltersResponse;
+}
+
+impl SendActivity for CreateModNote {
+  type Response = ModNoteResponse;
+}
+
+impl SendActivity for ListModNotes {
+  type Response = ListModNotesResponse;
+}
+
+impl SendActivity for DeleteModNote {
+  type Response = DeleteModNoteResponse;
+}
@@ -17,7 +17,12 @@ use crate::{
         page::CreateOrUpdatePage,
       },
       deletion::{delete::Delete, delete_user::DeleteUser, undo_delete::UndoDelete},
-      following::{accept::AcceptFollow, follow::Follow, undo_follow::UndoFollow},
+      following::{
+        accept::AcceptFollow,
+        follow::Follow,
+        move_person::MovePerson,
+        undo_follow::UndoFollow,
+      },
       voting::{undo_vote::UndoVote, vote::Vote},
     },
     objects::page::Page,
@@ -49,6 +54,7 @@ pub enum GroupInboxActivities {
   Follow(Follow),
   UndoFollow(UndoFollow),
   Report(Report),
+  MovePerson(MovePerson),
   // This is a catch-all and needs to be last
   AnnouncableActivities(RawAnnouncableActivities),
 }
@@ -7,6 +7,7 @@ use activitypub_federation::config::Data;
 use lemmy_api_common::{
   comment::{GetComments, GetCommentsResponse},
   context::LemmyContext,
+  image_proxy::ProxyImageLinks,
   utils::{check_private_instance, local_user_view_from_jwt_opt},
 };
 use lemmy_db_schema::{
@@ -71,6 +72,8 @@ impl PerformApub for GetComments {
       .await
       .map_err(|e| LemmyError::from_error_message(e, "couldnt_get_comments"))?;
 
-    Ok(GetCommentsResponse { comments })
+    let mut res = GetCommentsResponse { comments };
+    res.proxy_image_links();
+    Ok(res)
   }
 }
@@ -6,6 +6,7 @@ use crate::{
 use activitypub_federation::config::Data;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImageLinks,
   post::{GetPosts, GetPostsResponse},
   utils::{check_private_instance, is_mod_or_admin_opt, local_user_view_from_jwt_opt},
 };
@@ -53,6 +54,7 @@ impl PerformApub for GetPosts {
       .sort(sort)
       .community_id(community_id)
       .saved_only(saved_only)
+      .tag_id(data.tag_id)
       .page(page)
       .limit(limit)
       .is_mod_or_admin(Some(is_mod_or_admin))
@@ -61,6 +63,8 @@ impl PerformApub for GetPosts {
       .await
       .map_err(|e| LemmyError::from_error_message(e, "couldnt_get_posts"))?;
 
-    Ok(GetPostsResponse { posts })
+    let mut res = GetPostsResponse { posts };
+    res.proxy_image_links();
+    Ok(res)
   }
 }
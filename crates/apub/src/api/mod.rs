@@ -5,10 +5,12 @@ use lemmy_utils::error::LemmyError;
 
 mod list_comments;
 mod list_posts;
+mod move_account;
 mod read_community;
 mod read_person;
 mod resolve_object;
 mod search;
+mod user_settings_backup;
 
 #[async_trait::async_trait]
 pub trait PerformApub {
@@ -0,0 +1,46 @@
+use crate::{
+  activities::following::move_person::{move_person, verify_move_target},
+  api::PerformApub,
+  fetcher::resolve_actor_identifier,
+  objects::person::ApubPerson,
+};
+use activitypub_federation::{config::Data, traits::Actor};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{MoveAccount, MoveAccountResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::person::Person;
+use lemmy_db_views_actor::structs::PersonView;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait]
+impl PerformApub for MoveAccount {
+  type Response = MoveAccountResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<MoveAccountResponse, LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&self.auth, context).await?;
+    if local_user_view.person.moved_to.is_some() {
+      return Err(LemmyError::from_message("account_already_moved"));
+    }
+
+    let target: ApubPerson = resolve_actor_identifier::<ApubPerson, Person>(
+      &self.target,
+      context,
+      &Some(local_user_view.clone()),
+      false,
+    )
+    .await
+    .map_err(|e| e.with_message("couldnt_find_that_username_or_email"))?;
+    let actor: ApubPerson = local_user_view.person.into();
+    verify_move_target(&actor.id(), &target, context).await?;
+
+    // Follows of remote communities are transferred by their instances, once they receive the
+    // activity
+    move_person(&actor, &target, context).await?;
+
+    let person_view = PersonView::read(context.pool(), target.id).await?;
+    Ok(MoveAccountResponse { person_view })
+  }
+}
@@ -7,11 +7,13 @@ use activitypub_federation::config::Data;
 use lemmy_api_common::{
   community::{GetCommunity, GetCommunityResponse},
   context::LemmyContext,
+  image_proxy::ProxyImageLinks,
   utils::{check_private_instance, is_mod_or_admin_opt, local_user_view_from_jwt_opt},
 };
 use lemmy_db_schema::source::{
   actor_language::CommunityLanguage,
   community::Community,
+  community_post_tag::CommunityPostTag,
   local_site::LocalSite,
   site::Site,
 };
@@ -81,13 +83,16 @@ impl PerformApub for GetCommunity {
 
     let community_id = community_view.community.id;
     let discussion_languages = CommunityLanguage::read(context.pool(), community_id).await?;
+    let post_tags = CommunityPostTag::list_for_community(context.pool(), community_id).await?;
 
-    let res = GetCommunityResponse {
+    let mut res = GetCommunityResponse {
       community_view,
       site,
       moderators,
       discussion_languages,
+      post_tags,
     };
+    res.proxy_image_links();
 
     // Return the jwt
     Ok(res)
@@ -2,11 +2,16 @@ use crate::{api::PerformApub, fetcher::resolve_actor_identifier, objects::person
 use activitypub_federation::config::Data;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImageLinks,
   person::{GetPersonDetails, GetPersonDetailsResponse},
   utils::{check_private_instance, is_admin, local_user_view_from_jwt_opt},
 };
 use lemmy_db_schema::{
-  source::{local_site::LocalSite, person::Person},
+  source::{
+    local_site::LocalSite,
+    mod_note::ModNote,
+    person::{Person, PersonFollower},
+  },
   utils::post_to_comment_sort_type,
 };
 use lemmy_db_views::{comment_view::CommentQuery, post_view::PostQuery};
@@ -53,7 +58,28 @@ impl PerformApub for GetPersonDetails {
 
     // You don't need to return settings for the user, since this comes back with GetSite
     // `my_user`
-    let person_view = PersonView::read(context.pool(), person_details_id).await?;
+    let mut person_view = PersonView::read(context.pool(), person_details_id).await?;
+
+    // Mod notes are only visible to admins and to moderators
+    if let Some(local_user_view) = &local_user_view {
+      let viewer_id = local_user_view.person.id;
+      let viewer_is_admin = local_user_view.person.admin;
+      if viewer_is_admin
+        || !CommunityModeratorView::for_person(context.pool(), viewer_id)
+          .await?
+          .is_empty()
+      {
+        person_view.mod_note_count = Some(
+          ModNote::count_visible(
+            context.pool(),
+            person_details_id,
+            viewer_id,
+            viewer_is_admin,
+          )
+          .await?,
+        );
+      }
+    }
 
     let sort = data.sort;
     let page = data.page;
@@ -108,13 +134,21 @@ impl PerformApub for GetPersonDetails {
     .await?;
 
     let moderates = CommunityModeratorView::for_person(context.pool(), person_details_id).await?;
+    let followers =
+      PersonFollower::list_followers(context.pool(), person_details_id, page, limit).await?;
+    let following =
+      PersonFollower::list_following(context.pool(), person_details_id, page, limit).await?;
 
     // Return the jwt
-    Ok(GetPersonDetailsResponse {
+    let mut res = GetPersonDetailsResponse {
       person_view,
       moderates,
       comments,
       posts,
-    })
+      followers,
+      following,
+    };
+    res.proxy_image_links();
+    Ok(res)
   }
 }
@@ -6,6 +6,7 @@ use activitypub_federation::config::Data;
 use diesel::NotFound;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImageLinks,
   site::{ResolveObject, ResolveObjectResponse},
   utils::{check_private_instance, local_user_view_from_jwt},
 };
@@ -31,9 +32,11 @@ impl PerformApub for ResolveObject {
     let res = search_query_to_object_id(&self.q, context)
       .await
       .map_err(|e| e.with_message("couldnt_find_object"))?;
-    convert_response(res, person_id, context.pool())
+    let mut res = convert_response(res, person_id, context.pool())
       .await
-      .map_err(|e| e.with_message("couldnt_find_object"))
+      .map_err(|e| e.with_message("couldnt_find_object"))?;
+    res.proxy_image_links();
+    Ok(res)
   }
 }
 
@@ -1,22 +1,23 @@
 use crate::{
   api::PerformApub,
   fetcher::resolve_actor_identifier,
-  objects::community::ApubCommunity,
+  objects::{community::ApubCommunity, person::ApubPerson},
 };
 use activitypub_federation::config::Data;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::ProxyImageLinks,
   site::{Search, SearchResponse},
   utils::{check_private_instance, is_admin, local_user_view_from_jwt_opt},
 };
 use lemmy_db_schema::{
-  source::{community::Community, local_site::LocalSite},
+  source::{community::Community, instance::Instance, local_site::LocalSite, person::Person},
   utils::post_to_comment_sort_type,
   SearchType,
 };
 use lemmy_db_views::{comment_view::CommentQuery, post_view::PostQuery};
 use lemmy_db_views_actor::{community_view::CommunityQuery, person_view::PersonQuery};
-use lemmy_utils::error::LemmyError;
+use lemmy_utils::{error::LemmyError, utils::search::SearchQuery};
 
 #[async_trait::async_trait]
 impl PerformApub for Search {
@@ -38,15 +39,26 @@ impl PerformApub for Search {
     let mut communities = Vec::new();
     let mut users = Vec::new();
 
-    // TODO no clean / non-nsfw searching rn
+    // TODO no clean searching rn
 
-    let q = data.q.clone();
+    let search_query = SearchQuery::parse(&data.q)?;
+    let q = Some(search_query.text).filter(|t| !t.is_empty());
     let page = data.page;
     let limit = data.limit;
     let sort = data.sort;
     let listing_type = data.listing_type;
     let search_type = data.type_.unwrap_or(SearchType::All);
-    let community_id = if let Some(name) = &data.community_name {
+    let community_id = if let Some(name) = &search_query.community {
+      let community = resolve_actor_identifier::<ApubCommunity, Community>(
+        name,
+        context,
+        &local_user_view,
+        false,
+      )
+      .await
+      .map_err(|e| e.with_message("couldnt_find_community"))?;
+      Some(community.id)
+    } else if let Some(name) = &data.community_name {
       resolve_actor_identifier::<ApubCommunity, Community>(name, context, &local_user_view, false)
         .await
         .ok()
@@ -54,7 +66,27 @@ impl PerformApub for Search {
     } else {
       data.community_id
     };
-    let creator_id = data.creator_id;
+    let creator_id = if let Some(name) = &search_query.author {
+      let person =
+        resolve_actor_identifier::<ApubPerson, Person>(name, context, &local_user_view, false)
+          .await
+          .map_err(|e| e.with_message("couldnt_find_that_username_or_email"))?;
+      Some(person.id)
+    } else {
+      data.creator_id
+    };
+    let instance_id = if let Some(domain) = &search_query.instance {
+      let instance = Instance::read_from_domain(context.pool(), domain)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_instance"))?;
+      Some(instance.id)
+    } else {
+      None
+    };
+    let url_search = search_query.url;
+    let nsfw = search_query.nsfw;
+    let published_before = search_query.before;
+    let published_after = search_query.after;
     let local_user = local_user_view.map(|l| l.local_user);
     match search_type {
       SearchType::Posts => {
@@ -65,7 +97,12 @@ impl PerformApub for Search {
           .community_id(community_id)
           .creator_id(creator_id)
           .local_user(local_user.as_ref())
-          .search_term(Some(q))
+          .search_term(q)
+          .url_search(url_search)
+          .instance_id(instance_id)
+          .nsfw(nsfw)
+          .published_after(published_after)
+          .published_before(published_before)
           .is_mod_or_admin(is_admin)
           .page(page)
           .limit(limit)
@@ -78,9 +115,13 @@ impl PerformApub for Search {
           .pool(context.pool())
           .sort(sort.map(post_to_comment_sort_type))
           .listing_type(listing_type)
-          .search_term(Some(q))
+          .search_term(q)
           .community_id(community_id)
           .creator_id(creator_id)
+          .instance_id(instance_id)
+          .nsfw(nsfw)
+          .published_after(published_after)
+          .published_before(published_before)
           .local_user(local_user.as_ref())
           .page(page)
           .limit(limit)
@@ -93,7 +134,9 @@ impl PerformApub for Search {
           .pool(context.pool())
           .sort(sort)
           .listing_type(listing_type)
-          .search_term(Some(q))
+          .search_term(q)
+          .instance_id(instance_id)
+          .nsfw(nsfw)
           .local_user(local_user.as_ref())
           .is_mod_or_admin(is_admin)
           .page(page)
@@ -106,7 +149,7 @@ impl PerformApub for Search {
         users = PersonQuery::builder()
           .pool(context.pool())
           .sort(sort)
-          .search_term(Some(q))
+          .search_term(q)
           .page(page)
           .limit(limit)
           .build()
@@ -114,9 +157,14 @@ impl PerformApub for Search {
           .await?;
       }
       SearchType::All => {
-        // If the community or creator is included, dont search communities or users
-        let community_or_creator_included =
-          data.community_id.is_some() || data.community_name.is_some() || data.creator_id.is_some();
+        // If the community or creator is included, dont search communities or users. The same
+        // goes for operators which only apply to posts and comments.
+        let community_or_creator_included = community_id.is_some()
+          || data.community_name.is_some()
+          || creator_id.is_some()
+          || url_search.is_some()
+          || published_after.is_some()
+          || published_before.is_some();
 
         let local_user_ = local_user.clone();
         posts = PostQuery::builder()
@@ -126,7 +174,12 @@ impl PerformApub for Search {
           .community_id(community_id)
           .creator_id(creator_id)
           .local_user(local_user_.as_ref())
-          .search_term(Some(q))
+          .search_term(q.clone())
+          .url_search(url_search.clone())
+          .instance_id(instance_id)
+          .nsfw(nsfw)
+          .published_after(published_after)
+          .published_before(published_before)
           .is_mod_or_admin(is_admin)
           .page(page)
           .limit(limit)
@@ -134,24 +187,29 @@ impl PerformApub for Search {
           .list()
           .await?;
 
-        let q = data.q.clone();
-
+        // Comments have no url
         let local_user_ = local_user.clone();
-        comments = CommentQuery::builder()
-          .pool(context.pool())
-          .sort(sort.map(post_to_comment_sort_type))
-          .listing_type(listing_type)
-          .search_term(Some(q))
-          .community_id(community_id)
-          .creator_id(creator_id)
-          .local_user(local_user_.as_ref())
-          .page(page)
-          .limit(limit)
-          .build()
-          .list()
-          .await?;
-
-        let q = data.q.clone();
+        comments = if url_search.is_some() {
+          vec![]
+        } else {
+          CommentQuery::builder()
+            .pool(context.pool())
+            .sort(sort.map(post_to_comment_sort_type))
+            .listing_type(listing_type)
+            .search_term(q.clone())
+            .community_id(community_id)
+            .creator_id(creator_id)
+            .instance_id(instance_id)
+            .nsfw(nsfw)
+            .published_after(published_after)
+            .published_before(published_before)
+            .local_user(local_user_.as_ref())
+            .page(page)
+            .limit(limit)
+            .build()
+            .list()
+            .await?
+        };
 
         communities = if community_or_creator_included {
           vec![]
@@ -160,7 +218,9 @@ impl PerformApub for Search {
             .pool(context.pool())
             .sort(sort)
             .listing_type(listing_type)
-            .search_term(Some(q))
+            .search_term(q.clone())
+            .instance_id(instance_id)
+            .nsfw(nsfw)
             .local_user(local_user.as_ref())
             .is_mod_or_admin(is_admin)
             .page(page)
@@ -170,15 +230,13 @@ impl PerformApub for Search {
             .await?
         };
 
-        let q = data.q.clone();
-
-        users = if community_or_creator_included {
+        users = if community_or_creator_included || instance_id.is_some() || nsfw.is_some() {
           vec![]
         } else {
           PersonQuery::builder()
             .pool(context.pool())
             .sort(sort)
-            .search_term(Some(q))
+            .search_term(q)
             .page(page)
             .limit(limit)
             .build()
@@ -193,7 +251,11 @@ impl PerformApub for Search {
           .listing_type(listing_type)
           .community_id(community_id)
           .creator_id(creator_id)
-          .url_search(Some(q))
+          .url_search(url_search.or(q))
+          .instance_id(instance_id)
+          .nsfw(nsfw)
+          .published_after(published_after)
+          .published_before(published_before)
           .is_mod_or_admin(is_admin)
           .page(page)
           .limit(limit)
@@ -204,12 +266,14 @@ impl PerformApub for Search {
     };
 
     // Return the jwt
-    Ok(SearchResponse {
+    let mut res = SearchResponse {
       type_: search_type,
       comments,
       posts,
       communities,
       users,
-    })
+    };
+    res.proxy_image_links();
+    Ok(res)
   }
 }
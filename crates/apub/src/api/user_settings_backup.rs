@@ -0,0 +1,384 @@
+use crate::{
+  activities::following::follow::Follow,
+  api::PerformApub,
+  objects::{comment::ApubComment, community::ApubCommunity, person::ApubPerson, post::ApubPost},
+};
+use activitypub_federation::{config::Data, fetch::object_id::ObjectId};
+use actix_web::rt::spawn;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{ExportSettings, ImportSettings, ImportSettingsResponse, UserSettingsBackup},
+  utils::{check_community_ban, check_community_deleted_or_removed, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  newtypes::{DbUrl, LocalUserId},
+  source::{
+    actor_language::LocalUserLanguage,
+    comment::{CommentSaved, CommentSavedForm},
+    community::{CommunityFollower, CommunityFollowerForm},
+    community_block::{CommunityBlock, CommunityBlockForm},
+    language::Language,
+    local_site::LocalSite,
+    local_user::{LocalUser, LocalUserUpdateForm},
+    person::{Person, PersonUpdateForm},
+    person_block::{PersonBlock, PersonBlockForm},
+    post::{PostSaved, PostSavedForm},
+  },
+  traits::{Blockable, Crud, Followable, Saveable},
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::{CommunityBlockView, CommunityFollowerView, PersonBlockView};
+use lemmy_utils::{
+  error::LemmyError,
+  utils::validation::{
+    is_valid_bio_field,
+    is_valid_display_name,
+    is_valid_image_url,
+    is_valid_matrix_id,
+  },
+};
+use once_cell::sync::Lazy;
+use std::{
+  collections::HashSet,
+  future::Future,
+  sync::{Arc, Mutex},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{info, warn};
+
+/// Upper bound for the number of communities, users, posts and comments in a single import, as
+/// each of them may need to be fetched from a remote instance.
+const MAX_IMPORT_ITEMS: usize = 1000;
+/// Number of imports which may run in the background at the same time. Each import fetches one
+/// item after another, so this also limits the number of outstanding fetches.
+const MAX_RUNNING_IMPORTS: usize = 10;
+
+static IMPORT_PERMITS: Lazy<Arc<Semaphore>> =
+  Lazy::new(|| Arc::new(Semaphore::new(MAX_RUNNING_IMPORTS)));
+/// Users whose import is still running, as each user may only run one import at a time.
+static RUNNING_IMPORTS: Lazy<Mutex<HashSet<LocalUserId>>> = Lazy::new(Default::default);
+
+#[async_trait::async_trait]
+impl PerformApub for ExportSettings {
+  type Response = UserSettingsBackup;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<UserSettingsBackup, LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&self.auth, context).await?;
+    let LocalUserView {
+      person, local_user, ..
+    } = local_user_view;
+    let pool = context.pool();
+
+    let mut discussion_languages = vec![];
+    for language_id in LocalUserLanguage::read(pool, local_user.id).await? {
+      discussion_languages.push(Language::read_from_id(pool, language_id).await?.code);
+    }
+    let followed_communities = CommunityFollowerView::for_person(pool, person.id)
+      .await?
+      .into_iter()
+      .map(|f| f.community.actor_id)
+      .collect();
+    let blocked_communities = CommunityBlockView::for_person(pool, person.id)
+      .await?
+      .into_iter()
+      .map(|b| b.community.actor_id)
+      .collect();
+    let blocked_users = PersonBlockView::for_person(pool, person.id)
+      .await?
+      .into_iter()
+      .map(|b| b.target.actor_id)
+      .collect();
+
+    Ok(UserSettingsBackup {
+      display_name: person.display_name,
+      bio: person.bio,
+      avatar: person.avatar,
+      banner: person.banner,
+      matrix_user_id: person.matrix_user_id,
+      bot_account: Some(person.bot_account),
+      show_nsfw: Some(local_user.show_nsfw),
+      show_scores: Some(local_user.show_scores),
+      theme: Some(local_user.theme),
+      default_sort_type: Some(local_user.default_sort_type),
+      default_listing_type: Some(local_user.default_listing_type),
+      interface_language: Some(local_user.interface_language),
+      show_avatars: Some(local_user.show_avatars),
+      send_notifications_to_email: Some(local_user.send_notifications_to_email),
+      show_bot_accounts: Some(local_user.show_bot_accounts),
+      show_read_posts: Some(local_user.show_read_posts),
+      show_new_post_notifs: Some(local_user.show_new_post_notifs),
+      discussion_languages,
+      followed_communities,
+      blocked_communities,
+      blocked_users,
+      saved_posts: PostSaved::list_ap_ids(pool, person.id).await?,
+      saved_comments: CommentSaved::list_ap_ids(pool, person.id).await?,
+    })
+  }
+}
+
+#[async_trait::async_trait]
+impl PerformApub for ImportSettings {
+  type Response = ImportSettingsResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<ImportSettingsResponse, LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&self.auth, context).await?;
+    let local_site = LocalSite::read(context.pool()).await?;
+    let backup = &self.backup;
+
+    let item_count = backup.followed_communities.len()
+      + backup.blocked_communities.len()
+      + backup.blocked_users.len()
+      + backup.saved_posts.len()
+      + backup.saved_comments.len();
+    if item_count > MAX_IMPORT_ITEMS {
+      return Err(LemmyError::from_message("too_many_items"));
+    }
+    let import_guard = ImportGuard::acquire(local_user_view.local_user.id)?;
+
+    if let Some(bio) = &backup.bio {
+      is_valid_bio_field(bio)?;
+    }
+    if let Some(display_name) = &backup.display_name {
+      is_valid_display_name(
+        display_name.trim(),
+        local_site.actor_name_max_length as usize,
+      )?;
+    }
+    if let Some(matrix_user_id) = &backup.matrix_user_id {
+      is_valid_matrix_id(matrix_user_id)?;
+    }
+    for url in backup.avatar.iter().chain(backup.banner.iter()) {
+      is_valid_image_url(url)?;
+    }
+
+    let person_id = local_user_view.person.id;
+    let person_form = PersonUpdateForm::builder()
+      .display_name(backup.display_name.clone().map(Some))
+      .bio(backup.bio.clone().map(Some))
+      .matrix_user_id(backup.matrix_user_id.clone().map(Some))
+      .bot_account(backup.bot_account)
+      .avatar(backup.avatar.clone().map(Some))
+      .banner(backup.banner.clone().map(Some))
+      .build();
+    Person::update(context.pool(), person_id, &person_form).await?;
+
+    let local_user_form = LocalUserUpdateForm::builder()
+      .show_avatars(backup.show_avatars)
+      .show_read_posts(backup.show_read_posts)
+      .show_new_post_notifs(backup.show_new_post_notifs)
+      .send_notifications_to_email(backup.send_notifications_to_email)
+      .show_nsfw(backup.show_nsfw)
+      .show_bot_accounts(backup.show_bot_accounts)
+      .show_scores(backup.show_scores)
+      .default_sort_type(backup.default_sort_type)
+      .default_listing_type(backup.default_listing_type)
+      .theme(backup.theme.clone())
+      .interface_language(backup.interface_language.clone())
+      .build();
+    LocalUser::update(
+      context.pool(),
+      local_user_view.local_user.id,
+      &local_user_form,
+    )
+    .await?;
+
+    // Languages which don't exist on this instance are ignored
+    let mut language_ids = vec![];
+    for code in &backup.discussion_languages {
+      if let Some(language_id) = Language::read_id_from_code(context.pool(), Some(code)).await? {
+        language_ids.push(language_id);
+      }
+    }
+    if !language_ids.is_empty() {
+      LocalUserLanguage::update(context.pool(), language_ids, local_user_view.local_user.id)
+        .await?;
+    }
+
+    // Fetching the referenced objects from remote instances may take a long time, so it happens in
+    // the background
+    let person: ApubPerson = local_user_view.person.into();
+    let backup = backup.clone();
+    let context = context.reset_request_count();
+    spawn(async move {
+      import_remote_items(backup, person, &context).await;
+      drop(import_guard);
+    });
+
+    Ok(ImportSettingsResponse { success: true })
+  }
+}
+
+/// Marks an import as running until it is dropped.
+struct ImportGuard {
+  local_user_id: LocalUserId,
+  _permit: OwnedSemaphorePermit,
+}
+
+impl ImportGuard {
+  fn acquire(local_user_id: LocalUserId) -> Result<Self, LemmyError> {
+    let permit = IMPORT_PERMITS
+      .clone()
+      .try_acquire_owned()
+      .map_err(|_| LemmyError::from_message("too_many_running_imports"))?;
+    let newly_running = RUNNING_IMPORTS
+      .lock()
+      .expect("Failed to lock running imports")
+      .insert(local_user_id);
+    if !newly_running {
+      return Err(LemmyError::from_message("import_already_running"));
+    }
+    Ok(ImportGuard {
+      local_user_id,
+      _permit: permit,
+    })
+  }
+}
+
+impl Drop for ImportGuard {
+  fn drop(&mut self) {
+    RUNNING_IMPORTS
+      .lock()
+      .expect("Failed to lock running imports")
+      .remove(&self.local_user_id);
+  }
+}
+
+/// Applies the follows, blocks and saved items of a backup, which may reference objects on other
+/// instances. Items are handled one after another, to avoid flooding remote instances with
+/// requests.
+async fn import_remote_items(
+  backup: UserSettingsBackup,
+  person: ApubPerson,
+  context: &Data<LemmyContext>,
+) {
+  let person_id = person.id;
+  let mut failed_imports = 0;
+
+  for url in &backup.followed_communities {
+    import_item(url, &mut failed_imports, async {
+      let community = ObjectId::<ApubCommunity>::from(url.clone())
+        .dereference(context)
+        .await?;
+      check_community_ban(person_id, community.id, context.pool()).await?;
+      check_community_deleted_or_removed(community.id, context.pool()).await?;
+      if community.local {
+        let form = CommunityFollowerForm {
+          community_id: community.id,
+          person_id,
+          pending: false,
+        };
+        CommunityFollower::follow(context.pool(), &form).await?;
+        Ok(())
+      } else {
+        Follow::send(&person, &community, context).await
+      }
+    })
+    .await;
+  }
+
+  for url in &backup.blocked_communities {
+    import_item(url, &mut failed_imports, async {
+      let community = ObjectId::<ApubCommunity>::from(url.clone())
+        .dereference(context)
+        .await?;
+      let form = CommunityBlockForm {
+        person_id,
+        community_id: community.id,
+      };
+      CommunityBlock::block(context.pool(), &form).await?;
+      Ok(())
+    })
+    .await;
+  }
+
+  for url in &backup.blocked_users {
+    import_item(url, &mut failed_imports, async {
+      let target = ObjectId::<ApubPerson>::from(url.clone())
+        .dereference(context)
+        .await?;
+      if target.id == person_id {
+        return Err(LemmyError::from_message("cant_block_yourself"));
+      }
+      if target.admin {
+        return Err(LemmyError::from_message("cant_block_admin"));
+      }
+      let form = PersonBlockForm {
+        person_id,
+        target_id: target.id,
+      };
+      PersonBlock::block(context.pool(), &form).await?;
+      Ok(())
+    })
+    .await;
+  }
+
+  for url in &backup.saved_posts {
+    import_item(url, &mut failed_imports, async {
+      let post = ObjectId::<ApubPost>::from(url.clone())
+        .dereference(context)
+        .await?;
+      let form = PostSavedForm {
+        post_id: post.id,
+        person_id,
+      };
+      PostSaved::save(context.pool(), &form).await?;
+      Ok(())
+    })
+    .await;
+  }
+
+  for url in &backup.saved_comments {
+    import_item(url, &mut failed_imports, async {
+      let comment = ObjectId::<ApubComment>::from(url.clone())
+        .dereference(context)
+        .await?;
+      let form = CommentSavedForm {
+        comment_id: comment.id,
+        person_id,
+      };
+      CommentSaved::save(context.pool(), &form).await?;
+      Ok(())
+    })
+    .await;
+  }
+
+  info!(
+    "Finished settings import for {}, {failed_imports} items failed",
+    person.actor_id
+  );
+}
+
+/// Runs the import of a single item. Failures are counted instead of aborting the whole import,
+/// because remote instances may be unreachable or have deleted the object in the meantime.
+async fn import_item<F>(url: &DbUrl, failed_imports: &mut usize, import: F)
+where
+  F: Future<Output = Result<(), LemmyError>>,
+{
+  if let Err(e) = import.await {
+    warn!("Failed to import {url}: {e}");
+    *failed_imports += 1;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ImportGuard;
+  use lemmy_db_schema::newtypes::LocalUserId;
+
+  #[test]
+  fn test_one_import_per_user() {
+    let guard = ImportGuard::acquire(LocalUserId(-1)).unwrap();
+    assert!(ImportGuard::acquire(LocalUserId(-1)).is_err());
+    let other_guard = ImportGuard::acquire(LocalUserId(-2)).unwrap();
+
+    drop(guard);
+    assert!(ImportGuard::acquire(LocalUserId(-1)).is_ok());
+    drop(other_guard);
+  }
+}
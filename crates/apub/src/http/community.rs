@@ -18,7 +18,7 @@ use activitypub_federation::{
 use actix_web::{web, web::Bytes, HttpRequest, HttpResponse};
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{source::community::Community, traits::ApubActor};
-use lemmy_utils::error::LemmyError;
+use lemmy_utils::{error::LemmyError, metrics::record_activity_received};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -53,10 +53,12 @@ pub async fn community_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
-  receive_activity::<WithContext<GroupInboxActivities>, ApubPerson, LemmyContext>(
+  let res = receive_activity::<WithContext<GroupInboxActivities>, ApubPerson, LemmyContext>(
     request, body, &data,
   )
-  .await
+  .await;
+  record_activity_received("community", &res);
+  res
 }
 
 /// Returns an empty followers collection, only populating the size (for privacy).
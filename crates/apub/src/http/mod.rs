@@ -14,7 +14,10 @@ use actix_web::{web, web::Bytes, HttpRequest, HttpResponse};
 use http::StatusCode;
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::source::activity::Activity;
-use lemmy_utils::error::{LemmyError, LemmyResult};
+use lemmy_utils::{
+  error::{LemmyError, LemmyResult},
+  metrics::record_activity_received,
+};
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 use url::Url;
@@ -31,8 +34,11 @@ pub async fn shared_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> LemmyResult<HttpResponse> {
-  receive_activity::<SharedInboxActivities, UserOrCommunity, LemmyContext>(request, body, &data)
-    .await
+  let res =
+    receive_activity::<SharedInboxActivities, UserOrCommunity, LemmyContext>(request, body, &data)
+      .await;
+  record_activity_received("shared", &res);
+  res
 }
 
 /// Convert the data to json and turn it into an HTTP Response with the correct ActivityPub
@@ -14,7 +14,7 @@ use activitypub_federation::{
 use actix_web::{web, web::Bytes, HttpRequest, HttpResponse};
 use lemmy_api_common::{context::LemmyContext, utils::generate_outbox_url};
 use lemmy_db_schema::{source::person::Person, traits::ApubActor};
-use lemmy_utils::error::LemmyError;
+use lemmy_utils::{error::LemmyError, metrics::record_activity_received};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -49,10 +49,14 @@ pub async fn person_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
-  receive_activity::<WithContext<PersonInboxActivitiesWithAnnouncable>, UserOrCommunity, LemmyContext>(
-    request, body, &data,
-  )
-  .await
+  let res = receive_activity::<
+    WithContext<PersonInboxActivitiesWithAnnouncable>,
+    UserOrCommunity,
+    LemmyContext,
+  >(request, body, &data)
+  .await;
+  record_activity_received("person", &res);
+  res
 }
 
 #[tracing::instrument(skip_all)]
@@ -25,6 +25,10 @@ pub(crate) async fn get_apub_post(
   if !post.local {
     return Err(err_object_not_local());
   }
+  // Scheduled posts aren't public yet
+  if post.scheduled_publish_time.is_some() {
+    return Err(LemmyError::from_message("couldnt_find_post"));
+  }
 
   if !post.deleted && !post.removed {
     create_apub_response(&post.into_json(&context).await?)
@@ -13,7 +13,7 @@ use activitypub_federation::{
 use actix_web::{web::Bytes, HttpRequest, HttpResponse};
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_views::structs::SiteView;
-use lemmy_utils::error::LemmyError;
+use lemmy_utils::{error::LemmyError, metrics::record_activity_received};
 use url::Url;
 
 pub(crate) async fn get_apub_site_http(
@@ -43,8 +43,10 @@ pub async fn get_apub_site_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
-  receive_activity::<WithContext<SiteInboxActivities>, ApubPerson, LemmyContext>(
+  let res = receive_activity::<WithContext<SiteInboxActivities>, ApubPerson, LemmyContext>(
     request, body, &data,
   )
-  .await
+  .await;
+  record_activity_received("site", &res);
+  res
 }
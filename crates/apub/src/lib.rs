@@ -25,6 +25,7 @@ pub mod http;
 pub(crate) mod mentions;
 pub mod objects;
 pub mod protocol;
+pub mod send_queue;
 
 pub const FEDERATION_HTTP_FETCH_LIMIT: u32 = 50;
 
@@ -163,7 +164,7 @@ async fn insert_activity<T>(
   local: bool,
   sensitive: bool,
   data: &Data<LemmyContext>,
-) -> Result<(), LemmyError>
+) -> Result<Activity, LemmyError>
 where
   T: Serialize,
 {
@@ -175,8 +176,7 @@ where
     sensitive: Some(sensitive),
     updated: None,
   };
-  Activity::create(data.pool(), &form).await?;
-  Ok(())
+  Ok(Activity::create(data.pool(), &form).await?)
 }
 
 #[async_trait::async_trait]
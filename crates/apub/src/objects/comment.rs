@@ -3,7 +3,7 @@ use crate::{
   check_apub_id_valid_with_strictness,
   fetch_local_site_data,
   mentions::collect_non_local_mentions,
-  objects::{read_from_string_or_source, verify_is_remote_object},
+  objects::{read_from_string_or_source, store_remote_image_links, verify_is_remote_object},
   protocol::{
     objects::{note::Note, LanguageTag},
     InCommunity,
@@ -179,6 +179,7 @@ impl Object for ApubComment {
     };
     let parent_comment_path = parent_comment.map(|t| t.0.path);
     let comment = Comment::create(context.pool(), &form, parent_comment_path.as_ref()).await?;
+    store_remote_image_links(vec![], &[&Some(comment.content.clone())], context).await?;
     Ok(comment.into())
   }
 }
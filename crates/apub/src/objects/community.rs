@@ -1,7 +1,7 @@
 use crate::{
   check_apub_id_valid_with_strictness,
   fetch_local_site_data,
-  objects::instance::fetch_instance_actor_for_object,
+  objects::{instance::fetch_instance_actor_for_object, store_remote_image_links},
   protocol::{
     objects::{group::Group, Endpoints, LanguageTag},
     ImageObject,
@@ -135,6 +135,15 @@ impl Object for ApubCommunity {
 
     let community = Community::create(context.pool(), &form).await?;
     CommunityLanguage::update(context.pool(), languages, community.id).await?;
+    store_remote_image_links(
+      vec![
+        community.icon.clone().map(Into::into),
+        community.banner.clone().map(Into::into),
+      ],
+      &[&community.description],
+      context,
+    )
+    .await?;
 
     let community: ApubCommunity = community.into();
 
@@ -1,7 +1,7 @@
 use crate::{
   check_apub_id_valid_with_strictness,
   fetch_local_site_data,
-  objects::read_from_string_or_source_opt,
+  objects::{read_from_string_or_source_opt, store_remote_image_links},
   protocol::{
     objects::{instance::Instance, LanguageTag},
     ImageObject,
@@ -148,6 +148,15 @@ impl Object for ApubSite {
 
     let site = Site::create(data.pool(), &site_form).await?;
     SiteLanguage::update(data.pool(), languages, &site).await?;
+    store_remote_image_links(
+      vec![
+        site.icon.clone().map(Into::into),
+        site.banner.clone().map(Into::into),
+      ],
+      &[&site.sidebar],
+      data,
+    )
+    .await?;
     Ok(site.into())
   }
 }
@@ -2,7 +2,13 @@ use crate::protocol::Source;
 use activitypub_federation::protocol::values::MediaTypeMarkdownOrHtml;
 use anyhow::anyhow;
 use html2md::parse_html;
-use lemmy_utils::{error::LemmyError, settings::structs::Settings};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::remote_image::RemoteImage;
+use lemmy_utils::{
+  error::LemmyError,
+  settings::structs::Settings,
+  utils::{image_proxy::is_remote_image_link, markdown::markdown_image_links},
+};
 use url::Url;
 
 pub mod comment;
@@ -39,6 +45,29 @@ pub(crate) fn read_from_string_or_source_opt(
     .map(|content| read_from_string_or_source(content, media_type, source))
 }
 
+/// Records the links of images in an object which was received from another instance, so that
+/// the image proxy may serve them. Inline images in the markdown fields are included.
+pub(crate) async fn store_remote_image_links(
+  links: Vec<Option<Url>>,
+  markdown: &[&Option<String>],
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let links = links
+    .into_iter()
+    .flatten()
+    .chain(
+      markdown
+        .iter()
+        .filter_map(|m| m.as_deref())
+        .flat_map(markdown_image_links),
+    )
+    .filter(is_remote_image_link)
+    .map(Into::into)
+    .collect();
+  RemoteImage::create_links(context.pool(), links).await?;
+  Ok(())
+}
+
 /// When for example a Post is made in a remote community, the community will send it back,
 /// wrapped in Announce. If we simply receive this like any other federated object, overwrite the
 /// existing, local Post. In particular, it will set the field local = false, so that the object
@@ -1,7 +1,11 @@
 use crate::{
   check_apub_id_valid_with_strictness,
   fetch_local_site_data,
-  objects::{instance::fetch_instance_actor_for_object, read_from_string_or_source_opt},
+  objects::{
+    instance::fetch_instance_actor_for_object,
+    read_from_string_or_source_opt,
+    store_remote_image_links,
+  },
   protocol::{
     objects::{
       person::{Person, UserTypes},
@@ -32,6 +36,7 @@ use lemmy_utils::{
     markdown::markdown_to_html,
     slurs::{check_slurs, check_slurs_opt},
     time::convert_datetime,
+    validation::encryption_public_key_length_check,
   },
 };
 use std::ops::Deref;
@@ -108,6 +113,9 @@ impl Object for ApubPerson {
       public_key: self.public_key(),
       updated: self.updated.map(convert_datetime),
       inbox: self.inbox_url.clone().into(),
+      also_known_as: self.also_known_as.clone().map(|a| vec![a.into()]),
+      encryption_public_key: self.encryption_public_key.clone(),
+      moved_to: self.moved_to.clone().map(Into::into),
     };
     Ok(person)
   }
@@ -170,8 +178,26 @@ impl Object for ApubPerson {
       shared_inbox_url: person.endpoints.map(|e| e.shared_inbox.into()),
       matrix_user_id: person.matrix_user_id,
       instance_id,
+      moved_to: person.moved_to.map(Into::into),
+      also_known_as: person
+        .also_known_as
+        .and_then(|a| a.into_iter().next())
+        .map(Into::into),
+      // A key which is too long is ignored, instead of rejecting the whole profile
+      encryption_public_key: person
+        .encryption_public_key
+        .filter(|k| encryption_public_key_length_check(k).is_ok()),
     };
     let person = DbPerson::upsert(context.pool(), &person_form).await?;
+    store_remote_image_links(
+      vec![
+        person.avatar.clone().map(Into::into),
+        person.banner.clone().map(Into::into),
+      ],
+      &[&person.bio],
+      context,
+    )
+    .await?;
 
     Ok(person.into())
   }
@@ -2,10 +2,25 @@ use crate::{
   activities::{verify_is_public, verify_person_in_community},
   check_apub_id_valid_with_strictness,
   fetch_local_site_data,
-  objects::{read_from_string_or_source_opt, verify_is_remote_object},
+  objects::{
+    community::ApubCommunity,
+    read_from_string_or_source_opt,
+    store_remote_image_links,
+    verify_is_remote_object,
+  },
   protocol::{
     objects::{
-      page::{Attachment, AttributedTo, Page, PageType},
+      page::{
+        Attachment,
+        AttributedTo,
+        CommunityPostTag,
+        CommunityPostTagType,
+        Page,
+        PageType,
+        QuestionOption,
+        QuestionOptionReplies,
+        Tag,
+      },
       LanguageTag,
     },
     ImageObject,
@@ -15,12 +30,12 @@ use crate::{
 };
 use activitypub_federation::{
   config::Data,
-  kinds::public,
+  kinds::{collection::CollectionType, object::NoteType, public},
   protocol::{values::MediaTypeMarkdownOrHtml, verification::verify_domains_match},
   traits::Object,
 };
 use anyhow::anyhow;
-use chrono::NaiveDateTime;
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
 use html2md::parse_html;
 use lemmy_api_common::{
   context::LemmyContext,
@@ -29,27 +44,39 @@ use lemmy_api_common::{
 };
 use lemmy_db_schema::{
   self,
+  newtypes::{DbUrl, PostId},
   source::{
     community::Community,
+    community_post_tag::{
+      CommunityPostTag as DbCommunityPostTag,
+      CommunityPostTagInsertForm,
+      PostTag,
+    },
     local_site::LocalSite,
     moderator::{ModLockPost, ModLockPostForm},
     person::Person,
+    poll::{Poll, PollForm, PollOption, PollOptionForm},
     post::{Post, PostInsertForm, PostUpdateForm},
   },
   traits::Crud,
 };
+use lemmy_db_views::structs::PollView;
 use lemmy_utils::{
   error::LemmyError,
   utils::{
     markdown::markdown_to_html,
     slurs::{check_slurs_opt, remove_slurs},
     time::convert_datetime,
+    validation::is_valid_content_type,
   },
 };
 use std::ops::Deref;
 use url::Url;
 
 const MAX_TITLE_LENGTH: usize = 200;
+const MAX_TAG_NAME_LENGTH: usize = 50;
+const MAX_POLL_OPTION_LENGTH: usize = 200;
+const MAX_POLL_OPTIONS: usize = 20;
 
 #[derive(Clone, Debug)]
 pub struct ApubPost(pub(crate) Post);
@@ -106,9 +133,52 @@ impl Object for ApubPost {
     let community_id = self.community_id;
     let community = Community::read(context.pool(), community_id).await?;
     let language = LanguageTag::new_single(self.language_id, context.pool()).await?;
+    let tag = DbCommunityPostTag::list_for_post(context.pool(), self.id)
+      .await?
+      .into_iter()
+      .map(|t| {
+        Tag::CommunityPostTag(CommunityPostTag {
+          kind: CommunityPostTagType::CommunityPostTag,
+          id: t.ap_id.into(),
+          name: t.name,
+        })
+      })
+      .collect();
+    let poll = PollView::read(context.pool(), self.id, None).await?;
+    let end_time = poll
+      .as_ref()
+      .and_then(|p| p.poll.end_time)
+      .map(convert_datetime);
+    let kind = if poll.is_some() {
+      PageType::Question
+    } else {
+      PageType::Page
+    };
+    let (one_of, any_of) = match poll {
+      Some(poll) => {
+        let options = poll
+          .options
+          .into_iter()
+          .map(|o| QuestionOption {
+            kind: NoteType::Note,
+            name: o.poll_option.name,
+            replies: QuestionOptionReplies {
+              kind: CollectionType::Collection,
+              total_items: i32::try_from(o.vote_count).unwrap_or(i32::MAX),
+            },
+          })
+          .collect();
+        if poll.poll.multiple_choice {
+          (None, Some(options))
+        } else {
+          (Some(options), None)
+        }
+      }
+      None => (None, None),
+    };
 
     let page = Page {
-      kind: PageType::Page,
+      kind,
       id: self.ap_id.clone().into(),
       attributed_to: AttributedTo::Lemmy(creator.actor_id.into()),
       to: vec![community.actor_id.clone().into(), public()],
@@ -117,7 +187,12 @@ impl Object for ApubPost {
       content: self.body.as_ref().map(|b| markdown_to_html(b)),
       media_type: Some(MediaTypeMarkdownOrHtml::Html),
       source: self.body.clone().map(Source::new),
-      attachment: self.url.clone().map(Attachment::new).into_iter().collect(),
+      attachment: self
+        .url
+        .clone()
+        .map(|url| Attachment::new(url, self.url_content_type.clone()))
+        .into_iter()
+        .collect(),
       image: self.thumbnail_url.clone().map(ImageObject::new),
       comments_enabled: Some(!self.locked),
       sensitive: Some(self.nsfw),
@@ -126,6 +201,10 @@ impl Object for ApubPost {
       updated: self.updated.map(convert_datetime),
       audience: Some(community.actor_id.into()),
       in_reply_to: None,
+      tag,
+      one_of,
+      any_of,
+      end_time,
     };
     Ok(page)
   }
@@ -187,8 +266,16 @@ impl Object for ApubPost {
     // read existing, local post if any (for generating mod log)
     let old_post = page.id.dereference_local(context).await;
 
-    let form = if !page.is_mod_action(context).await? {
-      let first_attachment = page.attachment.into_iter().map(Attachment::url).next();
+    let is_mod_action = page.is_mod_action(context).await?;
+    let form = if !is_mod_action {
+      let first_attachment = page.attachment.into_iter().next();
+      // Invalid media types are discarded, as they are passed on to clients
+      let attachment_media_type = first_attachment
+        .as_ref()
+        .and_then(Attachment::media_type)
+        .map(|t| t.to_lowercase())
+        .filter(|t| is_valid_content_type(t).is_ok());
+      let first_attachment = first_attachment.map(Attachment::url);
       let url = if first_attachment.is_some() {
         first_attachment
       } else if page.kind == PageType::Video {
@@ -205,9 +292,11 @@ impl Object for ApubPost {
         }
         _ => (None, page.image.map(|i| i.url.into())),
       };
-      let (embed_title, embed_description, embed_video_url) = metadata_res
-        .map(|u| (u.title, u.description, u.embed_video_url))
+      let (embed_title, embed_description, embed_video_url, metadata_content_type) = metadata_res
+        .map(|u| (u.title, u.description, u.embed_video_url, u.content_type))
         .unwrap_or_default();
+      // Trust the type in the attachment more than the one of our own request
+      let url_content_type = attachment_media_type.or(metadata_content_type);
       let local_site = LocalSite::read(context.pool()).await.ok();
       let slur_regex = &local_site_opt_to_slur_regex(&local_site);
 
@@ -237,6 +326,8 @@ impl Object for ApubPost {
         language_id,
         featured_community: None,
         featured_local: None,
+        scheduled_publish_time: None,
+        url_content_type,
       }
     } else {
       // if is mod action, only update locked/stickied fields, nothing else
@@ -251,6 +342,17 @@ impl Object for ApubPost {
     };
 
     let post = Post::create(context.pool(), &form).await?;
+    store_remote_image_links(
+      vec![post.thumbnail_url.clone().map(Into::into)],
+      &[&post.body],
+      context,
+    )
+    .await?;
+
+    if !is_mod_action {
+      receive_post_tags(page.tag, post.id, &community, context).await?;
+      receive_poll(page.one_of, page.any_of, page.end_time, post.id, context).await?;
+    }
 
     // write mod log entry for lock
     if Page::is_locked_changed(&old_post, &page.comments_enabled) {
@@ -266,6 +368,124 @@ impl Object for ApubPost {
   }
 }
 
+/// Stores the tags of a received post. Only tags which were defined by the instance of the
+/// post's community are accepted.
+async fn receive_post_tags(
+  tags: Vec<Tag>,
+  post_id: PostId,
+  community: &ApubCommunity,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let local_site = LocalSite::read(context.pool()).await.ok();
+  let slur_regex = &local_site_opt_to_slur_regex(&local_site);
+
+  let mut tag_ids = vec![];
+  for tag in tags {
+    let tag = match tag {
+      Tag::CommunityPostTag(tag) => tag,
+      Tag::Other(_) => continue,
+    };
+    if verify_domains_match(&tag.id, community.actor_id.inner()).is_err() {
+      continue;
+    }
+    let name: String = remove_slurs(tag.name.trim(), slur_regex)
+      .chars()
+      .take(MAX_TAG_NAME_LENGTH)
+      .collect();
+    if name.is_empty() {
+      continue;
+    }
+    let ap_id: DbUrl = tag.id.into();
+    if community.local {
+      // Tags of local communities are only defined here, so other instances may only reference
+      // the existing ones
+      if let Some(tag) =
+        DbCommunityPostTag::read_from_apub_id(context.pool(), &ap_id, community.id).await?
+      {
+        tag_ids.push(tag.id);
+      }
+      continue;
+    }
+    let form = CommunityPostTagInsertForm {
+      ap_id: Some(ap_id),
+      community_id: community.id,
+      name,
+      updated: None,
+    };
+    // Fails if another tag of the community already has this name, or if the id belongs to a tag
+    // of another community, which shouldn't prevent receiving the post
+    if let Ok(tag) = DbCommunityPostTag::upsert(context.pool(), &form).await {
+      tag_ids.push(tag.id);
+    }
+  }
+  PostTag::update(context.pool(), post_id, tag_ids).await?;
+  Ok(())
+}
+
+/// Stores the poll of a received post, including the vote counts of the remote instance. Poll votes
+/// aren't federated in either direction, so local users can't vote in remote polls, and the
+/// counts of local polls only include votes of local users.
+async fn receive_poll(
+  one_of: Option<Vec<QuestionOption>>,
+  any_of: Option<Vec<QuestionOption>>,
+  end_time: Option<DateTime<FixedOffset>>,
+  post_id: PostId,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let (options, multiple_choice) = match (one_of, any_of) {
+    (Some(options), _) => (options, false),
+    (None, Some(options)) => (options, true),
+    (None, None) => return Ok(()),
+  };
+  let local_site = LocalSite::read(context.pool()).await.ok();
+  let slur_regex = &local_site_opt_to_slur_regex(&local_site);
+  let options: Vec<(String, i32)> = options
+    .into_iter()
+    .take(MAX_POLL_OPTIONS)
+    .map(|o| {
+      let name = remove_slurs(o.name.trim(), slur_regex)
+        .chars()
+        .take(MAX_POLL_OPTION_LENGTH)
+        .collect();
+      (name, o.replies.total_items.max(0))
+    })
+    .collect();
+
+  let poll_form = PollForm {
+    post_id,
+    multiple_choice,
+    end_time: end_time.map(|t| t.naive_local()),
+    updated: None,
+  };
+  Poll::upsert(context.pool(), &poll_form).await?;
+
+  let existing = PollOption::list_for_post(context.pool(), post_id).await?;
+  let unchanged = existing.len() == options.len()
+    && existing
+      .iter()
+      .zip(options.iter())
+      .all(|(e, (name, _))| &e.name == name);
+  if unchanged {
+    for (e, (_, count)) in existing.iter().zip(options) {
+      if e.remote_vote_count != count {
+        PollOption::update_remote_vote_count(context.pool(), e.id, count).await?;
+      }
+    }
+  } else {
+    PollOption::delete_for_post(context.pool(), post_id).await?;
+    let forms: Vec<PollOptionForm> = options
+      .into_iter()
+      .map(|(name, count)| PollOptionForm {
+        post_id,
+        name,
+        remote_vote_count: Some(count),
+      })
+      .collect();
+    PollOption::create_many(context.pool(), &forms).await?;
+  }
+  Ok(())
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -301,6 +521,12 @@ mod tests {
     assert!(!post.featured_community);
     assert_eq!(context.request_count(), 0);
 
+    let tags = DbCommunityPostTag::list_for_post(context.pool(), post.id)
+      .await
+      .unwrap();
+    assert_eq!(1, tags.len());
+    assert_eq!("Question", tags[0].name);
+
     Post::delete(context.pool(), post.id).await.unwrap();
     Person::delete(context.pool(), person.id).await.unwrap();
     Community::delete(context.pool(), community.id)
@@ -1,7 +1,7 @@
 use crate::{
   check_apub_id_valid_with_strictness,
   fetch_local_site_data,
-  objects::read_from_string_or_source,
+  objects::{person::ApubPerson, read_from_string_or_source, store_remote_image_links},
   protocol::{
     objects::chat_message::{ChatMessage, ChatMessageType},
     Source,
@@ -17,17 +17,24 @@ use lemmy_api_common::{context::LemmyContext, utils::check_person_block};
 use lemmy_db_schema::{
   source::{
     person::Person,
-    private_message::{PrivateMessage, PrivateMessageInsertForm},
+    private_message::{Conversation, PrivateMessage, PrivateMessageInsertForm},
   },
   traits::Crud,
 };
 use lemmy_utils::{
   error::LemmyError,
-  utils::{markdown::markdown_to_html, time::convert_datetime},
+  utils::{
+    markdown::markdown_to_html,
+    time::convert_datetime,
+    validation::encrypted_content_length_check,
+  },
 };
 use std::ops::Deref;
 use url::Url;
 
+/// The most recipients accepted for a single incoming message.
+const MAX_RECIPIENTS: usize = 20;
+
 #[derive(Clone, Debug)]
 pub struct ApubPrivateMessage(pub(crate) PrivateMessage);
 
@@ -44,6 +51,26 @@ impl From<PrivateMessage> for ApubPrivateMessage {
   }
 }
 
+impl ApubPrivateMessage {
+  /// Everyone in the conversation except the creator, with the first recipient first.
+  pub(crate) async fn recipients(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<Vec<ApubPerson>, LemmyError> {
+    let mut recipient_ids = vec![self.recipient_id];
+    for person_id in Conversation::list_participants(context.pool(), self.conversation_id).await? {
+      if person_id != self.creator_id && person_id != self.recipient_id {
+        recipient_ids.push(person_id);
+      }
+    }
+    let mut recipients = vec![];
+    for person_id in recipient_ids {
+      recipients.push(Person::read(context.pool(), person_id).await?.into());
+    }
+    Ok(recipients)
+  }
+}
+
 #[async_trait::async_trait]
 impl Object for ApubPrivateMessage {
   type DataType = LemmyContext;
@@ -75,16 +102,18 @@ impl Object for ApubPrivateMessage {
   async fn into_json(self, context: &Data<Self::DataType>) -> Result<ChatMessage, LemmyError> {
     let creator_id = self.creator_id;
     let creator = Person::read(context.pool(), creator_id).await?;
-
-    let recipient_id = self.recipient_id;
-    let recipient = Person::read(context.pool(), recipient_id).await?;
+    let recipients = self.recipients(context).await?;
 
     let note = ChatMessage {
       r#type: ChatMessageType::ChatMessage,
       id: self.ap_id.clone().into(),
       attributed_to: creator.actor_id.into(),
-      to: [recipient.actor_id.into()],
+      to: recipients
+        .into_iter()
+        .map(|r| r.actor_id.clone().into())
+        .collect(),
       content: markdown_to_html(&self.content),
+      encrypted_content: self.encrypted_content.clone(),
       media_type: Some(MediaTypeHtml::Html),
       source: Some(Source::new(self.content.clone())),
       published: Some(convert_datetime(self.published)),
@@ -122,13 +151,26 @@ impl Object for ApubPrivateMessage {
     note: ChatMessage,
     context: &Data<Self::DataType>,
   ) -> Result<ApubPrivateMessage, LemmyError> {
+    if note.to.is_empty() || note.to.len() > MAX_RECIPIENTS {
+      return Err(LemmyError::from_message("invalid_recipients"));
+    }
+    if let Some(encrypted_content) = &note.encrypted_content {
+      encrypted_content_length_check(encrypted_content)?;
+    }
     let creator = note.attributed_to.dereference(context).await?;
-    let recipient = note.to[0].dereference(context).await?;
-    check_person_block(creator.id, recipient.id, context.pool()).await?;
+    let mut recipient_ids = vec![];
+    for recipient in &note.to {
+      let recipient = recipient.dereference(context).await?;
+      check_person_block(creator.id, recipient.id, context.pool()).await?;
+      recipient_ids.push(recipient.id);
+    }
+    let mut participants = recipient_ids.clone();
+    participants.push(creator.id);
+    let conversation = Conversation::read_or_create(context.pool(), &participants).await?;
 
     let form = PrivateMessageInsertForm {
       creator_id: creator.id,
-      recipient_id: recipient.id,
+      recipient_id: recipient_ids[0],
       content: read_from_string_or_source(&note.content, &None, &note.source),
       published: note.published.map(|u| u.naive_local()),
       updated: note.updated.map(|u| u.naive_local()),
@@ -136,8 +178,12 @@ impl Object for ApubPrivateMessage {
       read: None,
       ap_id: Some(note.id.into()),
       local: Some(false),
+      conversation_id: conversation.id,
+      encrypted_content: note.encrypted_content,
     };
     let pm = PrivateMessage::create(context.pool(), &form).await?;
+    store_remote_image_links(vec![], &[&Some(pm.content.clone())], context).await?;
+    Conversation::update_for_message(context.pool(), conversation.id, pm.published).await?;
     Ok(pm.into())
   }
 }
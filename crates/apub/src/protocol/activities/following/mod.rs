@@ -1,11 +1,17 @@
 pub(crate) mod accept;
 pub mod follow;
+pub mod move_person;
 pub mod undo_follow;
 
 #[cfg(test)]
 mod tests {
   use crate::protocol::{
-    activities::following::{accept::AcceptFollow, follow::Follow, undo_follow::UndoFollow},
+    activities::following::{
+      accept::AcceptFollow,
+      follow::Follow,
+      move_person::MovePerson,
+      undo_follow::UndoFollow,
+    },
     tests::test_parse_lemmy_item,
   };
 
@@ -15,5 +21,6 @@ mod tests {
     test_parse_lemmy_item::<AcceptFollow>("assets/lemmy/activities/following/accept.json").unwrap();
     test_parse_lemmy_item::<UndoFollow>("assets/lemmy/activities/following/undo_follow.json")
       .unwrap();
+    test_parse_lemmy_item::<MovePerson>("assets/lemmy/activities/following/move.json").unwrap();
   }
 }
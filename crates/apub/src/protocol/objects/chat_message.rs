@@ -5,7 +5,7 @@ use crate::{
 use activitypub_federation::{
   fetch::object_id::ObjectId,
   protocol::{
-    helpers::{deserialize_one, deserialize_skip_error},
+    helpers::{deserialize_one_or_many, deserialize_skip_error},
     values::MediaTypeHtml,
   },
 };
@@ -20,9 +20,11 @@ pub struct ChatMessage {
   pub(crate) r#type: ChatMessageType,
   pub(crate) id: ObjectId<ApubPrivateMessage>,
   pub(crate) attributed_to: ObjectId<ApubPerson>,
-  #[serde(deserialize_with = "deserialize_one")]
-  pub(crate) to: [ObjectId<ApubPerson>; 1],
+  #[serde(deserialize_with = "deserialize_one_or_many")]
+  pub(crate) to: Vec<ObjectId<ApubPerson>>,
   pub(crate) content: String,
+  /// End-to-end encrypted message, which only the clients of the sender and recipients can read
+  pub(crate) encrypted_content: Option<String>,
 
   pub(crate) media_type: Option<MediaTypeHtml>,
   #[serde(deserialize_with = "deserialize_skip_error", default)]
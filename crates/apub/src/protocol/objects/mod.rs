@@ -137,6 +137,7 @@ mod tests {
     test_json::<Person>("assets/mastodon/objects/person.json").unwrap();
     test_json::<Note>("assets/mastodon/objects/note.json").unwrap();
     test_json::<Page>("assets/mastodon/objects/page.json").unwrap();
+    test_json::<Page>("assets/mastodon/objects/question.json").unwrap();
   }
 
   #[test]
@@ -8,8 +8,9 @@ use activitypub_federation::{
   config::Data,
   fetch::object_id::ObjectId,
   kinds::{
+    collection::CollectionType,
     link::LinkType,
-    object::{DocumentType, ImageType},
+    object::{AudioType, DocumentType, ImageType, NoteType, VideoType},
   },
   protocol::{
     helpers::{deserialize_one_or_many, deserialize_skip_error},
@@ -33,6 +34,7 @@ pub enum PageType {
   Note,
   Video,
   Event,
+  Question,
 }
 
 #[skip_serializing_none]
@@ -67,6 +69,54 @@ pub struct Page {
   pub(crate) updated: Option<DateTime<FixedOffset>>,
   pub(crate) language: Option<LanguageTag>,
   pub(crate) audience: Option<ObjectId<ApubCommunity>>,
+  /// Tags of the community which apply to the post. Other software uses this field for hashtags
+  /// and mentions, which are ignored.
+  #[serde(default)]
+  pub(crate) tag: Vec<Tag>,
+  /// Options of a poll which allows only a single choice.
+  pub(crate) one_of: Option<Vec<QuestionOption>>,
+  /// Options of a poll which allows multiple choices.
+  pub(crate) any_of: Option<Vec<QuestionOption>>,
+  pub(crate) end_time: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct QuestionOption {
+  #[serde(rename = "type")]
+  pub(crate) kind: NoteType,
+  pub(crate) name: String,
+  pub(crate) replies: QuestionOptionReplies,
+}
+
+/// Contains the number of votes for the option, as counted by the instance of the poll.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct QuestionOptionReplies {
+  #[serde(rename = "type")]
+  pub(crate) kind: CollectionType,
+  pub(crate) total_items: i32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum CommunityPostTagType {
+  CommunityPostTag,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CommunityPostTag {
+  #[serde(rename = "type")]
+  pub(crate) kind: CommunityPostTagType,
+  pub(crate) id: Url,
+  pub(crate) name: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub(crate) enum Tag {
+  CommunityPostTag(CommunityPostTag),
+  Other(serde_json::Value),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -84,12 +134,34 @@ pub(crate) struct Image {
   pub(crate) url: Url,
 }
 
+#[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct Document {
   #[serde(rename = "type")]
   pub(crate) kind: DocumentType,
   pub(crate) url: Url,
+  pub(crate) media_type: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Video {
+  #[serde(rename = "type")]
+  pub(crate) kind: VideoType,
+  pub(crate) url: Url,
+  pub(crate) media_type: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Audio {
+  #[serde(rename = "type")]
+  pub(crate) kind: AudioType,
+  pub(crate) url: Url,
+  pub(crate) media_type: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -98,6 +170,8 @@ pub(crate) enum Attachment {
   Link(Link),
   Image(Image),
   Document(Document),
+  Video(Video),
+  Audio(Audio),
 }
 
 impl Attachment {
@@ -109,6 +183,19 @@ impl Attachment {
       Attachment::Image(i) => i.url,
       // sent by mobilizon
       Attachment::Document(d) => d.url,
+      // uploaded video or audio, sent by Lemmy
+      Attachment::Video(v) => v.url,
+      Attachment::Audio(a) => a.url,
+    }
+  }
+
+  /// The MIME type of the attachment, if the sender included it.
+  pub(crate) fn media_type(&self) -> Option<String> {
+    match self {
+      Attachment::Link(_) | Attachment::Image(_) => None,
+      Attachment::Document(d) => d.media_type.clone(),
+      Attachment::Video(v) => v.media_type.clone(),
+      Attachment::Audio(a) => a.media_type.clone(),
     }
   }
 }
@@ -167,11 +254,24 @@ impl Page {
 }
 
 impl Attachment {
-  pub(crate) fn new(url: DbUrl) -> Attachment {
-    Attachment::Link(Link {
-      href: url.into(),
-      r#type: Default::default(),
-    })
+  /// Videos and audio are sent with their own types, so that other software can play them.
+  pub(crate) fn new(url: DbUrl, content_type: Option<String>) -> Attachment {
+    match content_type {
+      Some(media_type) if media_type.starts_with("video/") => Attachment::Video(Video {
+        kind: Default::default(),
+        url: url.into(),
+        media_type: Some(media_type),
+      }),
+      Some(media_type) if media_type.starts_with("audio/") => Attachment::Audio(Audio {
+        kind: Default::default(),
+        url: url.into(),
+        media_type: Some(media_type),
+      }),
+      _ => Attachment::Link(Link {
+        href: url.into(),
+        r#type: Default::default(),
+      }),
+    }
   }
 }
 
@@ -45,4 +45,11 @@ pub struct Person {
   pub(crate) endpoints: Option<Endpoints>,
   pub(crate) published: Option<DateTime<FixedOffset>>,
   pub(crate) updated: Option<DateTime<FixedOffset>>,
+  /// Other accounts of the same user, which are allowed to move to this one
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) also_known_as: Option<Vec<Url>>,
+  /// Set once the user moved to another account
+  pub(crate) moved_to: Option<ObjectId<ApubPerson>>,
+  /// Public key used by clients to encrypt private messages to the user
+  pub(crate) encryption_public_key: Option<String>,
 }
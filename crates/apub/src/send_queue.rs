@@ -0,0 +1,354 @@
+//! Persistent queue for outgoing activities.
+//!
+//! Activities are stored in the database together with the inboxes they need to be delivered to.
+//! Each remote instance has its own worker, which delivers the pending activities in order and
+//! removes every inbox from the queue once it received the activity, so that sending resumes after
+//! a restart. Failed deliveries are retried with exponential backoff, and instances which keep
+//! failing are marked as dead, dropping everything that was queued for them.
+use crate::{check_apub_id_valid, fetch_local_site_data};
+use activitypub_federation::config::Data;
+use actix_web::rt::{spawn, task::JoinHandle};
+use anyhow::anyhow;
+use http_signature_normalization_reqwest::prelude::{Config, SignExt};
+use itertools::Itertools;
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  newtypes::{DbUrl, InstanceId},
+  source::{
+    activity::{Activity, ActivitySendInbox, ActivitySendInboxForm},
+    community::Community,
+    federation_queue_state::{FederationQueueState, FederationQueueStateForm},
+    instance::Instance,
+    person::Person,
+    site::Site,
+  },
+  traits::ApubActor,
+  utils::{naive_now, DbPool},
+};
+use lemmy_utils::{error::LemmyError, REQWEST_TIMEOUT};
+use once_cell::sync::Lazy;
+use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+use reqwest::{header::CONTENT_TYPE, Request, StatusCode};
+use reqwest_middleware::RequestBuilder;
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, time::Duration};
+use tokio::{
+  sync::Notify,
+  time::{sleep, timeout},
+};
+use tracing::{error, info, warn};
+use url::Url;
+
+/// How often to check for new activities, if no notification was received. This is only relevant
+/// if activities are created by a different process than the one running the queue.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Number of activities which are read from the database at once
+const BATCH_SIZE: i64 = 50;
+const BACKOFF_BASE: Duration = Duration::from_secs(10);
+const MAX_BACKOFF: Duration = Duration::from_secs(24 * 60 * 60);
+/// The backoff reaches its maximum at this point, after about two days of failures. Dead instances
+/// are only retried once a day.
+const DEAD_AFTER_FAILURES: i32 = 14;
+
+static NEW_ACTIVITY: Lazy<Notify> = Lazy::new(Notify::new);
+/// Same signature format as activitypub_federation uses for its own requests, with
+/// `http_signature_compat` enabled.
+static HTTP_SIGNATURE_CONFIG: Lazy<Config> = Lazy::new(|| Config::new().mastodon_compat());
+
+/// Queues the activity for delivery to the given inboxes. Local and blocked inboxes are skipped.
+pub(crate) async fn enqueue_activity(
+  activity_id: i32,
+  inboxes: Vec<Url>,
+  data: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let local_site_data = fetch_local_site_data(data.pool()).await?;
+  let local_domain = data.settings().get_hostname_without_port()?;
+
+  let mut forms = vec![];
+  for inbox in inboxes.into_iter().unique() {
+    let domain = match inbox.domain() {
+      Some(domain) => domain,
+      None => {
+        warn!("Not sending activity {activity_id} to inbox {inbox} without domain");
+        continue;
+      }
+    };
+    if domain == local_domain || check_apub_id_valid(&inbox, &local_site_data).is_err() {
+      continue;
+    }
+    let instance = Instance::read_or_create(data.pool(), domain.to_string()).await?;
+    forms.push(ActivitySendInboxForm {
+      activity_id,
+      instance_id: instance.id,
+      inbox: inbox.into(),
+    });
+  }
+  if forms.is_empty() {
+    return Ok(());
+  }
+  ActivitySendInbox::create_many(data.pool(), &forms).await?;
+  NEW_ACTIVITY.notify_one();
+  Ok(())
+}
+
+/// Delivers queued activities in the background, starting a worker for each instance which has
+/// pending activities. Only a single process should run this.
+pub async fn run_send_queue(context: LemmyContext) {
+  let mut workers: HashMap<InstanceId, JoinHandle<()>> = HashMap::new();
+  loop {
+    match ActivitySendInbox::pending_instances(context.pool()).await {
+      Ok(instances) => {
+        workers.retain(|_, worker| !worker.is_finished());
+        for instance_id in instances {
+          workers.entry(instance_id).or_insert_with(|| {
+            let context = context.clone();
+            spawn(async move {
+              if let Err(e) = instance_worker(instance_id, &context).await {
+                error!("Activity delivery for instance {instance_id:?} stopped: {e}");
+              }
+            })
+          });
+        }
+      }
+      Err(e) => error!("Failed to read pending activities: {e}"),
+    }
+    // Either a notification or a timeout means it is time to check again
+    timeout(POLL_INTERVAL, NEW_ACTIVITY.notified()).await.ok();
+  }
+}
+
+/// Delivers all pending activities to a single instance, in order. Returns once there is nothing
+/// left to send.
+async fn instance_worker(
+  instance_id: InstanceId,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let mut state = FederationQueueState::read_or_create(context.pool(), instance_id).await?;
+  loop {
+    let delay = retry_delay(&state);
+    if !delay.is_zero() {
+      sleep(delay).await;
+    }
+
+    let pending = ActivitySendInbox::read_pending(context.pool(), instance_id, BATCH_SIZE).await?;
+    if pending.is_empty() {
+      return Ok(());
+    }
+
+    for (activity, inboxes) in pending {
+      match deliver_to_inboxes(&activity, instance_id, inboxes, context).await {
+        Ok(()) => {
+          state.fail_count = 0;
+          state.dead_since = None;
+        }
+        Err(e) => {
+          state.fail_count += 1;
+          state.last_retry = Some(naive_now());
+          warn!(
+            "Failed to send activity {} (attempt {}): {e}",
+            activity.ap_id, state.fail_count
+          );
+          if state.fail_count >= DEAD_AFTER_FAILURES && state.dead_since.is_none() {
+            info!("Marking instance {instance_id:?} as dead");
+            state.dead_since = Some(naive_now());
+          }
+          break;
+        }
+      }
+    }
+
+    let form = FederationQueueStateForm {
+      instance_id,
+      fail_count: state.fail_count,
+      last_retry: state.last_retry,
+      dead_since: state.dead_since,
+    };
+    state = FederationQueueState::upsert(context.pool(), &form).await?;
+    if state.dead_since.is_some() {
+      // Don't let activities pile up for an instance which may never come back. Newly queued
+      // activities are still attempted once a day, and a success revives the instance.
+      ActivitySendInbox::delete_for_instance(context.pool(), instance_id).await?;
+    }
+  }
+}
+
+/// Time to wait before the next attempt, doubling with each consecutive failure.
+fn retry_delay(state: &FederationQueueState) -> Duration {
+  let last_retry = match state.last_retry {
+    Some(last_retry) if state.fail_count > 0 => last_retry,
+    _ => return Duration::ZERO,
+  };
+  let backoff = if state.dead_since.is_some() {
+    MAX_BACKOFF
+  } else {
+    let exponent = u32::try_from(state.fail_count - 1).unwrap_or(0);
+    BACKOFF_BASE
+      .saturating_mul(2u32.saturating_pow(exponent))
+      .min(MAX_BACKOFF)
+  };
+  let elapsed = (naive_now() - last_retry).to_std().unwrap_or_default();
+  backoff.saturating_sub(elapsed)
+}
+
+/// Sends the activity to all inboxes, and removes each inbox from the queue once it received the
+/// activity. Returns an error if any delivery should be retried.
+async fn deliver_to_inboxes(
+  activity: &Activity,
+  instance_id: InstanceId,
+  inboxes: Vec<DbUrl>,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let actor_id = activity
+    .data
+    .get("actor")
+    .and_then(|a| a.as_str())
+    .map(Url::parse)
+    .transpose()?
+    .ok_or_else(|| anyhow!("Activity has no actor"))?;
+  let private_key = match actor_private_key(actor_id.clone().into(), context.pool()).await? {
+    Some(private_key) => private_key,
+    None => {
+      // Can't ever be sent, so skip it
+      warn!("No private key for actor {actor_id}, skipping activity");
+      ActivitySendInbox::delete_for_activity(context.pool(), activity.id, instance_id).await?;
+      return Ok(());
+    }
+  };
+  let body = serde_json::to_vec(&activity.data)?;
+
+  let mut failure = None;
+  for db_inbox in inboxes {
+    let inbox: Url = db_inbox.clone().into();
+    let request_builder = context
+      .client()
+      .post(inbox.as_str())
+      .header(CONTENT_TYPE, "application/activity+json")
+      .timeout(REQWEST_TIMEOUT);
+    let request = sign_request(request_builder, &actor_id, body.clone(), &private_key).await?;
+    let status = match context.client().execute(request).await {
+      Ok(res) => res.status(),
+      Err(e) => {
+        failure = Some(anyhow!("Failed to send to inbox {inbox}: {e}"));
+        continue;
+      }
+    };
+
+    if !status.is_success() {
+      // The remote instance refused this activity, retrying won't change that
+      let rejected = status.is_client_error()
+        && status != StatusCode::REQUEST_TIMEOUT
+        && status != StatusCode::TOO_MANY_REQUESTS;
+      if rejected {
+        info!(
+          "Activity {} was rejected by {inbox}: {status}",
+          activity.ap_id
+        );
+      } else {
+        failure = Some(anyhow!("Inbox {inbox} responded with {status}"));
+        continue;
+      }
+    }
+    ActivitySendInbox::delete_for_inbox(context.pool(), activity.id, &db_inbox).await?;
+  }
+  match failure {
+    Some(e) => Err(e.into()),
+    None => Ok(()),
+  }
+}
+
+/// Signs a post request to an inbox with the key of the actor, in the format which
+/// activitypub_federation uses for its own requests.
+async fn sign_request(
+  request_builder: RequestBuilder,
+  actor_id: &Url,
+  body: Vec<u8>,
+  private_key: &str,
+) -> Result<Request, LemmyError> {
+  let key_id = format!("{actor_id}#main-key");
+  let private_key = PKey::private_key_from_pem(private_key.as_bytes())?;
+  let request = request_builder
+    .signature_with_digest(
+      HTTP_SIGNATURE_CONFIG.clone(),
+      key_id,
+      Sha256::new(),
+      body,
+      move |signing_string| {
+        let mut signer = Signer::new(MessageDigest::sha256(), &private_key)?;
+        signer.update(signing_string.as_bytes())?;
+        Ok(base64::encode(signer.sign_to_vec()?)) as Result<_, anyhow::Error>
+      },
+    )
+    .await?;
+  Ok(request)
+}
+
+/// Local actors which can send activities are users, communities and the site itself.
+async fn actor_private_key(actor_id: DbUrl, pool: &DbPool) -> Result<Option<String>, LemmyError> {
+  if let Some(person) = Person::read_from_apub_id(pool, &actor_id).await? {
+    return Ok(person.private_key);
+  }
+  if let Some(community) = Community::read_from_apub_id(pool, &actor_id).await? {
+    return Ok(community.private_key);
+  }
+  if let Some(site) = Site::read_from_apub_id(pool, &actor_id).await? {
+    return Ok(site.private_key);
+  }
+  Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{retry_delay, sign_request, MAX_BACKOFF};
+  use activitypub_federation::http_signatures::generate_actor_keypair;
+  use lemmy_db_schema::{
+    newtypes::InstanceId,
+    source::federation_queue_state::FederationQueueState,
+    utils::naive_now,
+  };
+  use reqwest::Client;
+  use reqwest_middleware::ClientBuilder;
+  use std::time::Duration;
+  use url::Url;
+
+  #[tokio::test]
+  async fn test_sign_request() {
+    let keypair = generate_actor_keypair().unwrap();
+    let actor_id = Url::parse("https://my_domain.tld/u/alice").unwrap();
+    let client = ClientBuilder::new(Client::new()).build();
+    let request_builder = client.post("https://lemmy.tld:8536/inbox");
+    let body = br#"{"type":"Follow"}"#.to_vec();
+
+    let request = sign_request(request_builder, &actor_id, body, &keypair.private_key)
+      .await
+      .unwrap();
+    let header = |name: &str| request.headers().get(name).unwrap().to_str().unwrap();
+    assert!(header("Signature").contains(r#"keyId="https://my_domain.tld/u/alice#main-key""#));
+    assert!(header("Digest").starts_with("SHA-256="));
+  }
+
+  #[test]
+  fn test_retry_delay() {
+    let mut state = FederationQueueState {
+      id: 1,
+      instance_id: InstanceId::default(),
+      fail_count: 0,
+      last_retry: None,
+      dead_since: None,
+    };
+    assert_eq!(Duration::ZERO, retry_delay(&state));
+
+    state.fail_count = 3;
+    state.last_retry = Some(naive_now());
+    let delay = retry_delay(&state);
+    assert!(delay > Duration::from_secs(30) && delay <= Duration::from_secs(40));
+
+    // Backoff is capped
+    state.fail_count = 100;
+    assert!(retry_delay(&state) <= MAX_BACKOFF);
+
+    // Long enough ago that the next attempt is due
+    state.fail_count = 1;
+    state.last_retry = Some(naive_now() - chrono::Duration::minutes(1));
+    assert_eq!(Duration::ZERO, retry_delay(&state));
+  }
+}
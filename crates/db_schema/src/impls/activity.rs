@@ -1,7 +1,17 @@
 use crate::{
-  newtypes::DbUrl,
-  schema::activity::dsl::{activity, ap_id},
-  source::activity::{Activity, ActivityInsertForm, ActivityUpdateForm},
+  newtypes::{DbUrl, InstanceId},
+  schema,
+  schema::{
+    activity::dsl::{activity, ap_id},
+    activity_send_inbox,
+  },
+  source::activity::{
+    Activity,
+    ActivityInsertForm,
+    ActivitySendInbox,
+    ActivitySendInboxForm,
+    ActivityUpdateForm,
+  },
   traits::Crud,
   utils::{get_conn, DbPool},
 };
@@ -55,6 +65,108 @@ impl Activity {
   }
 }
 
+impl ActivitySendInbox {
+  pub async fn create_many(pool: &DbPool, forms: &[ActivitySendInboxForm]) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(activity_send_inbox::table)
+      .values(forms)
+      .execute(conn)
+      .await
+  }
+
+  /// Lists all instances which have activities waiting for delivery.
+  pub async fn pending_instances(pool: &DbPool) -> Result<Vec<InstanceId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    activity_send_inbox::table
+      .select(activity_send_inbox::instance_id)
+      .distinct()
+      .load::<InstanceId>(conn)
+      .await
+  }
+
+  /// Returns up to `limit` activities which are still waiting for delivery to the instance, in
+  /// order, together with all inboxes on the instance which each of them needs to be delivered to.
+  pub async fn read_pending(
+    pool: &DbPool,
+    for_instance_id: InstanceId,
+    limit: i64,
+  ) -> Result<Vec<(Activity, Vec<DbUrl>)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let activity_ids = activity_send_inbox::table
+      .filter(activity_send_inbox::instance_id.eq(for_instance_id))
+      .select(activity_send_inbox::activity_id)
+      .distinct()
+      .order_by(activity_send_inbox::activity_id)
+      .limit(limit)
+      .load::<i32>(conn)
+      .await?;
+    let rows = activity_send_inbox::table
+      .inner_join(activity)
+      .filter(activity_send_inbox::instance_id.eq(for_instance_id))
+      .filter(activity_send_inbox::activity_id.eq_any(activity_ids))
+      .order_by((activity_send_inbox::activity_id, activity_send_inbox::id))
+      .select((schema::activity::all_columns, activity_send_inbox::inbox))
+      .load::<(Activity, DbUrl)>(conn)
+      .await?;
+
+    // Rows are ordered by activity, so inboxes of the same activity are next to each other
+    let mut pending: Vec<(Activity, Vec<DbUrl>)> = vec![];
+    for (a, inbox) in rows {
+      match pending.last_mut() {
+        Some((last, inboxes)) if last.id == a.id => inboxes.push(inbox),
+        _ => pending.push((a, vec![inbox])),
+      }
+    }
+    Ok(pending)
+  }
+
+  /// Removes a single delivery which succeeded, so that it isn't repeated if the activity needs
+  /// to be retried for other inboxes.
+  pub async fn delete_for_inbox(
+    pool: &DbPool,
+    for_activity_id: i32,
+    for_inbox: &DbUrl,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      activity_send_inbox::table
+        .filter(activity_send_inbox::activity_id.eq(for_activity_id))
+        .filter(activity_send_inbox::inbox.eq(for_inbox)),
+    )
+    .execute(conn)
+    .await
+  }
+
+  /// Removes all deliveries of the activity to the instance, for activities which can't be sent.
+  pub async fn delete_for_activity(
+    pool: &DbPool,
+    for_activity_id: i32,
+    for_instance_id: InstanceId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      activity_send_inbox::table
+        .filter(activity_send_inbox::activity_id.eq(for_activity_id))
+        .filter(activity_send_inbox::instance_id.eq(for_instance_id)),
+    )
+    .execute(conn)
+    .await
+  }
+
+  /// Removes all pending deliveries to the instance, used once it is considered dead.
+  pub async fn delete_for_instance(
+    pool: &DbPool,
+    for_instance_id: InstanceId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      activity_send_inbox::table.filter(activity_send_inbox::instance_id.eq(for_instance_id)),
+    )
+    .execute(conn)
+    .await
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -129,6 +241,40 @@ mod tests {
 
     let read_activity = Activity::read(pool, inserted_activity.id).await.unwrap();
     let read_activity_by_apub_id = Activity::read_from_apub_id(pool, &ap_id_).await.unwrap();
+
+    // Successful deliveries are removed, so that only the remaining inboxes are retried
+    let inbox = |path: &str| -> DbUrl {
+      Url::parse(&format!("https://my_domain.tld/{path}"))
+        .unwrap()
+        .into()
+    };
+    let send_form = |path: &str| ActivitySendInboxForm {
+      activity_id: inserted_activity.id,
+      instance_id: inserted_instance.id,
+      inbox: inbox(path),
+    };
+    ActivitySendInbox::create_many(pool, &[send_form("inbox"), send_form("u/alice/inbox")])
+      .await
+      .unwrap();
+    let pending = ActivitySendInbox::read_pending(pool, inserted_instance.id, 10)
+      .await
+      .unwrap();
+    assert_eq!(vec![inbox("inbox"), inbox("u/alice/inbox")], pending[0].1);
+    ActivitySendInbox::delete_for_inbox(pool, inserted_activity.id, &inbox("inbox"))
+      .await
+      .unwrap();
+    let pending = ActivitySendInbox::read_pending(pool, inserted_instance.id, 10)
+      .await
+      .unwrap();
+    assert_eq!(vec![inbox("u/alice/inbox")], pending[0].1);
+    ActivitySendInbox::delete_for_inbox(pool, inserted_activity.id, &inbox("u/alice/inbox"))
+      .await
+      .unwrap();
+    let pending = ActivitySendInbox::read_pending(pool, inserted_instance.id, 10)
+      .await
+      .unwrap();
+    assert!(pending.is_empty());
+
     Person::delete(pool, inserted_creator.id).await.unwrap();
     Activity::delete(pool, inserted_activity.id).await.unwrap();
 
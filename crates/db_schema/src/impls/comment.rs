@@ -3,6 +3,8 @@ use crate::{
   schema::comment::dsl::{ap_id, comment, content, creator_id, deleted, path, removed, updated},
   source::comment::{
     Comment,
+    CommentEdit,
+    CommentEditForm,
     CommentInsertForm,
     CommentLike,
     CommentLikeForm,
@@ -28,8 +30,17 @@ impl Comment {
     pool: &DbPool,
     for_creator_id: PersonId,
   ) -> Result<Vec<Self>, Error> {
+    use crate::schema::{comment as comment_table, comment_edit};
     let conn = &mut get_conn(pool).await?;
 
+    // Previous versions would keep the content around
+    let creator_comments = comment
+      .filter(creator_id.eq(for_creator_id))
+      .select(comment_table::id);
+    diesel::delete(comment_edit::table.filter(comment_edit::comment_id.eq_any(creator_comments)))
+      .execute(conn)
+      .await?;
+
     diesel::update(comment.filter(creator_id.eq(for_creator_id)))
       .set((
         content.eq(DELETED_REPLACEMENT_TEXT),
@@ -214,6 +225,21 @@ impl Likeable for CommentLike {
   }
 }
 
+impl CommentSaved {
+  /// Lists the activitypub ids of all comments saved by the given person.
+  pub async fn list_ap_ids(pool: &DbPool, for_person_id: PersonId) -> Result<Vec<DbUrl>, Error> {
+    use crate::schema::{comment, comment_saved};
+    let conn = &mut get_conn(pool).await?;
+    comment_saved::table
+      .inner_join(comment::table)
+      .filter(comment_saved::person_id.eq(for_person_id))
+      .order_by(comment_saved::published)
+      .select(comment::ap_id)
+      .load::<DbUrl>(conn)
+      .await
+  }
+}
+
 #[async_trait]
 impl Saveable for CommentSaved {
   type Form = CommentSavedForm;
@@ -241,6 +267,45 @@ impl Saveable for CommentSaved {
   }
 }
 
+impl CommentEdit {
+  /// Stores the previous version of an updated comment, if its content was changed.
+  pub async fn create_for_update(
+    pool: &DbPool,
+    old_comment: &Comment,
+    new_comment: &Comment,
+  ) -> Result<Option<Self>, Error> {
+    use crate::schema::comment_edit;
+    if old_comment.content == new_comment.content {
+      return Ok(None);
+    }
+    let form = CommentEditForm {
+      comment_id: old_comment.id,
+      content: old_comment.content.clone(),
+      published: old_comment.updated.unwrap_or(old_comment.published),
+    };
+    let conn = &mut get_conn(pool).await?;
+    insert_into(comment_edit::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .map(Some)
+  }
+
+  /// Lists the previous versions of the comment, oldest first.
+  pub async fn list_for_comment(
+    pool: &DbPool,
+    for_comment_id: CommentId,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::comment_edit;
+    let conn = &mut get_conn(pool).await?;
+    comment_edit::table
+      .filter(comment_edit::comment_id.eq(for_comment_id))
+      .order_by(comment_edit::id)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -248,6 +313,7 @@ mod tests {
     source::{
       comment::{
         Comment,
+        CommentEdit,
         CommentInsertForm,
         CommentLike,
         CommentLikeForm,
@@ -361,6 +427,10 @@ mod tests {
     };
 
     let inserted_comment_saved = CommentSaved::save(pool, &comment_saved_form).await.unwrap();
+    let saved_ap_ids = CommentSaved::list_ap_ids(pool, inserted_person.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![inserted_comment.ap_id.clone()], saved_ap_ids);
 
     let expected_comment_saved = CommentSaved {
       id: inserted_comment_saved.id,
@@ -378,6 +448,18 @@ mod tests {
       .unwrap();
 
     let read_comment = Comment::read(pool, inserted_comment.id).await.unwrap();
+    let edited_comment = Comment {
+      content: "An edited comment".into(),
+      ..updated_comment.clone()
+    };
+    CommentEdit::create_for_update(pool, &updated_comment, &edited_comment)
+      .await
+      .unwrap();
+    let edits = CommentEdit::list_for_comment(pool, inserted_comment.id)
+      .await
+      .unwrap();
+    assert_eq!(1, edits.len());
+    assert_eq!("A test comment", edits[0].content);
     let like_removed = CommentLike::remove(pool, inserted_person.id, inserted_comment.id)
       .await
       .unwrap();
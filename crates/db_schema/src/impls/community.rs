@@ -228,6 +228,55 @@ impl CommunityFollower {
       None => SubscribedType::NotSubscribed,
     }
   }
+
+  /// Moves all follows of local communities from one person to another, used when an account
+  /// moves. Returns the number of transferred follows.
+  pub async fn transfer_local_follows(
+    pool: &DbPool,
+    from_person_id: PersonId,
+    to_person_id: PersonId,
+  ) -> Result<usize, Error> {
+    use crate::schema::community_follower;
+    let conn = &mut get_conn(pool).await?;
+
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let community_ids = community_follower::table
+            .inner_join(community::table)
+            .filter(community_follower::person_id.eq(from_person_id))
+            .filter(community_follower::pending.eq(false))
+            .filter(community::local.eq(true))
+            .select(community_follower::community_id)
+            .load::<CommunityId>(conn)
+            .await?;
+
+          let forms = community_ids
+            .iter()
+            .map(|community_id| CommunityFollowerForm {
+              community_id: *community_id,
+              person_id: to_person_id,
+              pending: false,
+            })
+            .collect::<Vec<_>>();
+          insert_into(community_follower::table)
+            .values(&forms)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+
+          diesel::delete(
+            community_follower::table
+              .filter(community_follower::person_id.eq(from_person_id))
+              .filter(community_follower::community_id.eq_any(&community_ids)),
+          )
+          .execute(conn)
+          .await
+        }) as _
+      })
+      .await
+  }
 }
 
 #[async_trait]
@@ -490,4 +539,70 @@ mod tests {
     // assert_eq!(2, loaded_count);
     assert_eq!(1, num_deleted);
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_transfer_local_follows() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let old_person_form = PersonInsertForm::builder()
+      .name("moving_old".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let old_person = Person::create(pool, &old_person_form).await.unwrap();
+    let new_person_form = PersonInsertForm::builder()
+      .name("moving_new".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let new_person = Person::create(pool, &new_person_form).await.unwrap();
+
+    let community_form = CommunityInsertForm::builder()
+      .name("moving_community".into())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await.unwrap();
+
+    let follower_form = CommunityFollowerForm {
+      community_id: community.id,
+      person_id: old_person.id,
+      pending: false,
+    };
+    CommunityFollower::follow(pool, &follower_form)
+      .await
+      .unwrap();
+
+    let transferred = CommunityFollower::transfer_local_follows(pool, old_person.id, new_person.id)
+      .await
+      .unwrap();
+    assert_eq!(1, transferred);
+
+    // The old account doesn't follow anymore, so unfollowing it has no effect
+    let unfollow_old = CommunityFollower::unfollow(pool, &follower_form)
+      .await
+      .unwrap();
+    assert_eq!(0, unfollow_old);
+    let unfollow_new = CommunityFollower::unfollow(
+      pool,
+      &CommunityFollowerForm {
+        person_id: new_person.id,
+        ..follower_form
+      },
+    )
+    .await
+    .unwrap();
+    assert_eq!(1, unfollow_new);
+
+    Community::delete(pool, community.id).await.unwrap();
+    Person::delete(pool, old_person.id).await.unwrap();
+    Person::delete(pool, new_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
 }
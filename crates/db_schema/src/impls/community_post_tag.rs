@@ -0,0 +1,365 @@
+use crate::{
+  newtypes::{CommunityId, CommunityPostTagId, DbUrl, PostId},
+  schema::{community_post_tag, post_tag},
+  source::community_post_tag::{
+    CommunityPostTag,
+    CommunityPostTagInsertForm,
+    CommunityPostTagUpdateForm,
+    PostTag,
+    PostTagForm,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait]
+impl Crud for CommunityPostTag {
+  type InsertForm = CommunityPostTagInsertForm;
+  type UpdateForm = CommunityPostTagUpdateForm;
+  type IdType = CommunityPostTagId;
+  async fn read(pool: &DbPool, tag_id: CommunityPostTagId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_post_tag::table
+      .find(tag_id)
+      .first::<Self>(conn)
+      .await
+  }
+
+  async fn create(pool: &DbPool, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_post_tag::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &DbPool,
+    tag_id: CommunityPostTagId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(community_post_tag::table.find(tag_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn delete(pool: &DbPool, tag_id: CommunityPostTagId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(community_post_tag::table.find(tag_id))
+      .execute(conn)
+      .await
+  }
+}
+
+impl CommunityPostTag {
+  /// Inserts a tag of a remote community received over federation, or updates its name if it is
+  /// already known. A tag of another community with the same id is never changed, and results in
+  /// an error instead.
+  pub async fn upsert(pool: &DbPool, form: &CommunityPostTagInsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let form = form.clone();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let existing = match &form.ap_id {
+            Some(ap_id) => community_post_tag::table
+              .filter(community_post_tag::ap_id.eq(ap_id))
+              .first::<Self>(conn)
+              .await
+              .optional()?,
+            None => None,
+          };
+          match existing {
+            Some(existing) if existing.community_id != form.community_id => Err(Error::NotFound),
+            Some(existing) => {
+              diesel::update(community_post_tag::table.find(existing.id))
+                .set((
+                  community_post_tag::name.eq(form.name),
+                  community_post_tag::updated.eq(form.updated),
+                ))
+                .get_result::<Self>(conn)
+                .await
+            }
+            None => {
+              insert_into(community_post_tag::table)
+                .values(form)
+                .get_result::<Self>(conn)
+                .await
+            }
+          }
+        }) as _
+      })
+      .await
+  }
+
+  /// Reads a tag of the given community by its federated id.
+  pub async fn read_from_apub_id(
+    pool: &DbPool,
+    ap_id: &DbUrl,
+    for_community_id: CommunityId,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_post_tag::table
+      .filter(community_post_tag::ap_id.eq(ap_id))
+      .filter(community_post_tag::community_id.eq(for_community_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn list_for_community(
+    pool: &DbPool,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_post_tag::table
+      .filter(community_post_tag::community_id.eq(for_community_id))
+      .order_by(community_post_tag::name)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Posts can only be tagged with the tags of their own community.
+  pub async fn check_community(
+    pool: &DbPool,
+    tag_ids: &[CommunityPostTagId],
+    for_community_id: CommunityId,
+  ) -> Result<(), LemmyError> {
+    let conn = &mut get_conn(pool).await?;
+    let count: i64 = community_post_tag::table
+      .filter(community_post_tag::id.eq_any(tag_ids))
+      .filter(community_post_tag::community_id.eq(for_community_id))
+      .count()
+      .get_result(conn)
+      .await?;
+    if count == tag_ids.len() as i64 {
+      Ok(())
+    } else {
+      Err(LemmyError::from_message("post_tag_not_in_community"))
+    }
+  }
+
+  pub async fn list_for_post(pool: &DbPool, for_post_id: PostId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_tag::table
+      .inner_join(community_post_tag::table)
+      .filter(post_tag::post_id.eq(for_post_id))
+      .select(community_post_tag::all_columns)
+      .order_by(community_post_tag::name)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Reads the tags of multiple posts at once, for post listings.
+  pub async fn list_for_posts(
+    pool: &DbPool,
+    post_ids: &[PostId],
+  ) -> Result<Vec<(PostId, Self)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_tag::table
+      .inner_join(community_post_tag::table)
+      .filter(post_tag::post_id.eq_any(post_ids))
+      .select((post_tag::post_id, community_post_tag::all_columns))
+      .order_by(community_post_tag::name)
+      .load::<(PostId, Self)>(conn)
+      .await
+  }
+}
+
+impl PostTag {
+  /// Replaces the tags of the post.
+  pub async fn update(
+    pool: &DbPool,
+    for_post_id: PostId,
+    tag_ids: Vec<CommunityPostTagId>,
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          diesel::delete(post_tag::table.filter(post_tag::post_id.eq(for_post_id)))
+            .execute(conn)
+            .await?;
+
+          let forms: Vec<PostTagForm> = tag_ids
+            .into_iter()
+            .map(|tag_id| PostTagForm {
+              post_id: for_post_id,
+              tag_id,
+            })
+            .collect();
+          if !forms.is_empty() {
+            insert_into(post_tag::table)
+              .values(forms)
+              .on_conflict_do_nothing()
+              .execute(conn)
+              .await?;
+          }
+          Ok(())
+        }) as _
+      })
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    newtypes::DbUrl,
+    source::{
+      community::{Community, CommunityInsertForm},
+      community_post_tag::{CommunityPostTag, CommunityPostTagInsertForm, PostTag},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_post_tags() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("post_tag_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("post_tag_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+    let other_community = CommunityInsertForm::builder()
+      .name("post_tag_other_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let other_community = Community::create(pool, &other_community).await.unwrap();
+
+    let tag_form = |community_id, name: &str| CommunityPostTagInsertForm {
+      ap_id: None,
+      community_id,
+      name: name.to_string(),
+      updated: None,
+    };
+    let question = CommunityPostTag::create(pool, &tag_form(inserted_community.id, "Question"))
+      .await
+      .unwrap();
+    let meta = CommunityPostTag::create(pool, &tag_form(inserted_community.id, "Meta"))
+      .await
+      .unwrap();
+    let other = CommunityPostTag::create(pool, &tag_form(other_community.id, "Question"))
+      .await
+      .unwrap();
+    // Tag names are unique per community
+    assert!(
+      CommunityPostTag::create(pool, &tag_form(inserted_community.id, "Meta"))
+        .await
+        .is_err()
+    );
+
+    let tags = CommunityPostTag::list_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![meta.clone(), question.clone()], tags);
+
+    assert!(CommunityPostTag::check_community(
+      pool,
+      &[question.id, meta.id],
+      inserted_community.id
+    )
+    .await
+    .is_ok());
+    assert!(CommunityPostTag::check_community(
+      pool,
+      &[question.id, other.id],
+      inserted_community.id
+    )
+    .await
+    .is_err());
+
+    let new_post = PostInsertForm::builder()
+      .name("A post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    PostTag::update(pool, inserted_post.id, vec![question.id, meta.id])
+      .await
+      .unwrap();
+    PostTag::update(pool, inserted_post.id, vec![question.id])
+      .await
+      .unwrap();
+    let post_tags = CommunityPostTag::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![question.clone()], post_tags);
+
+    // Tags received over federation are updated, but never moved to another community
+    let ap_id: DbUrl = Url::parse("https://remote.example/c/tags/1")
+      .unwrap()
+      .into();
+    let remote_form = CommunityPostTagInsertForm {
+      ap_id: Some(ap_id.clone()),
+      ..tag_form(other_community.id, "Remote")
+    };
+    let remote = CommunityPostTag::upsert(pool, &remote_form).await.unwrap();
+    let renamed_form = CommunityPostTagInsertForm {
+      name: "Renamed".to_string(),
+      ..remote_form.clone()
+    };
+    let renamed = CommunityPostTag::upsert(pool, &renamed_form).await.unwrap();
+    assert_eq!(remote.id, renamed.id);
+    assert_eq!("Renamed", renamed.name);
+    let moved_form = CommunityPostTagInsertForm {
+      community_id: inserted_community.id,
+      ..remote_form
+    };
+    assert!(CommunityPostTag::upsert(pool, &moved_form).await.is_err());
+    let read = CommunityPostTag::read_from_apub_id(pool, &ap_id, other_community.id)
+      .await
+      .unwrap();
+    assert_eq!(Some(renamed), read);
+    let read = CommunityPostTag::read_from_apub_id(pool, &ap_id, inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(None, read);
+
+    // Deleting a tag removes it from posts
+    CommunityPostTag::delete(pool, question.id).await.unwrap();
+    let post_tags = CommunityPostTag::list_for_posts(pool, &[inserted_post.id])
+      .await
+      .unwrap();
+    assert!(post_tags.is_empty());
+
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Community::delete(pool, other_community.id).await.unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,376 @@
+use crate::{
+  newtypes::{CommunityId, CommunityRecurringPostId, DbUrl, PostId},
+  schema::{community_recurring_post, mod_feature_post, post, post_like},
+  source::{
+    community_recurring_post::{
+      CommunityRecurringPost,
+      CommunityRecurringPostInsertForm,
+      CommunityRecurringPostUpdateForm,
+    },
+    moderator::ModFeaturePostForm,
+    post::{Post, PostInsertForm, PostLikeForm},
+  },
+  traits::Crud,
+  utils::{get_conn, naive_now, DbPool},
+};
+use chrono::NaiveDateTime;
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use url::ParseError;
+
+#[async_trait]
+impl Crud for CommunityRecurringPost {
+  type InsertForm = CommunityRecurringPostInsertForm;
+  type UpdateForm = CommunityRecurringPostUpdateForm;
+  type IdType = CommunityRecurringPostId;
+  async fn read(pool: &DbPool, recurring_post_id: CommunityRecurringPostId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_recurring_post::table
+      .find(recurring_post_id)
+      .first::<Self>(conn)
+      .await
+  }
+
+  async fn create(pool: &DbPool, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_recurring_post::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &DbPool,
+    recurring_post_id: CommunityRecurringPostId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(community_recurring_post::table.find(recurring_post_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn delete(
+    pool: &DbPool,
+    recurring_post_id: CommunityRecurringPostId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(community_recurring_post::table.find(recurring_post_id))
+      .execute(conn)
+      .await
+  }
+}
+
+impl CommunityRecurringPost {
+  pub async fn list_for_community(
+    pool: &DbPool,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_recurring_post::table
+      .filter(community_recurring_post::community_id.eq(for_community_id))
+      .order_by(community_recurring_post::id)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// The templates whose next post should be created now.
+  pub async fn list_due(pool: &DbPool) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_recurring_post::table
+      .filter(community_recurring_post::next_publish_time.le(naive_now()))
+      .order_by(community_recurring_post::next_publish_time)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Creates the next post of the template, the creator's like of it and the modlog entry if it
+  /// is featured, and advances the template like [CommunityRecurringPost::advance]. This happens in
+  /// one transaction, so that a failure can't leave a post behind without advancing the template.
+  /// The activitypub id of the post is built from its id by `ap_id`.
+  ///
+  /// Returns the new post, and the previous post if it was unfeatured.
+  pub async fn create_post<F>(
+    pool: &DbPool,
+    recurring_post: &Self,
+    post_form: &PostInsertForm,
+    ap_id: F,
+    next_publish_time: Option<NaiveDateTime>,
+  ) -> Result<(Post, Option<Post>), Error>
+  where
+    F: FnOnce(PostId) -> Result<DbUrl, ParseError> + Send + 'static,
+  {
+    let conn = &mut get_conn(pool).await?;
+    let recurring_post = recurring_post.clone();
+    let post_form = post_form.clone();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let inserted_post = insert_into(post::table)
+            .values(post_form)
+            .get_result::<Post>(conn)
+            .await?;
+          let ap_id = ap_id(inserted_post.id).map_err(|e| Error::QueryBuilderError(e.into()))?;
+          let post = diesel::update(post::table.find(inserted_post.id))
+            .set(post::ap_id.eq(ap_id))
+            .get_result::<Post>(conn)
+            .await?;
+
+          if post.featured_community {
+            let form = ModFeaturePostForm {
+              mod_person_id: recurring_post.creator_id,
+              post_id: post.id,
+              featured: true,
+              is_featured_community: true,
+            };
+            insert_into(mod_feature_post::table)
+              .values(form)
+              .execute(conn)
+              .await?;
+          }
+
+          // Like other posts, the creator likes it by default
+          let like_form = PostLikeForm {
+            post_id: post.id,
+            person_id: recurring_post.creator_id,
+            score: 1,
+          };
+          insert_into(post_like::table)
+            .values(like_form)
+            .execute(conn)
+            .await?;
+
+          let unfeatured_post =
+            Self::advance_conn(conn, &recurring_post, Some(post.id), next_publish_time).await?;
+          Ok((post, unfeatured_post))
+        }) as _
+      })
+      .await
+  }
+
+  /// Remembers the post which was just created from the template, and when the next one is due.
+  /// If the template features its posts, the previous post is unfeatured, and returned so that
+  /// this can be federated. Without a new post only the next publish time is changed.
+  pub async fn advance(
+    pool: &DbPool,
+    recurring_post: &Self,
+    new_post_id: Option<PostId>,
+    next_publish_time: Option<NaiveDateTime>,
+  ) -> Result<Option<Post>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let recurring_post = recurring_post.clone();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          Self::advance_conn(conn, &recurring_post, new_post_id, next_publish_time).await
+        }) as _
+      })
+      .await
+  }
+
+  async fn advance_conn(
+    conn: &mut AsyncPgConnection,
+    recurring_post: &Self,
+    new_post_id: Option<PostId>,
+    next_publish_time: Option<NaiveDateTime>,
+  ) -> Result<Option<Post>, Error> {
+    let mut unfeatured_post = None;
+    if let (true, Some(_), Some(last_post_id)) = (
+      recurring_post.featured,
+      new_post_id,
+      recurring_post.last_post_id,
+    ) {
+      // Mods may have unfeatured or deleted the previous post already
+      let last_post = post::table
+        .find(last_post_id)
+        .first::<Post>(conn)
+        .await
+        .optional()?;
+      if let Some(last_post) = last_post.filter(|p| p.featured_community) {
+        let last_post = diesel::update(post::table.find(last_post.id))
+          .set(post::featured_community.eq(false))
+          .get_result::<Post>(conn)
+          .await?;
+        let form = ModFeaturePostForm {
+          mod_person_id: recurring_post.creator_id,
+          post_id: last_post.id,
+          featured: false,
+          is_featured_community: true,
+        };
+        insert_into(mod_feature_post::table)
+          .values(form)
+          .execute(conn)
+          .await?;
+        unfeatured_post = Some(last_post);
+      }
+    }
+
+    let form = CommunityRecurringPostUpdateForm {
+      last_post_id: new_post_id.map(Some),
+      next_publish_time: Some(next_publish_time),
+      ..Default::default()
+    };
+    diesel::update(community_recurring_post::table.find(recurring_post.id))
+      .set(form)
+      .execute(conn)
+      .await?;
+    Ok(unfeatured_post)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      community_recurring_post::{
+        CommunityRecurringPost,
+        CommunityRecurringPostInsertForm,
+        CommunityRecurringPostUpdateForm,
+      },
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::{build_db_pool_for_tests, naive_now},
+  };
+  use chrono::Duration;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_recurring_posts() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("recurring_post_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("recurring_post_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let form = CommunityRecurringPostInsertForm {
+      community_id: inserted_community.id,
+      creator_id: inserted_person.id,
+      name: "Weekly megathread {date}".to_string(),
+      body: None,
+      schedule: "0 9 * * 1".to_string(),
+      featured: true,
+      next_publish_time: Some(naive_now() + Duration::days(1)),
+    };
+    let inserted = CommunityRecurringPost::create(pool, &form).await.unwrap();
+    let listed = CommunityRecurringPost::list_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![inserted.clone()], listed);
+
+    // Only templates whose publish time has passed are due
+    assert!(CommunityRecurringPost::list_due(pool)
+      .await
+      .unwrap()
+      .is_empty());
+    let update_form = CommunityRecurringPostUpdateForm {
+      next_publish_time: Some(Some(naive_now() - Duration::minutes(1))),
+      ..Default::default()
+    };
+    let updated = CommunityRecurringPost::update(pool, inserted.id, &update_form)
+      .await
+      .unwrap();
+    let due = CommunityRecurringPost::list_due(pool).await.unwrap();
+    assert_eq!(vec![updated.clone()], due);
+
+    // Publishing the first post only advances the schedule
+    let post_form = |name: &str| {
+      PostInsertForm::builder()
+        .name(name.to_string())
+        .creator_id(inserted_person.id)
+        .community_id(inserted_community.id)
+        .featured_community(Some(true))
+        .build()
+    };
+    let first_post = Post::create(pool, &post_form("first")).await.unwrap();
+    let next_publish_time = Some(naive_now() + Duration::days(7));
+    let unfeatured =
+      CommunityRecurringPost::advance(pool, &updated, Some(first_post.id), next_publish_time)
+        .await
+        .unwrap();
+    assert_eq!(None, unfeatured);
+    let advanced = CommunityRecurringPost::read(pool, inserted.id)
+      .await
+      .unwrap();
+    assert_eq!(Some(first_post.id), advanced.last_post_id);
+    assert_eq!(next_publish_time, advanced.next_publish_time);
+    assert!(CommunityRecurringPost::list_due(pool)
+      .await
+      .unwrap()
+      .is_empty());
+
+    // The second post replaces the first one as featured post
+    let (second_post, unfeatured) = CommunityRecurringPost::create_post(
+      pool,
+      &advanced,
+      &post_form("second"),
+      |id| Url::parse(&format!("https://my_domain.tld/post/{id}")).map(Into::into),
+      next_publish_time,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+      "https://my_domain.tld/post/".to_string() + &second_post.id.to_string(),
+      second_post.ap_id.to_string()
+    );
+    assert_eq!(Some(first_post.id), unfeatured.map(|p| p.id));
+    assert!(
+      !Post::read(pool, first_post.id)
+        .await
+        .unwrap()
+        .featured_community
+    );
+    assert!(
+      Post::read(pool, second_post.id)
+        .await
+        .unwrap()
+        .featured_community
+    );
+    let advanced = CommunityRecurringPost::read(pool, inserted.id)
+      .await
+      .unwrap();
+    assert_eq!(Some(second_post.id), advanced.last_post_id);
+
+    // Skipping a post keeps the last one
+    CommunityRecurringPost::advance(pool, &advanced, None, None)
+      .await
+      .unwrap();
+    let skipped = CommunityRecurringPost::read(pool, inserted.id)
+      .await
+      .unwrap();
+    assert_eq!(Some(second_post.id), skipped.last_post_id);
+    assert_eq!(None, skipped.next_publish_time);
+
+    CommunityRecurringPost::delete(pool, inserted.id)
+      .await
+      .unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
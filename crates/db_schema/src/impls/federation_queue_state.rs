@@ -0,0 +1,169 @@
+use crate::{
+  newtypes::InstanceId,
+  schema::federation_queue_state::dsl::{federation_queue_state, instance_id},
+  source::federation_queue_state::{FederationQueueState, FederationQueueStateForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl FederationQueueState {
+  /// Reads the delivery state of the instance, creating it if necessary.
+  pub async fn read_or_create(pool: &DbPool, for_instance_id: InstanceId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_queue_state)
+      .values(instance_id.eq(for_instance_id))
+      .on_conflict(instance_id)
+      .do_nothing()
+      .execute(conn)
+      .await?;
+    federation_queue_state
+      .filter(instance_id.eq(for_instance_id))
+      .first::<Self>(conn)
+      .await
+  }
+
+  pub async fn upsert(pool: &DbPool, form: &FederationQueueStateForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_queue_state)
+      .values(form)
+      .on_conflict(instance_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    newtypes::DbUrl,
+    source::{
+      activity::{Activity, ActivityInsertForm, ActivitySendInbox, ActivitySendInboxForm},
+      federation_queue_state::{FederationQueueState, FederationQueueStateForm},
+      instance::Instance,
+    },
+    traits::Crud,
+    utils::{build_db_pool_for_tests, naive_now},
+  };
+  use serde_json::json;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_send_queue() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "queue_target.tld".to_string())
+      .await
+      .unwrap();
+
+    let mut inserted_activities = vec![];
+    for i in 0..2 {
+      let ap_id: DbUrl = Url::parse(&format!("https://my_domain.tld/activities/like/{i}"))
+        .unwrap()
+        .into();
+      let form = ActivityInsertForm {
+        data: json!({ "id": ap_id.clone(), "actor": "https://my_domain.tld/u/queue_sender" }),
+        local: Some(true),
+        updated: None,
+        ap_id,
+        sensitive: Some(false),
+      };
+      inserted_activities.push(Activity::create(pool, &form).await.unwrap());
+    }
+    let inbox = |path: &str| -> DbUrl {
+      Url::parse(&format!("https://queue_target.tld/{path}"))
+        .unwrap()
+        .into()
+    };
+    let forms = [
+      (&inserted_activities[0], inbox("inbox")),
+      (&inserted_activities[0], inbox("u/alice/inbox")),
+      (&inserted_activities[1], inbox("inbox")),
+    ]
+    .map(|(a, inbox)| ActivitySendInboxForm {
+      activity_id: a.id,
+      instance_id: inserted_instance.id,
+      inbox,
+    });
+    let num_inserted = ActivitySendInbox::create_many(pool, &forms).await.unwrap();
+    assert_eq!(3, num_inserted);
+
+    let pending_instances = ActivitySendInbox::pending_instances(pool).await.unwrap();
+    assert_eq!(vec![inserted_instance.id], pending_instances);
+
+    let state = FederationQueueState::read_or_create(pool, inserted_instance.id)
+      .await
+      .unwrap();
+    assert_eq!(0, state.fail_count);
+
+    // Inboxes are grouped by activity, limit applies to activities
+    let pending = ActivitySendInbox::read_pending(pool, inserted_instance.id, 1)
+      .await
+      .unwrap();
+    assert_eq!(1, pending.len());
+    assert_eq!(inserted_activities[0].id, pending[0].0.id);
+    assert_eq!(vec![inbox("inbox"), inbox("u/alice/inbox")], pending[0].1);
+
+    // Deliver the first activity
+    for i in &pending[0].1 {
+      ActivitySendInbox::delete_for_inbox(pool, inserted_activities[0].id, i)
+        .await
+        .unwrap();
+    }
+    let form = FederationQueueStateForm {
+      instance_id: inserted_instance.id,
+      fail_count: 0,
+      last_retry: Some(naive_now()),
+      dead_since: None,
+    };
+    let updated_state = FederationQueueState::upsert(pool, &form).await.unwrap();
+    assert_eq!(state.id, updated_state.id);
+
+    let pending = ActivitySendInbox::read_pending(pool, inserted_instance.id, 10)
+      .await
+      .unwrap();
+    assert_eq!(1, pending.len());
+    assert_eq!(inserted_activities[1].id, pending[0].0.id);
+
+    // An activity which is queued late is still delivered, before the newer ones
+    let late_form = ActivitySendInboxForm {
+      activity_id: inserted_activities[0].id,
+      instance_id: inserted_instance.id,
+      inbox: inbox("u/bob/inbox"),
+    };
+    ActivitySendInbox::create_many(pool, &[late_form])
+      .await
+      .unwrap();
+    let pending = ActivitySendInbox::read_pending(pool, inserted_instance.id, 10)
+      .await
+      .unwrap();
+    assert_eq!(2, pending.len());
+    assert_eq!(inserted_activities[0].id, pending[0].0.id);
+    assert_eq!(vec![inbox("u/bob/inbox")], pending[0].1);
+
+    let num_deleted =
+      ActivitySendInbox::delete_for_activity(pool, inserted_activities[0].id, inserted_instance.id)
+        .await
+        .unwrap();
+    assert_eq!(1, num_deleted);
+
+    // Dead instances don't keep anything queued
+    let num_deleted = ActivitySendInbox::delete_for_instance(pool, inserted_instance.id)
+      .await
+      .unwrap();
+    assert_eq!(1, num_deleted);
+
+    for a in inserted_activities {
+      Activity::delete(pool, a.id).await.unwrap();
+    }
+    assert!(ActivitySendInbox::pending_instances(pool)
+      .await
+      .unwrap()
+      .is_empty());
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
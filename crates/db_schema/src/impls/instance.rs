@@ -46,6 +46,13 @@ impl Instance {
     let conn = &mut get_conn(pool).await?;
     Self::read_or_create_with_conn(conn, domain).await
   }
+  pub async fn read_from_domain(pool: &DbPool, domain_: &str) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    instance::table
+      .filter(instance::domain.eq(domain_))
+      .first::<Self>(conn)
+      .await
+  }
   pub async fn delete(pool: &DbPool, instance_id: InstanceId) -> Result<usize, Error> {
     let conn = &mut get_conn(pool).await?;
     diesel::delete(instance::table.find(instance_id))
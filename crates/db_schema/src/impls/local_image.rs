@@ -0,0 +1,108 @@
+use crate::{
+  newtypes::PersonId,
+  schema::{local_image, local_user},
+  source::local_image::{LocalImage, LocalImageForm},
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl LocalImage {
+  pub async fn create(pool: &DbPool, form: &LocalImageForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(local_image::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Images which the person uploaded, oldest first. Empty for remote persons.
+  pub async fn list_for_person(
+    pool: &DbPool,
+    for_person_id: PersonId,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    local_image::table
+      .inner_join(local_user::table)
+      .filter(local_user::person_id.eq(for_person_id))
+      .select(local_image::all_columns)
+      .order_by(local_image::id)
+      .limit(limit)
+      .offset(offset)
+      .load::<Self>(conn)
+      .await
+  }
+
+  pub async fn delete_by_alias(pool: &DbPool, alias: &str) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(local_image::table.filter(local_image::pictrs_alias.eq(alias)))
+      .execute(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      instance::Instance,
+      local_image::{LocalImage, LocalImageForm},
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_local_images() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("local_image_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("123456".to_string())
+      .build();
+    let inserted_local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+
+    let form = LocalImageForm {
+      local_user_id: inserted_local_user.id,
+      pictrs_alias: "a1b2c3.png".to_string(),
+      pictrs_delete_token: "d4e5f6".to_string(),
+      flag_reason: None,
+    };
+    let inserted_image = LocalImage::create(pool, &form).await.unwrap();
+
+    let images = LocalImage::list_for_person(pool, inserted_person.id, None, None)
+      .await
+      .unwrap();
+    assert_eq!(vec![inserted_image], images);
+
+    let deleted = LocalImage::delete_by_alias(pool, "a1b2c3.png")
+      .await
+      .unwrap();
+    assert_eq!(1, deleted);
+    let images = LocalImage::list_for_person(pool, inserted_person.id, None, None)
+      .await
+      .unwrap();
+    assert!(images.is_empty());
+
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
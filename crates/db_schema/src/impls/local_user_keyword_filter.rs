@@ -0,0 +1,217 @@
+use crate::{
+  newtypes::{KeywordFilterId, LocalUserId},
+  schema::local_user_keyword_filter::dsl::{keyword, local_user_id, local_user_keyword_filter},
+  source::{
+    comment::Comment,
+    local_user_keyword_filter::{
+      LocalUserKeywordFilter,
+      LocalUserKeywordFilterInsertForm,
+      LocalUserKeywordFilterUpdateForm,
+    },
+    post::Post,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+#[async_trait]
+impl Crud for LocalUserKeywordFilter {
+  type InsertForm = LocalUserKeywordFilterInsertForm;
+  type UpdateForm = LocalUserKeywordFilterUpdateForm;
+  type IdType = KeywordFilterId;
+  async fn read(pool: &DbPool, filter_id: KeywordFilterId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    local_user_keyword_filter
+      .find(filter_id)
+      .first::<Self>(conn)
+      .await
+  }
+
+  async fn create(pool: &DbPool, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(local_user_keyword_filter)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &DbPool,
+    filter_id: KeywordFilterId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(local_user_keyword_filter.find(filter_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn delete(pool: &DbPool, filter_id: KeywordFilterId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(local_user_keyword_filter.find(filter_id))
+      .execute(conn)
+      .await
+  }
+}
+
+impl LocalUserKeywordFilter {
+  pub async fn list(pool: &DbPool, for_local_user_id: LocalUserId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    local_user_keyword_filter
+      .filter(local_user_id.eq(for_local_user_id))
+      .order_by(keyword)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Pattern for `ILIKE`, which matches text containing the keyword.
+  pub fn like_pattern(&self) -> String {
+    format!("%{}%", escape_like(&self.keyword))
+  }
+
+  /// Pattern for `LIKE`, which matches subdomains of the keyword.
+  pub fn subdomain_like_pattern(&self) -> String {
+    format!("%.{}", escape_like(&self.keyword))
+  }
+
+  /// Same check as the filter in post listings, for posts which are already loaded.
+  pub fn matches_post(&self, post: &Post) -> bool {
+    let domain_matches = post
+      .url
+      .as_ref()
+      .and_then(|url| url.inner().domain())
+      .map(|domain| self.matches_domain(&domain.to_lowercase()))
+      .unwrap_or(false);
+    self.matches_text(&post.name)
+      || post
+        .body
+        .as_deref()
+        .map(|b| self.matches_text(b))
+        .unwrap_or(false)
+      || domain_matches
+  }
+
+  /// Same check as the filter in comment listings, for comments which are already loaded.
+  pub fn matches_comment(&self, comment: &Comment) -> bool {
+    self.matches_text(&comment.content)
+  }
+
+  fn matches_text(&self, text: &str) -> bool {
+    text.to_lowercase().contains(&self.keyword)
+  }
+
+  fn matches_domain(&self, domain: &str) -> bool {
+    domain == self.keyword || domain.ends_with(&format!(".{}", self.keyword))
+  }
+}
+
+fn escape_like(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace('%', "\\%")
+    .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      local_user_keyword_filter::{
+        LocalUserKeywordFilter,
+        LocalUserKeywordFilterInsertForm,
+        LocalUserKeywordFilterUpdateForm,
+      },
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+    KeywordFilterAction,
+  };
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("keyword_filter_user".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_local_user = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("pass".to_string())
+      .build();
+    let inserted_local_user = LocalUser::create(pool, &new_local_user).await.unwrap();
+
+    let form = LocalUserKeywordFilterInsertForm {
+      local_user_id: inserted_local_user.id,
+      keyword: "example.com".to_string(),
+      action: None,
+    };
+    let inserted_filter = LocalUserKeywordFilter::create(pool, &form).await.unwrap();
+    assert_eq!(KeywordFilterAction::Hide, inserted_filter.action);
+    // Each keyword can only be filtered once per user
+    assert!(LocalUserKeywordFilter::create(pool, &form).await.is_err());
+
+    let update_form = LocalUserKeywordFilterUpdateForm {
+      keyword: None,
+      action: Some(KeywordFilterAction::Collapse),
+      updated: None,
+    };
+    let updated_filter = LocalUserKeywordFilter::update(pool, inserted_filter.id, &update_form)
+      .await
+      .unwrap();
+    assert_eq!(KeywordFilterAction::Collapse, updated_filter.action);
+
+    let filters = LocalUserKeywordFilter::list(pool, inserted_local_user.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![updated_filter.clone()], filters);
+
+    let new_community = CommunityInsertForm::builder()
+      .name("keyword_filter_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+    let new_post = PostInsertForm::builder()
+      .name("A post".into())
+      .url(Some(
+        Url::parse("https://www.Example.com/page").unwrap().into(),
+      ))
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+    assert!(updated_filter.matches_post(&inserted_post));
+
+    let num_deleted = LocalUserKeywordFilter::delete(pool, updated_filter.id)
+      .await
+      .unwrap();
+    assert_eq!(1, num_deleted);
+
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
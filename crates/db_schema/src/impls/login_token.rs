@@ -0,0 +1,175 @@
+use crate::{
+  impls::password_reset_request::bytes_to_hex,
+  newtypes::LocalUserId,
+  schema::login_token::dsl::{last_used, login_token, token_encrypted, user_id},
+  source::login_token::{LoginToken, LoginTokenCreateForm},
+  utils::{get_conn, naive_now, DbPool},
+};
+use diesel::{
+  delete,
+  dsl::{exists, insert_into},
+  result::Error,
+  select,
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use sha2::{Digest, Sha256};
+
+impl LoginToken {
+  /// Stores a newly issued jwt. Only a hash of the token is written to the database.
+  pub async fn create(
+    pool: &DbPool,
+    token: &str,
+    for_user_id: LocalUserId,
+    ip: Option<String>,
+    user_agent: Option<String>,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let form = LoginTokenCreateForm {
+      token_encrypted: hash_token(token),
+      user_id: for_user_id,
+      ip,
+      user_agent,
+    };
+    insert_into(login_token)
+      .values(&form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Checks if the given token belongs to this user and hasn't been invalidated. Also updates
+  /// the last used time of the token.
+  pub async fn validate(
+    pool: &DbPool,
+    for_user_id: LocalUserId,
+    token: &str,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let token_hash = hash_token(token);
+    let valid = select(exists(
+      login_token
+        .filter(user_id.eq(for_user_id))
+        .filter(token_encrypted.eq(&token_hash)),
+    ))
+    .get_result::<bool>(conn)
+    .await?;
+    if valid {
+      diesel::update(login_token.filter(token_encrypted.eq(&token_hash)))
+        .set(last_used.eq(naive_now()))
+        .execute(conn)
+        .await?;
+    }
+    Ok(valid)
+  }
+
+  /// Lists all active logins of the given user.
+  pub async fn list(pool: &DbPool, for_user_id: LocalUserId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    login_token
+      .filter(user_id.eq(for_user_id))
+      .order_by(last_used.desc())
+      .get_results::<Self>(conn)
+      .await
+  }
+
+  /// Invalidates a single login token, on logout.
+  pub async fn invalidate(pool: &DbPool, token: &str) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(login_token.filter(token_encrypted.eq(hash_token(token))))
+      .execute(conn)
+      .await
+  }
+
+  /// Invalidates all logins of the given user, eg after a password change.
+  pub async fn invalidate_all(pool: &DbPool, for_user_id: LocalUserId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(login_token.filter(user_id.eq(for_user_id)))
+      .execute(conn)
+      .await
+  }
+}
+
+fn hash_token(token: &str) -> String {
+  let mut hasher = Sha256::new();
+  hasher.update(token);
+  bytes_to_hex(hasher.finalize().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      login_token::LoginToken,
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("login_token_user".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_local_user = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("pass".to_string())
+      .build();
+
+    let inserted_local_user = LocalUser::create(pool, &new_local_user).await.unwrap();
+
+    let token = "my_jwt";
+    let inserted_login_token = LoginToken::create(
+      pool,
+      token,
+      inserted_local_user.id,
+      Some("127.0.0.1".to_string()),
+      Some("Firefox".to_string()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(inserted_local_user.id, inserted_login_token.user_id);
+    assert_ne!(token, inserted_login_token.token_encrypted);
+
+    let valid = LoginToken::validate(pool, inserted_local_user.id, token)
+      .await
+      .unwrap();
+    assert!(valid);
+    let other_token_valid = LoginToken::validate(pool, inserted_local_user.id, "other_jwt")
+      .await
+      .unwrap();
+    assert!(!other_token_valid);
+
+    let logins = LoginToken::list(pool, inserted_local_user.id)
+      .await
+      .unwrap();
+    assert_eq!(1, logins.len());
+
+    let num_invalidated = LoginToken::invalidate(pool, token).await.unwrap();
+    assert_eq!(1, num_invalidated);
+    let valid_after_logout = LoginToken::validate(pool, inserted_local_user.id, token)
+      .await
+      .unwrap();
+    assert!(!valid_after_logout);
+
+    let num_deleted = Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+    assert_eq!(1, num_deleted);
+  }
+}
@@ -0,0 +1,256 @@
+use crate::{
+  newtypes::{CommunityId, ModNoteId, PersonId},
+  schema::{community_moderator, mod_note, person},
+  source::mod_note::{ModNote, ModNoteForm},
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  dsl::{count_star, exists, insert_into},
+  result::Error,
+  select,
+  ExpressionMethods,
+  NullableExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use std::collections::HashMap;
+
+#[async_trait]
+impl Crud for ModNote {
+  type InsertForm = ModNoteForm;
+  type UpdateForm = ModNoteForm;
+  type IdType = ModNoteId;
+  async fn read(pool: &DbPool, note_id: ModNoteId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    mod_note::table.find(note_id).first::<Self>(conn).await
+  }
+
+  async fn create(pool: &DbPool, form: &ModNoteForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(mod_note::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(pool: &DbPool, note_id: ModNoteId, form: &ModNoteForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(mod_note::table.find(note_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn delete(pool: &DbPool, note_id: ModNoteId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(mod_note::table.find(note_id))
+      .execute(conn)
+      .await
+  }
+}
+
+impl ModNote {
+  /// The number of notes about a person which the viewer can see. Admins see all of them, and
+  /// moderators those of the communities they moderate.
+  pub async fn count_visible(
+    pool: &DbPool,
+    for_person_id: PersonId,
+    viewer_id: PersonId,
+    admin: bool,
+  ) -> Result<i64, Error> {
+    let counts = ModNoteCounts::read(pool, vec![for_person_id], viewer_id, admin).await?;
+    Ok(counts.get(for_person_id))
+  }
+
+  /// Same as [ModNote::count_visible], but None if the viewer is neither a moderator of the
+  /// community nor an admin, so that the count is only shown where the viewer acts as one.
+  pub async fn count_for_community(
+    pool: &DbPool,
+    for_person_id: PersonId,
+    for_community_id: CommunityId,
+    viewer_id: PersonId,
+  ) -> Result<Option<i64>, Error> {
+    let (admin, moderator) = {
+      let conn = &mut get_conn(pool).await?;
+      let admin = person::table
+        .find(viewer_id)
+        .select(person::admin)
+        .first::<bool>(conn)
+        .await?;
+      let moderator = select(exists(
+        community_moderator::table
+          .filter(community_moderator::community_id.eq(for_community_id))
+          .filter(community_moderator::person_id.eq(viewer_id)),
+      ))
+      .get_result::<bool>(conn)
+      .await?;
+      (admin, moderator)
+    };
+    if !admin && !moderator {
+      return Ok(None);
+    }
+    Self::count_visible(pool, for_person_id, viewer_id, admin)
+      .await
+      .map(Some)
+  }
+}
+
+/// The number of notes about some people, which the viewer can see.
+pub struct ModNoteCounts(HashMap<PersonId, i64>);
+
+impl ModNoteCounts {
+  /// Admins see all notes, and moderators those of the communities they moderate.
+  pub async fn read(
+    pool: &DbPool,
+    person_ids: Vec<PersonId>,
+    viewer_id: PersonId,
+    admin: bool,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = mod_note::table
+      .filter(mod_note::target_person_id.eq_any(person_ids))
+      .into_boxed();
+    if !admin {
+      let moderated = community_moderator::table
+        .filter(community_moderator::person_id.eq(viewer_id))
+        .select(community_moderator::community_id.nullable());
+      query = query.filter(mod_note::community_id.eq_any(moderated));
+    }
+    let counts = query
+      .group_by(mod_note::target_person_id)
+      .select((mod_note::target_person_id, count_star()))
+      .load::<(PersonId, i64)>(conn)
+      .await?
+      .into_iter()
+      .collect();
+    Ok(ModNoteCounts(counts))
+  }
+
+  pub fn get(&self, person_id: PersonId) -> i64 {
+    self.0.get(&person_id).copied().unwrap_or(0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    impls::mod_note::ModNoteCounts,
+    source::{
+      community::{Community, CommunityInsertForm, CommunityModerator, CommunityModeratorForm},
+      instance::Instance,
+      mod_note::{ModNote, ModNoteForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::{Crud, Joinable},
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_mod_notes() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_mod = PersonInsertForm::builder()
+      .name("mod_note_mod".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_mod = Person::create(pool, &new_mod).await.unwrap();
+
+    let new_target = PersonInsertForm::builder()
+      .name("mod_note_target".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_target = Person::create(pool, &new_target).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("mod_note_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+    let moderator_form = CommunityModeratorForm {
+      community_id: inserted_community.id,
+      person_id: inserted_mod.id,
+    };
+    CommunityModerator::join(pool, &moderator_form)
+      .await
+      .unwrap();
+
+    let community_note_form = ModNoteForm {
+      target_person_id: inserted_target.id,
+      community_id: Some(inserted_community.id),
+      author_id: inserted_mod.id,
+      content: "Warned for spamming".to_string(),
+    };
+    let community_note = ModNote::create(pool, &community_note_form).await.unwrap();
+    let site_note_form = ModNoteForm {
+      community_id: None,
+      content: "Known ban evader".to_string(),
+      ..community_note_form
+    };
+    let site_note = ModNote::create(pool, &site_note_form).await.unwrap();
+
+    let read_note = ModNote::read(pool, community_note.id).await.unwrap();
+    assert_eq!(community_note, read_note);
+    assert_eq!(None, site_note.community_id);
+
+    // Moderators only see the notes of their communities, admins all of them
+    let mod_count = ModNote::count_visible(pool, inserted_target.id, inserted_mod.id, false)
+      .await
+      .unwrap();
+    assert_eq!(1, mod_count);
+    let admin_count = ModNote::count_visible(pool, inserted_target.id, inserted_mod.id, true)
+      .await
+      .unwrap();
+    assert_eq!(2, admin_count);
+
+    let ids = vec![inserted_target.id, inserted_mod.id];
+    let counts = ModNoteCounts::read(pool, ids.clone(), inserted_mod.id, false)
+      .await
+      .unwrap();
+    assert_eq!(1, counts.get(inserted_target.id));
+    let counts = ModNoteCounts::read(pool, ids, inserted_mod.id, true)
+      .await
+      .unwrap();
+    assert_eq!(2, counts.get(inserted_target.id));
+    assert_eq!(0, counts.get(inserted_mod.id));
+
+    // Other users can't see any notes
+    let for_community = ModNote::count_for_community(
+      pool,
+      inserted_target.id,
+      inserted_community.id,
+      inserted_mod.id,
+    )
+    .await
+    .unwrap();
+    assert_eq!(Some(1), for_community);
+    let for_community = ModNote::count_for_community(
+      pool,
+      inserted_mod.id,
+      inserted_community.id,
+      inserted_target.id,
+    )
+    .await
+    .unwrap();
+    assert_eq!(None, for_community);
+
+    ModNote::delete(pool, community_note.id).await.unwrap();
+    ModNote::delete(pool, site_note.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_mod.id).await.unwrap();
+    Person::delete(pool, inserted_target.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
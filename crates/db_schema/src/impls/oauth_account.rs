@@ -0,0 +1,95 @@
+use crate::{
+  schema::oauth_account::dsl::{oauth_account, provider, subject},
+  source::oauth_account::{OAuthAccount, OAuthAccountInsertForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl OAuthAccount {
+  pub async fn create(pool: &DbPool, form: &OAuthAccountInsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(oauth_account)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Finds the link for the given subject of an external provider.
+  pub async fn read_by_subject(
+    pool: &DbPool,
+    for_provider: &str,
+    for_subject: &str,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    oauth_account
+      .filter(provider.eq(for_provider))
+      .filter(subject.eq(for_subject))
+      .first::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      oauth_account::{OAuthAccount, OAuthAccountInsertForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("oauth_account_user".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_local_user = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("pass".to_string())
+      .build();
+
+    let inserted_local_user = LocalUser::create(pool, &new_local_user).await.unwrap();
+
+    let form = OAuthAccountInsertForm {
+      local_user_id: inserted_local_user.id,
+      provider: "keycloak".to_string(),
+      subject: "e3d5a2b1".to_string(),
+    };
+    let inserted_oauth_account = OAuthAccount::create(pool, &form).await.unwrap();
+
+    let read_oauth_account = OAuthAccount::read_by_subject(pool, "keycloak", "e3d5a2b1")
+      .await
+      .unwrap();
+    assert_eq!(inserted_oauth_account, read_oauth_account);
+    assert_eq!(inserted_local_user.id, read_oauth_account.local_user_id);
+
+    // The same subject at a different provider is a different account
+    assert!(OAuthAccount::read_by_subject(pool, "other", "e3d5a2b1")
+      .await
+      .is_err());
+    // Each subject can only be linked once
+    assert!(OAuthAccount::create(pool, &form).await.is_err());
+
+    let num_deleted = Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+    assert_eq!(1, num_deleted);
+  }
+}
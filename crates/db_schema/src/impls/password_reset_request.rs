@@ -94,7 +94,7 @@ impl PasswordResetRequest {
   }
 }
 
-fn bytes_to_hex(bytes: Vec<u8>) -> String {
+pub(crate) fn bytes_to_hex(bytes: Vec<u8>) -> String {
   let mut str = String::new();
   for byte in bytes {
     str = format!("{str}{byte:02x}");
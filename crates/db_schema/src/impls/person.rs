@@ -9,7 +9,7 @@ use crate::{
     PersonUpdateForm,
   },
   traits::{ApubActor, Crud, Followable},
-  utils::{functions::lower, get_conn, naive_now, DbPool},
+  utils::{functions::lower, get_conn, limit_and_offset, naive_now, DbPool},
 };
 use diesel::{dsl::insert_into, result::Error, ExpressionMethods, JoinOnDsl, QueryDsl};
 use diesel_async::RunQueryDsl;
@@ -86,6 +86,7 @@ impl Person {
         person::banner.eq::<Option<String>>(None),
         person::bio.eq::<Option<String>>(None),
         person::matrix_user_id.eq::<Option<String>>(None),
+        person::encryption_public_key.eq::<Option<String>>(None),
         person::deleted.eq(true),
         person::updated.eq(naive_now()),
       ))
@@ -181,18 +182,86 @@ impl Followable for PersonFollower {
 }
 
 impl PersonFollower {
+  /// Lists the followers of the given person, excluding pending follow requests.
   pub async fn list_followers(
     pool: &DbPool,
     for_person_id: PersonId,
+    page: Option<i64>,
+    limit: Option<i64>,
   ) -> Result<Vec<Person>, Error> {
     let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
     person_follower::table
       .inner_join(person::table.on(person_follower::follower_id.eq(person::id)))
       .filter(person_follower::person_id.eq(for_person_id))
+      .filter(person_follower::pending.eq(false))
       .select(person::all_columns)
+      .order_by(person_follower::published.desc())
+      .limit(limit)
+      .offset(offset)
       .load(conn)
       .await
   }
+
+  /// The inboxes of all accepted followers of the given person, which activities of the person
+  /// are delivered to.
+  pub async fn list_follower_inboxes(
+    pool: &DbPool,
+    for_person_id: PersonId,
+  ) -> Result<Vec<DbUrl>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let inboxes = person_follower::table
+      .inner_join(person::table.on(person_follower::follower_id.eq(person::id)))
+      .filter(person_follower::person_id.eq(for_person_id))
+      .filter(person_follower::pending.eq(false))
+      .select((person::inbox_url, person::shared_inbox_url))
+      .load::<(DbUrl, Option<DbUrl>)>(conn)
+      .await?;
+    Ok(
+      inboxes
+        .into_iter()
+        .map(|(inbox, shared_inbox)| shared_inbox.unwrap_or(inbox))
+        .collect(),
+    )
+  }
+
+  /// Lists the persons which the given person follows, excluding pending follow requests.
+  pub async fn list_following(
+    pool: &DbPool,
+    for_follower_id: PersonId,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Person>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    person_follower::table
+      .inner_join(person::table.on(person_follower::person_id.eq(person::id)))
+      .filter(person_follower::follower_id.eq(for_follower_id))
+      .filter(person_follower::pending.eq(false))
+      .select(person::all_columns)
+      .order_by(person_follower::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .load(conn)
+      .await
+  }
+
+  /// Marks a pending follow as accepted. Throws an error if no follow was requested.
+  pub async fn follow_accepted_by_person(
+    pool: &DbPool,
+    for_person_id: PersonId,
+    for_follower_id: PersonId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      person_follower::table
+        .filter(person_follower::person_id.eq(for_person_id))
+        .filter(person_follower::follower_id.eq(for_follower_id)),
+    )
+    .set(person_follower::pending.eq(false))
+    .get_result::<Self>(conn)
+    .await
+  }
 }
 
 #[cfg(test)]
@@ -247,6 +316,9 @@ mod tests {
       matrix_user_id: None,
       ban_expires: None,
       instance_id: inserted_instance.id,
+      moved_to: None,
+      also_known_as: None,
+      encryption_public_key: None,
     };
 
     let read_person = Person::read(pool, inserted_person.id).await.unwrap();
@@ -291,17 +363,46 @@ mod tests {
     let follow_form = PersonFollowerForm {
       person_id: person_1.id,
       follower_id: person_2.id,
-      pending: false,
+      pending: true,
     };
     let person_follower = PersonFollower::follow(pool, &follow_form).await.unwrap();
     assert_eq!(person_1.id, person_follower.person_id);
     assert_eq!(person_2.id, person_follower.follower_id);
-    assert!(!person_follower.pending);
+    assert!(person_follower.pending);
+
+    // Pending follows are not listed yet
+    let following = PersonFollower::list_following(pool, person_2.id, None, None)
+      .await
+      .unwrap();
+    assert!(following.is_empty());
+    let followers = PersonFollower::list_followers(pool, person_1.id, None, None)
+      .await
+      .unwrap();
+    assert!(followers.is_empty());
+    let inboxes = PersonFollower::list_follower_inboxes(pool, person_1.id)
+      .await
+      .unwrap();
+    assert!(inboxes.is_empty());
+
+    let accepted = PersonFollower::follow_accepted_by_person(pool, person_1.id, person_2.id)
+      .await
+      .unwrap();
+    assert!(!accepted.pending);
+
+    let following = PersonFollower::list_following(pool, person_2.id, None, None)
+      .await
+      .unwrap();
+    assert_eq!(vec![person_1.clone()], following);
+
+    let followers = PersonFollower::list_followers(pool, person_1.id, None, None)
+      .await
+      .unwrap();
+    assert_eq!(vec![person_2.clone()], followers);
 
-    let followers = PersonFollower::list_followers(pool, person_1.id)
+    let inboxes = PersonFollower::list_follower_inboxes(pool, person_1.id)
       .await
       .unwrap();
-    assert_eq!(vec![person_2], followers);
+    assert_eq!(vec![person_2.inbox_url], inboxes);
 
     let unfollow = PersonFollower::unfollow(pool, &follow_form).await.unwrap();
     assert_eq!(1, unfollow);
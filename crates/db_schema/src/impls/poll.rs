@@ -0,0 +1,316 @@
+use crate::{
+  newtypes::{PersonId, PollOptionId, PostId},
+  schema::{poll, poll_option, poll_vote, post},
+  source::{
+    poll::{Poll, PollForm, PollOption, PollOptionForm, PollVote, PollVoteForm},
+    post::{Post, PostInsertForm},
+  },
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl Poll {
+  pub async fn read(pool: &DbPool, for_post_id: PostId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    poll::table.find(for_post_id).first::<Self>(conn).await
+  }
+
+  /// Creates a new post together with its poll and options, so that a failure can't leave a poll
+  /// post without options behind.
+  pub async fn create_with_post(
+    pool: &DbPool,
+    post_form: &PostInsertForm,
+    multiple_choice: bool,
+    end_time: Option<chrono::NaiveDateTime>,
+    option_names: Vec<String>,
+  ) -> Result<Post, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let post_form = post_form.clone();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let inserted_post = insert_into(post::table)
+            .values(post_form)
+            .get_result::<Post>(conn)
+            .await?;
+          let poll_form = PollForm {
+            post_id: inserted_post.id,
+            multiple_choice,
+            end_time,
+            updated: None,
+          };
+          insert_into(poll::table)
+            .values(poll_form)
+            .execute(conn)
+            .await?;
+          let option_forms: Vec<PollOptionForm> = option_names
+            .into_iter()
+            .map(|name| PollOptionForm {
+              post_id: inserted_post.id,
+              name,
+              remote_vote_count: None,
+            })
+            .collect();
+          insert_into(poll_option::table)
+            .values(option_forms)
+            .execute(conn)
+            .await?;
+          Ok(inserted_post)
+        }) as _
+      })
+      .await
+  }
+
+  /// Creates the poll of a post, or updates it if the post already has one.
+  pub async fn upsert(pool: &DbPool, form: &PollForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(poll::table)
+      .values(form)
+      .on_conflict(poll::post_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl PollOption {
+  pub async fn create_many(pool: &DbPool, forms: &[PollOptionForm]) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(poll_option::table)
+      .values(forms)
+      .get_results::<Self>(conn)
+      .await
+  }
+
+  pub async fn list_for_post(pool: &DbPool, for_post_id: PostId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    poll_option::table
+      .filter(poll_option::post_id.eq(for_post_id))
+      .order_by(poll_option::id)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Removes all options of the poll, together with their votes.
+  pub async fn delete_for_post(pool: &DbPool, for_post_id: PostId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(poll_option::table.filter(poll_option::post_id.eq(for_post_id)))
+      .execute(conn)
+      .await
+  }
+
+  /// Stores the number of votes which the instance of a remote poll has counted.
+  pub async fn update_remote_vote_count(
+    pool: &DbPool,
+    option_id: PollOptionId,
+    count: i32,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(poll_option::table.find(option_id))
+      .set(poll_option::remote_vote_count.eq(count))
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl PollVote {
+  /// Replaces the votes of the person in the poll. An empty list of options retracts the vote.
+  pub async fn update(
+    pool: &DbPool,
+    for_person_id: PersonId,
+    for_post_id: PostId,
+    option_ids: Vec<PollOptionId>,
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          diesel::delete(
+            poll_vote::table
+              .filter(poll_vote::person_id.eq(for_person_id))
+              .filter(poll_vote::post_id.eq(for_post_id)),
+          )
+          .execute(conn)
+          .await?;
+
+          let forms: Vec<PollVoteForm> = option_ids
+            .into_iter()
+            .map(|poll_option_id| PollVoteForm {
+              poll_option_id,
+              person_id: for_person_id,
+              post_id: for_post_id,
+            })
+            .collect();
+          if !forms.is_empty() {
+            insert_into(poll_vote::table)
+              .values(forms)
+              .on_conflict_do_nothing()
+              .execute(conn)
+              .await?;
+          }
+          Ok(())
+        }) as _
+      })
+      .await
+  }
+
+  pub async fn list_for_person(
+    pool: &DbPool,
+    for_person_id: PersonId,
+    for_post_id: PostId,
+  ) -> Result<Vec<PollOptionId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    poll_vote::table
+      .filter(poll_vote::person_id.eq(for_person_id))
+      .filter(poll_vote::post_id.eq(for_post_id))
+      .select(poll_vote::poll_option_id)
+      .load::<PollOptionId>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      poll::{Poll, PollForm, PollOption, PollOptionForm, PollVote},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_poll_votes() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("poll_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("poll_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A poll".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let poll_form = PollForm {
+      post_id: inserted_post.id,
+      multiple_choice: false,
+      end_time: None,
+      updated: None,
+    };
+    let inserted_poll = Poll::upsert(pool, &poll_form).await.unwrap();
+    assert!(!inserted_poll.multiple_choice);
+    let poll_form = PollForm {
+      multiple_choice: true,
+      ..poll_form
+    };
+    let updated_poll = Poll::upsert(pool, &poll_form).await.unwrap();
+    assert!(updated_poll.multiple_choice);
+
+    let option_forms: Vec<PollOptionForm> = ["Yes", "No"]
+      .into_iter()
+      .map(|name| PollOptionForm {
+        post_id: inserted_post.id,
+        name: name.to_string(),
+        remote_vote_count: None,
+      })
+      .collect();
+    let options = PollOption::create_many(pool, &option_forms).await.unwrap();
+    assert_eq!(
+      options,
+      PollOption::list_for_post(pool, inserted_post.id)
+        .await
+        .unwrap()
+    );
+
+    let option_ids: Vec<_> = options.iter().map(|o| o.id).collect();
+    PollVote::update(
+      pool,
+      inserted_person.id,
+      inserted_post.id,
+      option_ids.clone(),
+    )
+    .await
+    .unwrap();
+    PollVote::update(
+      pool,
+      inserted_person.id,
+      inserted_post.id,
+      vec![option_ids[1]],
+    )
+    .await
+    .unwrap();
+    let votes = PollVote::list_for_person(pool, inserted_person.id, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![option_ids[1]], votes);
+
+    // Posts can be created together with their poll
+    let poll_post = Poll::create_with_post(
+      pool,
+      &new_post,
+      true,
+      None,
+      vec!["Red".to_string(), "Blue".to_string()],
+    )
+    .await
+    .unwrap();
+    assert!(
+      Poll::read(pool, poll_post.id)
+        .await
+        .unwrap()
+        .multiple_choice
+    );
+    let names: Vec<_> = PollOption::list_for_post(pool, poll_post.id)
+      .await
+      .unwrap()
+      .into_iter()
+      .map(|o| o.name)
+      .collect();
+    assert_eq!(vec!["Red".to_string(), "Blue".to_string()], names);
+    Post::delete(pool, poll_post.id).await.unwrap();
+
+    // Removing the options also removes their votes
+    PollOption::delete_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    let votes = PollVote::list_for_person(pool, inserted_person.id, inserted_post.id)
+      .await
+      .unwrap();
+    assert!(votes.is_empty());
+
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -1,22 +1,30 @@
 use crate::{
   newtypes::{CommunityId, DbUrl, PersonId, PostId},
-  schema::post::dsl::{
-    ap_id,
-    body,
-    community_id,
-    creator_id,
-    deleted,
-    featured_community,
-    name,
-    post,
-    published,
-    removed,
-    thumbnail_url,
-    updated,
-    url,
+  schema::{
+    community,
+    person,
+    post::dsl::{
+      ap_id,
+      body,
+      community_id,
+      creator_id,
+      deleted,
+      featured_community,
+      name,
+      post,
+      published,
+      removed,
+      scheduled_publish_time,
+      thumbnail_url,
+      updated,
+      url,
+    },
+    post_aggregates,
   },
   source::post::{
     Post,
+    PostEdit,
+    PostEditForm,
     PostInsertForm,
     PostLike,
     PostLikeForm,
@@ -30,6 +38,7 @@ use crate::{
   utils::{get_conn, naive_now, DbPool, DELETED_REPLACEMENT_TEXT, FETCH_LIMIT_MAX},
 };
 use ::url::Url;
+use chrono::NaiveDateTime;
 use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl, TextExpressionMethods};
 use diesel_async::RunQueryDsl;
 
@@ -82,6 +91,7 @@ impl Post {
       .filter(community_id.eq(the_community_id))
       .filter(deleted.eq(false))
       .filter(removed.eq(false))
+      .filter(scheduled_publish_time.is_null())
       .then_order_by(featured_community.desc())
       .then_order_by(published.desc())
       .limit(FETCH_LIMIT_MAX)
@@ -98,6 +108,7 @@ impl Post {
       .filter(community_id.eq(the_community_id))
       .filter(deleted.eq(false))
       .filter(removed.eq(false))
+      .filter(scheduled_publish_time.is_null())
       .filter(featured_community.eq(true))
       .then_order_by(published.desc())
       .limit(FETCH_LIMIT_MAX)
@@ -105,12 +116,70 @@ impl Post {
       .await
   }
 
+  /// Scheduled posts whose publish time has come. Posts of banned or deleted users, or in removed
+  /// or deleted communities, stay unpublished.
+  pub async fn list_scheduled_to_publish(pool: &DbPool) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post
+      .inner_join(person::table)
+      .inner_join(community::table)
+      .filter(scheduled_publish_time.le(naive_now()))
+      .filter(deleted.eq(false))
+      .filter(removed.eq(false))
+      .filter(person::banned.eq(false))
+      .filter(person::deleted.eq(false))
+      .filter(community::removed.eq(false))
+      .filter(community::deleted.eq(false))
+      .select(crate::schema::post::all_columns)
+      .order_by(scheduled_publish_time)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Publishes a scheduled post. It is treated as if it was created now, so that it shows up at
+  /// the top of new posts.
+  pub async fn publish_scheduled(pool: &DbPool, post_id: PostId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let now = naive_now();
+          diesel::update(post_aggregates::table.filter(post_aggregates::post_id.eq(post_id)))
+            .set((
+              post_aggregates::published.eq(now),
+              post_aggregates::newest_comment_time.eq(now),
+              post_aggregates::newest_comment_time_necro.eq(now),
+            ))
+            .execute(conn)
+            .await?;
+          diesel::update(post.find(post_id))
+            .set((
+              scheduled_publish_time.eq(Option::<NaiveDateTime>::None),
+              published.eq(now),
+            ))
+            .get_result::<Self>(conn)
+            .await
+        }) as _
+      })
+      .await
+  }
+
   pub async fn permadelete_for_creator(
     pool: &DbPool,
     for_creator_id: PersonId,
   ) -> Result<Vec<Self>, Error> {
+    use crate::schema::{post as post_table, post_edit};
     let conn = &mut get_conn(pool).await?;
 
+    // Previous versions would keep the content around
+    let creator_posts = post
+      .filter(creator_id.eq(for_creator_id))
+      .select(post_table::id);
+    diesel::delete(post_edit::table.filter(post_edit::post_id.eq_any(creator_posts)))
+      .execute(conn)
+      .await?;
+
     diesel::update(post.filter(creator_id.eq(for_creator_id)))
       .set((
         name.eq(DELETED_REPLACEMENT_TEXT),
@@ -259,6 +328,21 @@ impl Likeable for PostLike {
   }
 }
 
+impl PostSaved {
+  /// Lists the activitypub ids of all posts saved by the given person.
+  pub async fn list_ap_ids(pool: &DbPool, for_person_id: PersonId) -> Result<Vec<DbUrl>, Error> {
+    use crate::schema::{post, post_saved};
+    let conn = &mut get_conn(pool).await?;
+    post_saved::table
+      .inner_join(post::table)
+      .filter(post_saved::person_id.eq(for_person_id))
+      .order_by(post_saved::published)
+      .select(post::ap_id)
+      .load::<DbUrl>(conn)
+      .await
+  }
+}
+
 #[async_trait]
 impl Saveable for PostSaved {
   type Form = PostSavedForm;
@@ -314,6 +398,48 @@ impl Readable for PostRead {
   }
 }
 
+impl PostEdit {
+  /// Stores the previous version of an updated post. Nothing is stored if the title, url and body
+  /// are unchanged.
+  pub async fn create_for_update(
+    pool: &DbPool,
+    old_post: &Post,
+    new_post: &Post,
+  ) -> Result<Option<Self>, Error> {
+    use crate::schema::post_edit;
+    if old_post.name == new_post.name
+      && old_post.url == new_post.url
+      && old_post.body == new_post.body
+    {
+      return Ok(None);
+    }
+    let form = PostEditForm {
+      post_id: old_post.id,
+      name: old_post.name.clone(),
+      url: old_post.url.clone(),
+      body: old_post.body.clone(),
+      published: old_post.updated.unwrap_or(old_post.published),
+    };
+    let conn = &mut get_conn(pool).await?;
+    insert_into(post_edit::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .map(Some)
+  }
+
+  /// Lists the previous versions of the post, oldest first.
+  pub async fn list_for_post(pool: &DbPool, for_post_id: PostId) -> Result<Vec<Self>, Error> {
+    use crate::schema::post_edit;
+    let conn = &mut get_conn(pool).await?;
+    post_edit::table
+      .filter(post_edit::post_id.eq(for_post_id))
+      .order_by(post_edit::id)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -323,6 +449,7 @@ mod tests {
       person::{Person, PersonInsertForm},
       post::{
         Post,
+        PostEdit,
         PostInsertForm,
         PostLike,
         PostLikeForm,
@@ -394,6 +521,8 @@ mod tests {
       language_id: Default::default(),
       featured_community: false,
       featured_local: false,
+      scheduled_publish_time: None,
+      url_content_type: None,
     };
 
     // Post Like
@@ -420,6 +549,10 @@ mod tests {
     };
 
     let inserted_post_saved = PostSaved::save(pool, &post_saved_form).await.unwrap();
+    let saved_ap_ids = PostSaved::list_ap_ids(pool, inserted_person.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![inserted_post.ap_id.clone()], saved_ap_ids);
 
     let expected_post_saved = PostSaved {
       id: inserted_post_saved.id,
@@ -452,6 +585,24 @@ mod tests {
       .await
       .unwrap();
 
+    // Only edits which change the content are kept in the history
+    let unchanged = PostEdit::create_for_update(pool, &inserted_post, &updated_post)
+      .await
+      .unwrap();
+    assert!(unchanged.is_none());
+    let edited_post = Post {
+      name: "An edited post".into(),
+      ..updated_post.clone()
+    };
+    PostEdit::create_for_update(pool, &updated_post, &edited_post)
+      .await
+      .unwrap();
+    let edits = PostEdit::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(1, edits.len());
+    assert_eq!("A test post", edits[0].name);
+
     let like_removed = PostLike::remove(pool, inserted_person.id, inserted_post.id)
       .await
       .unwrap();
@@ -1,11 +1,29 @@
 use crate::{
-  newtypes::{DbUrl, PersonId, PrivateMessageId},
-  schema::private_message::dsl::{ap_id, private_message, read, recipient_id},
-  source::private_message::{PrivateMessage, PrivateMessageInsertForm, PrivateMessageUpdateForm},
+  newtypes::{ConversationId, DbUrl, PersonId, PrivateMessageId},
+  schema::{
+    conversation,
+    conversation_participant,
+    private_message::dsl::{ap_id, private_message, read, recipient_id},
+  },
+  source::private_message::{
+    Conversation,
+    ConversationParticipant,
+    PrivateMessage,
+    PrivateMessageInsertForm,
+    PrivateMessageUpdateForm,
+  },
   traits::Crud,
-  utils::{get_conn, DbPool},
+  utils::{get_conn, naive_now, DbPool},
+};
+use diesel::{
+  dsl::{count_star, insert_into, sql_query},
+  result::Error,
+  sql_types::Text,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
 };
-use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
 use lemmy_utils::error::LemmyError;
 use url::Url;
@@ -59,6 +77,13 @@ impl PrivateMessage {
     for_recipient_id: PersonId,
   ) -> Result<Vec<PrivateMessage>, Error> {
     let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      conversation_participant::table
+        .filter(conversation_participant::person_id.eq(for_recipient_id)),
+    )
+    .set(conversation_participant::last_read.eq(naive_now()))
+    .execute(conn)
+    .await?;
     diesel::update(
       private_message
         .filter(recipient_id.eq(for_recipient_id))
@@ -86,13 +111,158 @@ impl PrivateMessage {
   }
 }
 
+impl Conversation {
+  pub async fn read(pool: &DbPool, conversation_id: ConversationId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conversation::table
+      .find(conversation_id)
+      .first::<Self>(conn)
+      .await
+  }
+
+  /// Finds the conversation between exactly the given people, or starts a new one.
+  pub async fn read_or_create(pool: &DbPool, participants: &[PersonId]) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut participants = participants.to_vec();
+    participants.sort_by_key(|p| p.0);
+    participants.dedup();
+    let participant_count = participants.len() as i64;
+    let lock_key = format!(
+      "conversation:{}",
+      participants
+        .iter()
+        .map(|p| p.0.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+    );
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          // Two messages between the same people may arrive at the same time. The lock makes one
+          // of them wait until the other one created the conversation, so that it finds it.
+          sql_query("select pg_advisory_xact_lock(hashtext($1))")
+            .bind::<Text, _>(lock_key)
+            .execute(conn)
+            .await?;
+
+          // Conversations which all of the people are part of
+          let candidates = conversation_participant::table
+            .filter(conversation_participant::person_id.eq_any(participants.clone()))
+            .group_by(conversation_participant::conversation_id)
+            .having(count_star().eq(participant_count))
+            .select(conversation_participant::conversation_id)
+            .load::<ConversationId>(conn)
+            .await?;
+          // Of those, the one without anybody else
+          let existing = conversation_participant::table
+            .filter(conversation_participant::conversation_id.eq_any(candidates))
+            .group_by(conversation_participant::conversation_id)
+            .having(count_star().eq(participant_count))
+            .select(conversation_participant::conversation_id)
+            .first::<ConversationId>(conn)
+            .await
+            .optional()?;
+          if let Some(existing) = existing {
+            return conversation::table.find(existing).first::<Self>(conn).await;
+          }
+
+          let inserted = insert_into(conversation::table)
+            .default_values()
+            .get_result::<Self>(conn)
+            .await?;
+          let forms: Vec<ConversationParticipant> = participants
+            .into_iter()
+            .map(|person_id| ConversationParticipant {
+              conversation_id: inserted.id,
+              person_id,
+              last_read: None,
+            })
+            .collect();
+          insert_into(conversation_participant::table)
+            .values(forms)
+            .execute(conn)
+            .await?;
+          Ok(inserted)
+        }) as _
+      })
+      .await
+  }
+
+  /// Moves the conversation up in the list, if the message is newer than the previous ones.
+  pub async fn update_for_message(
+    pool: &DbPool,
+    conversation_id: ConversationId,
+    message_published: chrono::NaiveDateTime,
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      conversation::table.find(conversation_id).filter(
+        conversation::updated
+          .is_null()
+          .or(conversation::updated.lt(message_published)),
+      ),
+    )
+    .set(conversation::updated.eq(message_published))
+    .execute(conn)
+    .await?;
+    Ok(())
+  }
+
+  pub async fn list_participants(
+    pool: &DbPool,
+    conversation_id: ConversationId,
+  ) -> Result<Vec<PersonId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conversation_participant::table
+      .filter(conversation_participant::conversation_id.eq(conversation_id))
+      .order_by(conversation_participant::person_id)
+      .select(conversation_participant::person_id)
+      .load::<PersonId>(conn)
+      .await
+  }
+}
+
+impl ConversationParticipant {
+  pub async fn read(
+    pool: &DbPool,
+    conversation_id: ConversationId,
+    person_id: PersonId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conversation_participant::table
+      .find((conversation_id, person_id))
+      .first::<Self>(conn)
+      .await
+  }
+
+  pub async fn update_last_read(
+    pool: &DbPool,
+    conversation_id: ConversationId,
+    person_id: PersonId,
+    last_read: Option<chrono::NaiveDateTime>,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(conversation_participant::table.find((conversation_id, person_id)))
+      .set(conversation_participant::last_read.eq(last_read))
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
     source::{
       instance::Instance,
       person::{Person, PersonInsertForm},
-      private_message::{PrivateMessage, PrivateMessageInsertForm, PrivateMessageUpdateForm},
+      private_message::{
+        Conversation,
+        ConversationParticipant,
+        PrivateMessage,
+        PrivateMessageInsertForm,
+        PrivateMessageUpdateForm,
+      },
     },
     traits::Crud,
     utils::build_db_pool_for_tests,
@@ -124,10 +294,25 @@ mod tests {
 
     let inserted_recipient = Person::create(pool, &recipient_form).await.unwrap();
 
+    let participants = [inserted_creator.id, inserted_recipient.id];
+    let conversation = Conversation::read_or_create(pool, &participants)
+      .await
+      .unwrap();
+    // The same people in a different order are still the same conversation
+    let participants_reversed = [inserted_recipient.id, inserted_creator.id];
+    let read_conversation = Conversation::read_or_create(pool, &participants_reversed)
+      .await
+      .unwrap();
+    // But a message to oneself is a different one
+    let self_conversation = Conversation::read_or_create(pool, &[inserted_creator.id])
+      .await
+      .unwrap();
+
     let private_message_form = PrivateMessageInsertForm::builder()
       .content("A test private message".into())
       .creator_id(inserted_creator.id)
       .recipient_id(inserted_recipient.id)
+      .conversation_id(conversation.id)
       .build();
 
     let inserted_private_message = PrivateMessage::create(pool, &private_message_form)
@@ -145,6 +330,8 @@ mod tests {
       published: inserted_private_message.published,
       ap_id: inserted_private_message.ap_id.clone(),
       local: true,
+      conversation_id: conversation.id,
+      encrypted_content: None,
     };
 
     let read_private_message = PrivateMessage::read(pool, inserted_private_message.id)
@@ -178,6 +365,21 @@ mod tests {
     )
     .await
     .unwrap();
+
+    Conversation::update_for_message(pool, conversation.id, inserted_private_message.published)
+      .await
+      .unwrap();
+    let updated_conversation = Conversation::read(pool, conversation.id).await.unwrap();
+    let listed_participants = Conversation::list_participants(pool, conversation.id)
+      .await
+      .unwrap();
+    PrivateMessage::mark_all_as_read(pool, inserted_recipient.id)
+      .await
+      .unwrap();
+    let participant = ConversationParticipant::read(pool, conversation.id, inserted_recipient.id)
+      .await
+      .unwrap();
+
     Person::delete(pool, inserted_creator.id).await.unwrap();
     Person::delete(pool, inserted_recipient.id).await.unwrap();
     Instance::delete(pool, inserted_instance.id).await.unwrap();
@@ -187,5 +389,16 @@ mod tests {
     assert_eq!(expected_private_message, inserted_private_message);
     assert!(deleted_private_message.deleted);
     assert!(marked_read_private_message.read);
+    assert_eq!(conversation, read_conversation);
+    assert_ne!(conversation.id, self_conversation.id);
+    assert_eq!(
+      Some(inserted_private_message.published),
+      updated_conversation.updated
+    );
+    assert_eq!(
+      vec![inserted_creator.id, inserted_recipient.id],
+      listed_participants
+    );
+    assert!(participant.last_read.is_some());
   }
 }
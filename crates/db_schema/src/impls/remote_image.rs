@@ -0,0 +1,146 @@
+use crate::{
+  newtypes::{DbUrl, RemoteImageId},
+  schema::remote_image,
+  source::remote_image::{RemoteImage, RemoteImageForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  dsl::{insert_into, update},
+  result::Error,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl RemoteImage {
+  /// Records the links of images which were received from other instances, so that the image
+  /// proxy may serve them.
+  pub async fn create_links(pool: &DbPool, links: Vec<DbUrl>) -> Result<usize, Error> {
+    if links.is_empty() {
+      return Ok(0);
+    }
+    let conn = &mut get_conn(pool).await?;
+    let forms = links
+      .into_iter()
+      .map(|link| RemoteImageForm {
+        link,
+        pictrs_alias: None,
+        pictrs_delete_token: None,
+      })
+      .collect::<Vec<_>>();
+    insert_into(remote_image::table)
+      .values(forms)
+      .on_conflict(remote_image::link)
+      .do_nothing()
+      .execute(conn)
+      .await
+  }
+
+  /// Returns those of the given links which were recorded.
+  pub async fn filter_known(pool: &DbPool, links: Vec<DbUrl>) -> Result<Vec<DbUrl>, Error> {
+    if links.is_empty() {
+      return Ok(links);
+    }
+    let conn = &mut get_conn(pool).await?;
+    remote_image::table
+      .filter(remote_image::link.eq_any(links))
+      .select(remote_image::link)
+      .load::<DbUrl>(conn)
+      .await
+  }
+
+  pub async fn read_for_link(pool: &DbPool, link: &DbUrl) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    remote_image::table
+      .filter(remote_image::link.eq(link))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  /// Stores the pict-rs copy of a remote image. Returns None if another request already stored a
+  /// copy, which should then be used instead.
+  pub async fn store_copy(
+    pool: &DbPool,
+    id: RemoteImageId,
+    pictrs_alias: &str,
+    pictrs_delete_token: &str,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    update(
+      remote_image::table
+        .find(id)
+        .filter(remote_image::pictrs_alias.is_null()),
+    )
+    .set((
+      remote_image::pictrs_alias.eq(pictrs_alias),
+      remote_image::pictrs_delete_token.eq(pictrs_delete_token),
+    ))
+    .get_result::<Self>(conn)
+    .await
+    .optional()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    newtypes::DbUrl,
+    schema::remote_image,
+    source::remote_image::RemoteImage,
+    utils::{build_db_pool_for_tests, get_conn},
+  };
+  use diesel_async::RunQueryDsl;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_remote_images() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let link: DbUrl = Url::parse("https://example.com/image.png").unwrap().into();
+    let unknown: DbUrl = Url::parse("https://example.com/other.png").unwrap().into();
+    assert_eq!(None, RemoteImage::read_for_link(pool, &link).await.unwrap());
+
+    // Recording the same link twice keeps a single row
+    let created = RemoteImage::create_links(pool, vec![link.clone()])
+      .await
+      .unwrap();
+    assert_eq!(1, created);
+    let created = RemoteImage::create_links(pool, vec![link.clone()])
+      .await
+      .unwrap();
+    assert_eq!(0, created);
+    let known = RemoteImage::filter_known(pool, vec![link.clone(), unknown])
+      .await
+      .unwrap();
+    assert_eq!(vec![link.clone()], known);
+
+    let recorded = RemoteImage::read_for_link(pool, &link)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(None, recorded.pictrs_alias);
+
+    // Storing a copy again keeps the first copy
+    let stored = RemoteImage::store_copy(pool, recorded.id, "a1b2c3.png", "d4e5f6")
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(Some("a1b2c3.png".to_string()), stored.pictrs_alias);
+    let second = RemoteImage::store_copy(pool, recorded.id, "g7h8i9.png", "j1k2l3")
+      .await
+      .unwrap();
+    assert_eq!(None, second);
+    let read = RemoteImage::read_for_link(pool, &link).await.unwrap();
+    assert_eq!(Some(stored), read);
+
+    let conn = &mut get_conn(pool).await.unwrap();
+    diesel::delete(remote_image::table)
+      .execute(conn)
+      .await
+      .unwrap();
+  }
+}
@@ -1,6 +1,10 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "keyword_filter_action_enum"))]
+    pub struct KeywordFilterActionEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "listing_type_enum"))]
     pub struct ListingTypeEnum;
@@ -26,6 +30,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    activity_send_inbox (id) {
+        id -> Int4,
+        activity_id -> Int4,
+        instance_id -> Int4,
+        inbox -> Text,
+    }
+}
+
 diesel::table! {
     admin_purge_comment (id) {
         id -> Int4,
@@ -108,6 +121,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    comment_edit (id) {
+        id -> Int4,
+        comment_id -> Int4,
+        content -> Text,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     comment_like (id) {
         id -> Int4,
@@ -251,6 +273,53 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    community_post_tag (id) {
+        id -> Int4,
+        #[max_length = 255]
+        ap_id -> Varchar,
+        community_id -> Int4,
+        #[max_length = 50]
+        name -> Varchar,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    community_recurring_post (id) {
+        id -> Int4,
+        community_id -> Int4,
+        creator_id -> Int4,
+        #[max_length = 200]
+        name -> Varchar,
+        body -> Nullable<Text>,
+        #[max_length = 100]
+        schedule -> Varchar,
+        featured -> Bool,
+        last_post_id -> Nullable<Int4>,
+        next_publish_time -> Nullable<Timestamp>,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    conversation (id) {
+        id -> Int4,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    conversation_participant (conversation_id, person_id) {
+        conversation_id -> Int4,
+        person_id -> Int4,
+        last_read -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     custom_emoji (id) {
         id -> Int4,
@@ -302,6 +371,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    federation_queue_state (id) {
+        id -> Int4,
+        instance_id -> Int4,
+        fail_count -> Int4,
+        last_retry -> Nullable<Timestamp>,
+        dead_since -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     instance (id) {
         id -> Int4,
@@ -316,6 +395,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    instance_block (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        instance_id -> Int4,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     language (id) {
         id -> Int4,
@@ -325,6 +413,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    local_image (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        pictrs_alias -> Text,
+        pictrs_delete_token -> Text,
+        published -> Timestamp,
+        flag_reason -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::ListingTypeEnum;
@@ -355,6 +454,10 @@ diesel::table! {
         updated -> Nullable<Timestamp>,
         registration_mode -> RegistrationModeEnum,
         reports_email_admins -> Bool,
+        image_upload_max_bytes -> Nullable<Int4>,
+        image_upload_max_dimension -> Nullable<Int4>,
+        image_upload_allowed_mime_types -> Nullable<Text>,
+        image_upload_classifier_url -> Nullable<Text>,
     }
 }
 
@@ -409,6 +512,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::KeywordFilterActionEnum;
+
+    local_user_keyword_filter (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        #[max_length = 100]
+        keyword -> Varchar,
+        action -> KeywordFilterActionEnum,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     local_user_language (id) {
         id -> Int4,
@@ -417,6 +535,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    login_token (id) {
+        id -> Int4,
+        token_encrypted -> Text,
+        user_id -> Int4,
+        published -> Timestamp,
+        last_used -> Timestamp,
+        ip -> Nullable<Text>,
+        user_agent -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     mod_add (id) {
         id -> Int4,
@@ -495,6 +625,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    mod_note (id) {
+        id -> Int4,
+        target_person_id -> Int4,
+        community_id -> Nullable<Int4>,
+        author_id -> Int4,
+        content -> Text,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     mod_remove_comment (id) {
         id -> Int4,
@@ -539,6 +680,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    oauth_account (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        provider -> Text,
+        subject -> Text,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     password_reset_request (id) {
         id -> Int4,
@@ -577,6 +728,11 @@ diesel::table! {
         bot_account -> Bool,
         ban_expires -> Nullable<Timestamp>,
         instance_id -> Int4,
+        #[max_length = 255]
+        moved_to -> Nullable<Varchar>,
+        #[max_length = 255]
+        also_known_as -> Nullable<Varchar>,
+        encryption_public_key -> Nullable<Text>,
     }
 }
 
@@ -638,6 +794,35 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    poll (post_id) {
+        post_id -> Int4,
+        multiple_choice -> Bool,
+        end_time -> Nullable<Timestamp>,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    poll_option (id) {
+        id -> Int4,
+        post_id -> Int4,
+        #[max_length = 200]
+        name -> Varchar,
+        remote_vote_count -> Int4,
+    }
+}
+
+diesel::table! {
+    poll_vote (poll_option_id, person_id) {
+        poll_option_id -> Int4,
+        person_id -> Int4,
+        post_id -> Int4,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     post (id) {
         id -> Int4,
@@ -664,6 +849,8 @@ diesel::table! {
         language_id -> Int4,
         featured_community -> Bool,
         featured_local -> Bool,
+        scheduled_publish_time -> Nullable<Timestamp>,
+        url_content_type -> Nullable<Text>,
     }
 }
 
@@ -685,6 +872,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_edit (id) {
+        id -> Int4,
+        post_id -> Int4,
+        #[max_length = 200]
+        name -> Varchar,
+        #[max_length = 512]
+        url -> Nullable<Varchar>,
+        body -> Nullable<Text>,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     post_like (id) {
         id -> Int4,
@@ -730,6 +930,13 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_tag (post_id, tag_id) {
+        post_id -> Int4,
+        tag_id -> Int4,
+    }
+}
+
 diesel::table! {
     private_message (id) {
         id -> Int4,
@@ -743,6 +950,8 @@ diesel::table! {
         #[max_length = 255]
         ap_id -> Varchar,
         local -> Bool,
+        conversation_id -> Int4,
+        encrypted_content -> Nullable<Text>,
     }
 }
 
@@ -771,6 +980,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    remote_image (id) {
+        id -> Int4,
+        link -> Text,
+        pictrs_alias -> Nullable<Text>,
+        pictrs_delete_token -> Nullable<Text>,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     secret (id) {
         id -> Int4,
@@ -834,6 +1053,8 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(activity_send_inbox -> activity (activity_id));
+diesel::joinable!(activity_send_inbox -> instance (instance_id));
 diesel::joinable!(admin_purge_comment -> person (admin_person_id));
 diesel::joinable!(admin_purge_comment -> post (post_id));
 diesel::joinable!(admin_purge_community -> person (admin_person_id));
@@ -844,6 +1065,7 @@ diesel::joinable!(comment -> language (language_id));
 diesel::joinable!(comment -> person (creator_id));
 diesel::joinable!(comment -> post (post_id));
 diesel::joinable!(comment_aggregates -> comment (comment_id));
+diesel::joinable!(comment_edit -> comment (comment_id));
 diesel::joinable!(comment_like -> comment (comment_id));
 diesel::joinable!(comment_like -> person (person_id));
 diesel::joinable!(comment_like -> post (post_id));
@@ -864,16 +1086,28 @@ diesel::joinable!(community_moderator -> community (community_id));
 diesel::joinable!(community_moderator -> person (person_id));
 diesel::joinable!(community_person_ban -> community (community_id));
 diesel::joinable!(community_person_ban -> person (person_id));
+diesel::joinable!(community_post_tag -> community (community_id));
+diesel::joinable!(community_recurring_post -> community (community_id));
+diesel::joinable!(community_recurring_post -> person (creator_id));
+diesel::joinable!(community_recurring_post -> post (last_post_id));
+diesel::joinable!(conversation_participant -> conversation (conversation_id));
+diesel::joinable!(conversation_participant -> person (person_id));
 diesel::joinable!(custom_emoji -> local_site (local_site_id));
 diesel::joinable!(custom_emoji_keyword -> custom_emoji (custom_emoji_id));
 diesel::joinable!(email_verification -> local_user (local_user_id));
 diesel::joinable!(federation_allowlist -> instance (instance_id));
 diesel::joinable!(federation_blocklist -> instance (instance_id));
+diesel::joinable!(federation_queue_state -> instance (instance_id));
+diesel::joinable!(instance_block -> instance (instance_id));
+diesel::joinable!(instance_block -> local_user (local_user_id));
+diesel::joinable!(local_image -> local_user (local_user_id));
 diesel::joinable!(local_site -> site (site_id));
 diesel::joinable!(local_site_rate_limit -> local_site (local_site_id));
 diesel::joinable!(local_user -> person (person_id));
+diesel::joinable!(local_user_keyword_filter -> local_user (local_user_id));
 diesel::joinable!(local_user_language -> language (language_id));
 diesel::joinable!(local_user_language -> local_user (local_user_id));
+diesel::joinable!(login_token -> local_user (user_id));
 diesel::joinable!(mod_add_community -> community (community_id));
 diesel::joinable!(mod_ban_from_community -> community (community_id));
 diesel::joinable!(mod_feature_post -> person (mod_person_id));
@@ -882,6 +1116,7 @@ diesel::joinable!(mod_hide_community -> community (community_id));
 diesel::joinable!(mod_hide_community -> person (mod_person_id));
 diesel::joinable!(mod_lock_post -> person (mod_person_id));
 diesel::joinable!(mod_lock_post -> post (post_id));
+diesel::joinable!(mod_note -> community (community_id));
 diesel::joinable!(mod_remove_comment -> comment (comment_id));
 diesel::joinable!(mod_remove_comment -> person (mod_person_id));
 diesel::joinable!(mod_remove_community -> community (community_id));
@@ -889,6 +1124,7 @@ diesel::joinable!(mod_remove_community -> person (mod_person_id));
 diesel::joinable!(mod_remove_post -> person (mod_person_id));
 diesel::joinable!(mod_remove_post -> post (post_id));
 diesel::joinable!(mod_transfer_community -> community (community_id));
+diesel::joinable!(oauth_account -> local_user (local_user_id));
 diesel::joinable!(password_reset_request -> local_user (local_user_id));
 diesel::joinable!(person -> instance (instance_id));
 diesel::joinable!(person_aggregates -> person (person_id));
@@ -897,10 +1133,16 @@ diesel::joinable!(person_mention -> comment (comment_id));
 diesel::joinable!(person_mention -> person (recipient_id));
 diesel::joinable!(person_post_aggregates -> person (person_id));
 diesel::joinable!(person_post_aggregates -> post (post_id));
+diesel::joinable!(poll -> post (post_id));
+diesel::joinable!(poll_option -> poll (post_id));
+diesel::joinable!(poll_vote -> person (person_id));
+diesel::joinable!(poll_vote -> poll (post_id));
+diesel::joinable!(poll_vote -> poll_option (poll_option_id));
 diesel::joinable!(post -> community (community_id));
 diesel::joinable!(post -> language (language_id));
 diesel::joinable!(post -> person (creator_id));
 diesel::joinable!(post_aggregates -> post (post_id));
+diesel::joinable!(post_edit -> post (post_id));
 diesel::joinable!(post_like -> person (person_id));
 diesel::joinable!(post_like -> post (post_id));
 diesel::joinable!(post_read -> person (person_id));
@@ -908,6 +1150,9 @@ diesel::joinable!(post_read -> post (post_id));
 diesel::joinable!(post_report -> post (post_id));
 diesel::joinable!(post_saved -> person (person_id));
 diesel::joinable!(post_saved -> post (post_id));
+diesel::joinable!(post_tag -> community_post_tag (tag_id));
+diesel::joinable!(post_tag -> post (post_id));
+diesel::joinable!(private_message -> conversation (conversation_id));
 diesel::joinable!(private_message_report -> private_message (private_message_id));
 diesel::joinable!(registration_application -> local_user (local_user_id));
 diesel::joinable!(registration_application -> person (admin_id));
@@ -919,6 +1164,7 @@ diesel::joinable!(tagline -> local_site (local_site_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     activity,
+    activity_send_inbox,
     admin_purge_comment,
     admin_purge_community,
     admin_purge_person,
@@ -926,6 +1172,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     captcha_answer,
     comment,
     comment_aggregates,
+    comment_edit,
     comment_like,
     comment_reply,
     comment_report,
@@ -937,17 +1184,26 @@ diesel::allow_tables_to_appear_in_same_query!(
     community_language,
     community_moderator,
     community_person_ban,
+    community_post_tag,
+    community_recurring_post,
+    conversation,
+    conversation_participant,
     custom_emoji,
     custom_emoji_keyword,
     email_verification,
     federation_allowlist,
     federation_blocklist,
+    federation_queue_state,
     instance,
+    instance_block,
     language,
+    local_image,
     local_site,
     local_site_rate_limit,
     local_user,
+    local_user_keyword_filter,
     local_user_language,
+    login_token,
     mod_add,
     mod_add_community,
     mod_ban,
@@ -955,10 +1211,12 @@ diesel::allow_tables_to_appear_in_same_query!(
     mod_feature_post,
     mod_hide_community,
     mod_lock_post,
+    mod_note,
     mod_remove_comment,
     mod_remove_community,
     mod_remove_post,
     mod_transfer_community,
+    oauth_account,
     password_reset_request,
     person,
     person_aggregates,
@@ -967,15 +1225,21 @@ diesel::allow_tables_to_appear_in_same_query!(
     person_follower,
     person_mention,
     person_post_aggregates,
+    poll,
+    poll_option,
+    poll_vote,
     post,
     post_aggregates,
+    post_edit,
     post_like,
     post_read,
     post_report,
     post_saved,
+    post_tag,
     private_message,
     private_message_report,
     registration_application,
+    remote_image,
     secret,
     site,
     site_aggregates,
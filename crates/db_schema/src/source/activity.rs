@@ -1,4 +1,7 @@
-use crate::{newtypes::DbUrl, schema::activity};
+use crate::{
+  newtypes::{DbUrl, InstanceId},
+  schema::{activity, activity_send_inbox},
+};
 use serde_json::Value;
 use std::fmt::Debug;
 
@@ -32,3 +35,21 @@ pub struct ActivityUpdateForm {
   pub updated: Option<Option<chrono::NaiveDateTime>>,
   pub sensitive: Option<bool>,
 }
+
+/// An inbox which a local activity still needs to be delivered to.
+#[derive(PartialEq, Eq, Debug, Queryable, Identifiable)]
+#[diesel(table_name = activity_send_inbox)]
+pub struct ActivitySendInbox {
+  pub id: i32,
+  pub activity_id: i32,
+  pub instance_id: InstanceId,
+  pub inbox: DbUrl,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = activity_send_inbox)]
+pub struct ActivitySendInboxForm {
+  pub activity_id: i32,
+  pub instance_id: InstanceId,
+  pub inbox: DbUrl,
+}
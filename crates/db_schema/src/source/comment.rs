@@ -2,7 +2,7 @@
 use crate::newtypes::LtreeDef;
 use crate::newtypes::{CommentId, DbUrl, LanguageId, PersonId, PostId};
 #[cfg(feature = "full")]
-use crate::schema::{comment, comment_like, comment_saved};
+use crate::schema::{comment, comment_edit, comment_like, comment_saved};
 #[cfg(feature = "full")]
 use diesel_ltree::Ltree;
 use serde::{Deserialize, Serialize};
@@ -122,3 +122,25 @@ pub struct CommentSavedForm {
   pub comment_id: CommentId,
   pub person_id: PersonId,
 }
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Identifiable, Queryable, Associations, TS))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::comment::Comment)))]
+#[cfg_attr(feature = "full", diesel(table_name = comment_edit))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A previous version of an edited comment.
+pub struct CommentEdit {
+  pub id: i32,
+  pub comment_id: CommentId,
+  pub content: String,
+  /// When this version was written.
+  pub published: chrono::NaiveDateTime,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = comment_edit))]
+pub struct CommentEditForm {
+  pub comment_id: CommentId,
+  pub content: String,
+  pub published: chrono::NaiveDateTime,
+}
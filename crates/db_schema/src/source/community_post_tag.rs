@@ -0,0 +1,64 @@
+use crate::newtypes::{CommunityId, CommunityPostTagId, DbUrl, PostId};
+#[cfg(feature = "full")]
+use crate::schema::{community_post_tag, post_tag};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::community::Community))
+)]
+#[cfg_attr(feature = "full", diesel(table_name = community_post_tag))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A tag which can be added to posts in a community, defined by the community moderators.
+pub struct CommunityPostTag {
+  pub id: CommunityPostTagId,
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  /// The federated id of the tag.
+  pub ap_id: DbUrl,
+  pub community_id: CommunityId,
+  pub name: String,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_post_tag))]
+pub struct CommunityPostTagInsertForm {
+  pub ap_id: Option<DbUrl>,
+  pub community_id: CommunityId,
+  pub name: String,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_post_tag))]
+pub struct CommunityPostTagUpdateForm {
+  pub ap_id: Option<DbUrl>,
+  pub name: Option<String>,
+  pub updated: Option<Option<chrono::NaiveDateTime>>,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = post_tag))]
+pub struct PostTag {
+  pub post_id: PostId,
+  pub tag_id: CommunityPostTagId,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_tag))]
+pub struct PostTagForm {
+  pub post_id: PostId,
+  pub tag_id: CommunityPostTagId,
+}
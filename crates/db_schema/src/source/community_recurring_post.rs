@@ -0,0 +1,61 @@
+use crate::newtypes::{CommunityId, CommunityRecurringPostId, PersonId, PostId};
+#[cfg(feature = "full")]
+use crate::schema::community_recurring_post;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::community::Community))
+)]
+#[cfg_attr(feature = "full", diesel(table_name = community_recurring_post))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A template for posts which are created regularly in a community, like weekly megathreads.
+pub struct CommunityRecurringPost {
+  pub id: CommunityRecurringPostId,
+  pub community_id: CommunityId,
+  /// The moderator who created the template, and who is the creator of the posts.
+  pub creator_id: PersonId,
+  /// The post title, which can contain date placeholders like `{date}`.
+  pub name: String,
+  pub body: Option<String>,
+  /// A cron-like schedule in UTC, like `0 9 * * 1` for every Monday at 09:00.
+  pub schedule: String,
+  /// Whether the posts get featured in the community, replacing the previous post.
+  pub featured: bool,
+  pub last_post_id: Option<PostId>,
+  pub next_publish_time: Option<chrono::NaiveDateTime>,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = community_recurring_post))]
+pub struct CommunityRecurringPostInsertForm {
+  pub community_id: CommunityId,
+  pub creator_id: PersonId,
+  pub name: String,
+  pub body: Option<String>,
+  pub schedule: String,
+  pub featured: bool,
+  pub next_publish_time: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_recurring_post))]
+pub struct CommunityRecurringPostUpdateForm {
+  pub name: Option<String>,
+  pub body: Option<Option<String>>,
+  pub schedule: Option<String>,
+  pub featured: Option<bool>,
+  pub last_post_id: Option<Option<PostId>>,
+  pub next_publish_time: Option<Option<chrono::NaiveDateTime>>,
+  pub updated: Option<Option<chrono::NaiveDateTime>>,
+}
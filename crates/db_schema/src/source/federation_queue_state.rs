@@ -0,0 +1,23 @@
+use crate::{newtypes::InstanceId, schema::federation_queue_state};
+
+/// Progress of delivering local activities to a remote instance.
+#[derive(Clone, PartialEq, Eq, Debug, Queryable, Identifiable)]
+#[diesel(table_name = federation_queue_state)]
+pub struct FederationQueueState {
+  pub id: i32,
+  pub instance_id: InstanceId,
+  /// Number of failed attempts since the last successful delivery.
+  pub fail_count: i32,
+  pub last_retry: Option<chrono::NaiveDateTime>,
+  /// Set after too many consecutive failures.
+  pub dead_since: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = federation_queue_state, treat_none_as_null = true)]
+pub struct FederationQueueStateForm {
+  pub instance_id: InstanceId,
+  pub fail_count: i32,
+  pub last_retry: Option<chrono::NaiveDateTime>,
+  pub dead_since: Option<chrono::NaiveDateTime>,
+}
@@ -0,0 +1,25 @@
+use crate::newtypes::{InstanceBlockId, InstanceId, LocalUserId};
+#[cfg(feature = "full")]
+use crate::schema::instance_block;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::instance::Instance))
+)]
+#[cfg_attr(feature = "full", diesel(table_name = instance_block))]
+pub struct InstanceBlock {
+  pub id: InstanceBlockId,
+  pub local_user_id: LocalUserId,
+  pub instance_id: InstanceId,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = instance_block))]
+pub struct InstanceBlockForm {
+  pub local_user_id: LocalUserId,
+  pub instance_id: InstanceId,
+}
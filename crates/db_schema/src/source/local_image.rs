@@ -0,0 +1,34 @@
+use crate::newtypes::{LocalImageId, LocalUserId};
+#[cfg(feature = "full")]
+use crate::schema::local_image;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = local_image))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An image which a local user uploaded to pict-rs.
+pub struct LocalImage {
+  pub id: LocalImageId,
+  pub local_user_id: LocalUserId,
+  /// The file name of the image in pict-rs.
+  pub pictrs_alias: String,
+  /// Allows deleting the image from pict-rs without its api key.
+  pub pictrs_delete_token: String,
+  pub published: chrono::NaiveDateTime,
+  /// Why the image classifier flagged the image, if it did.
+  pub flag_reason: Option<String>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = local_image))]
+pub struct LocalImageForm {
+  pub local_user_id: LocalUserId,
+  pub pictrs_alias: String,
+  pub pictrs_delete_token: String,
+  pub flag_reason: Option<String>,
+}
@@ -59,6 +59,14 @@ pub struct LocalSite {
   pub registration_mode: RegistrationMode,
   /// Whether to email admins on new reports.
   pub reports_email_admins: bool,
+  /// The maximum size of uploaded images in bytes.
+  pub image_upload_max_bytes: Option<i32>,
+  /// The maximum width and height of uploaded images in pixels.
+  pub image_upload_max_dimension: Option<i32>,
+  /// A comma separated list of the MIME types which can be uploaded, like `image/png,image/jpeg`.
+  pub image_upload_allowed_mime_types: Option<String>,
+  /// An external service which decides whether to accept, reject or flag uploaded images.
+  pub image_upload_classifier_url: Option<String>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -87,6 +95,10 @@ pub struct LocalSiteInsertForm {
   pub captcha_difficulty: Option<String>,
   pub registration_mode: Option<RegistrationMode>,
   pub reports_email_admins: Option<bool>,
+  pub image_upload_max_bytes: Option<i32>,
+  pub image_upload_max_dimension: Option<i32>,
+  pub image_upload_allowed_mime_types: Option<String>,
+  pub image_upload_classifier_url: Option<String>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -113,5 +125,9 @@ pub struct LocalSiteUpdateForm {
   pub captcha_difficulty: Option<String>,
   pub registration_mode: Option<RegistrationMode>,
   pub reports_email_admins: Option<bool>,
+  pub image_upload_max_bytes: Option<Option<i32>>,
+  pub image_upload_max_dimension: Option<Option<i32>>,
+  pub image_upload_allowed_mime_types: Option<Option<String>>,
+  pub image_upload_classifier_url: Option<Option<String>>,
   pub updated: Option<Option<chrono::NaiveDateTime>>,
 }
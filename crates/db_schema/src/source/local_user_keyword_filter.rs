@@ -0,0 +1,47 @@
+#[cfg(feature = "full")]
+use crate::schema::local_user_keyword_filter;
+use crate::{
+  newtypes::{KeywordFilterId, LocalUserId},
+  KeywordFilterAction,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::local_user::LocalUser))
+)]
+#[cfg_attr(feature = "full", diesel(table_name = local_user_keyword_filter))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A keyword which a user doesn't want to see in post and comment listings.
+pub struct LocalUserKeywordFilter {
+  pub id: KeywordFilterId,
+  pub local_user_id: LocalUserId,
+  /// Matched case-insensitively against post titles, bodies and url domains, and comment
+  /// contents.
+  pub keyword: String,
+  pub action: KeywordFilterAction,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = local_user_keyword_filter))]
+pub struct LocalUserKeywordFilterInsertForm {
+  pub local_user_id: LocalUserId,
+  pub keyword: String,
+  pub action: Option<KeywordFilterAction>,
+}
+
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = local_user_keyword_filter))]
+pub struct LocalUserKeywordFilterUpdateForm {
+  pub keyword: Option<String>,
+  pub action: Option<KeywordFilterAction>,
+  pub updated: Option<Option<chrono::NaiveDateTime>>,
+}
@@ -0,0 +1,38 @@
+use crate::newtypes::{LocalUserId, LoginTokenId};
+#[cfg(feature = "full")]
+use crate::schema::login_token;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = login_token))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Stores data related to a specific user login session.
+pub struct LoginToken {
+  pub id: LoginTokenId,
+  /// Hash of the jwt which was handed out for this login.
+  #[serde(skip)]
+  pub token_encrypted: String,
+  pub user_id: LocalUserId,
+  /// Time of login.
+  pub published: chrono::NaiveDateTime,
+  /// Time when the token was last used for an api request.
+  pub last_used: chrono::NaiveDateTime,
+  /// IP address where login was made from, allows invalidating logins by IP address.
+  pub ip: Option<String>,
+  /// User-Agent header of the client which logged in.
+  pub user_agent: Option<String>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = login_token))]
+pub struct LoginTokenCreateForm {
+  pub token_encrypted: String,
+  pub user_id: LocalUserId,
+  pub ip: Option<String>,
+  pub user_agent: Option<String>,
+}
@@ -7,26 +7,38 @@ pub mod comment_reply;
 pub mod comment_report;
 pub mod community;
 pub mod community_block;
+pub mod community_post_tag;
+pub mod community_recurring_post;
 pub mod custom_emoji;
 pub mod custom_emoji_keyword;
 pub mod email_verification;
 pub mod federation_allowlist;
 pub mod federation_blocklist;
+#[cfg(feature = "full")]
+pub mod federation_queue_state;
 pub mod instance;
+pub mod instance_block;
 pub mod language;
+pub mod local_image;
 pub mod local_site;
 pub mod local_site_rate_limit;
 pub mod local_user;
+pub mod local_user_keyword_filter;
+pub mod login_token;
+pub mod mod_note;
 pub mod moderator;
+pub mod oauth_account;
 pub mod password_reset_request;
 pub mod person;
 pub mod person_block;
 pub mod person_mention;
+pub mod poll;
 pub mod post;
 pub mod post_report;
 pub mod private_message;
 pub mod private_message_report;
 pub mod registration_application;
+pub mod remote_image;
 pub mod secret;
 pub mod site;
 pub mod tagline;
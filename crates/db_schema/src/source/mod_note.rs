@@ -0,0 +1,35 @@
+use crate::newtypes::{CommunityId, ModNoteId, PersonId};
+#[cfg(feature = "full")]
+use crate::schema::mod_note;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_note))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A note which moderators or admins keep about a person, like the reason for a warning.
+pub struct ModNote {
+  pub id: ModNoteId,
+  /// The person the note is about.
+  pub target_person_id: PersonId,
+  /// The community whose moderators can see the note. Notes without a community are site-wide,
+  /// and only visible to admins.
+  pub community_id: Option<CommunityId>,
+  pub author_id: PersonId,
+  pub content: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_note))]
+pub struct ModNoteForm {
+  pub target_person_id: PersonId,
+  pub community_id: Option<CommunityId>,
+  pub author_id: PersonId,
+  pub content: String,
+}
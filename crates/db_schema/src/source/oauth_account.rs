@@ -0,0 +1,29 @@
+use crate::newtypes::{LocalUserId, OAuthAccountId};
+#[cfg(feature = "full")]
+use crate::schema::oauth_account;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = oauth_account))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An account at an external OpenID Connect provider, which is linked to a local user.
+pub struct OAuthAccount {
+  pub id: OAuthAccountId,
+  pub local_user_id: LocalUserId,
+  /// Name of the provider, as given in the config file.
+  pub provider: String,
+  /// The `sub` claim, which identifies the user at the provider.
+  pub subject: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = oauth_account))]
+pub struct OAuthAccountInsertForm {
+  pub local_user_id: LocalUserId,
+  pub provider: String,
+  pub subject: String,
+}
@@ -53,6 +53,12 @@ pub struct Person {
   /// When their ban, if it exists, expires, if at all.
   pub ban_expires: Option<chrono::NaiveDateTime>,
   pub instance_id: InstanceId,
+  /// The account which this person moved to, if any.
+  pub moved_to: Option<DbUrl>,
+  /// An account which is allowed to move to this person.
+  pub also_known_as: Option<DbUrl>,
+  /// A public key which clients use to encrypt private messages to the person.
+  pub encryption_public_key: Option<String>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -84,6 +90,9 @@ pub struct PersonInsertForm {
   pub admin: Option<bool>,
   pub bot_account: Option<bool>,
   pub ban_expires: Option<chrono::NaiveDateTime>,
+  pub moved_to: Option<DbUrl>,
+  pub also_known_as: Option<DbUrl>,
+  pub encryption_public_key: Option<String>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -109,6 +118,9 @@ pub struct PersonUpdateForm {
   pub admin: Option<bool>,
   pub bot_account: Option<bool>,
   pub ban_expires: Option<Option<chrono::NaiveDateTime>>,
+  pub moved_to: Option<Option<DbUrl>>,
+  pub also_known_as: Option<Option<DbUrl>>,
+  pub encryption_public_key: Option<Option<String>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -0,0 +1,81 @@
+use crate::newtypes::{PersonId, PollOptionId, PostId};
+#[cfg(feature = "full")]
+use crate::schema::{poll, poll_option, poll_vote};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = poll))]
+#[cfg_attr(feature = "full", diesel(primary_key(post_id)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A poll, which is attached to a post.
+pub struct Poll {
+  pub post_id: PostId,
+  /// Whether voters can choose more than one option.
+  pub multiple_choice: bool,
+  /// No more votes are accepted after this time.
+  pub end_time: Option<chrono::NaiveDateTime>,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = poll))]
+pub struct PollForm {
+  pub post_id: PostId,
+  pub multiple_choice: bool,
+  pub end_time: Option<chrono::NaiveDateTime>,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::poll::Poll, foreign_key = post_id))
+)]
+#[cfg_attr(feature = "full", diesel(table_name = poll_option))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An option which can be voted for in a poll.
+pub struct PollOption {
+  pub id: PollOptionId,
+  pub post_id: PostId,
+  pub name: String,
+  /// For polls from other instances, the number of votes which that instance counted.
+  pub remote_vote_count: i32,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = poll_option))]
+pub struct PollOptionForm {
+  pub post_id: PostId,
+  pub name: String,
+  pub remote_vote_count: Option<i32>,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::poll::PollOption)))]
+#[cfg_attr(feature = "full", diesel(table_name = poll_vote))]
+pub struct PollVote {
+  pub poll_option_id: PollOptionId,
+  pub person_id: PersonId,
+  pub post_id: PostId,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = poll_vote))]
+pub struct PollVoteForm {
+  pub poll_option_id: PollOptionId,
+  pub person_id: PersonId,
+  pub post_id: PostId,
+}
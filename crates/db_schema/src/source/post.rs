@@ -1,6 +1,6 @@
 use crate::newtypes::{CommunityId, DbUrl, LanguageId, PersonId, PostId};
 #[cfg(feature = "full")]
-use crate::schema::{post, post_like, post_read, post_saved};
+use crate::schema::{post, post_edit, post_like, post_read, post_saved};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
@@ -53,6 +53,10 @@ pub struct Post {
   pub featured_community: bool,
   /// Whether the post is featured to its site.
   pub featured_local: bool,
+  /// For posts which are not published yet, the time when they will be published.
+  pub scheduled_publish_time: Option<chrono::NaiveDateTime>,
+  /// The MIME type of the url, like `video/mp4`, so that videos and audio can be played directly.
+  pub url_content_type: Option<String>,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -83,6 +87,8 @@ pub struct PostInsertForm {
   pub language_id: Option<LanguageId>,
   pub featured_community: Option<bool>,
   pub featured_local: Option<bool>,
+  pub scheduled_publish_time: Option<chrono::NaiveDateTime>,
+  pub url_content_type: Option<String>,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -108,6 +114,8 @@ pub struct PostUpdateForm {
   pub language_id: Option<LanguageId>,
   pub featured_community: Option<bool>,
   pub featured_local: Option<bool>,
+  pub scheduled_publish_time: Option<Option<chrono::NaiveDateTime>>,
+  pub url_content_type: Option<Option<String>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -166,3 +174,31 @@ pub struct PostReadForm {
   pub post_id: PostId,
   pub person_id: PersonId,
 }
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Identifiable, Queryable, Associations, TS))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = post_edit))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A previous version of an edited post.
+pub struct PostEdit {
+  pub id: i32,
+  pub post_id: PostId,
+  pub name: String,
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  pub url: Option<DbUrl>,
+  pub body: Option<String>,
+  /// When this version was written.
+  pub published: chrono::NaiveDateTime,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_edit))]
+pub struct PostEditForm {
+  pub post_id: PostId,
+  pub name: String,
+  pub url: Option<DbUrl>,
+  pub body: Option<String>,
+  pub published: chrono::NaiveDateTime,
+}
@@ -1,6 +1,6 @@
-use crate::newtypes::{DbUrl, PersonId, PrivateMessageId};
+use crate::newtypes::{ConversationId, DbUrl, PersonId, PrivateMessageId};
 #[cfg(feature = "full")]
-use crate::schema::private_message;
+use crate::schema::{conversation, conversation_participant, private_message};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
@@ -20,6 +20,8 @@ use typed_builder::TypedBuilder;
 pub struct PrivateMessage {
   pub id: PrivateMessageId,
   pub creator_id: PersonId,
+  /// For messages with multiple recipients, this is the first one. The others are the remaining
+  /// participants of the conversation.
   pub recipient_id: PersonId,
   pub content: String,
   pub deleted: bool,
@@ -28,6 +30,10 @@ pub struct PrivateMessage {
   pub updated: Option<chrono::NaiveDateTime>,
   pub ap_id: DbUrl,
   pub local: bool,
+  pub conversation_id: ConversationId,
+  /// The end-to-end encrypted message, if any. Then `content` is only a notice for clients
+  /// without encryption support.
+  pub encrypted_content: Option<String>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -47,6 +53,9 @@ pub struct PrivateMessageInsertForm {
   pub updated: Option<chrono::NaiveDateTime>,
   pub ap_id: Option<DbUrl>,
   pub local: Option<bool>,
+  #[builder(!default)]
+  pub conversation_id: ConversationId,
+  pub encrypted_content: Option<String>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -61,4 +70,28 @@ pub struct PrivateMessageUpdateForm {
   pub updated: Option<Option<chrono::NaiveDateTime>>,
   pub ap_id: Option<DbUrl>,
   pub local: Option<bool>,
+  pub encrypted_content: Option<Option<String>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = conversation))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The private messages between a fixed group of people.
+pub struct Conversation {
+  pub id: ConversationId,
+  pub published: chrono::NaiveDateTime,
+  /// The time of the latest message.
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "full", derive(Queryable, Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = conversation_participant))]
+pub struct ConversationParticipant {
+  pub conversation_id: ConversationId,
+  pub person_id: PersonId,
+  /// Messages published after this time are unread for the participant.
+  pub last_read: Option<chrono::NaiveDateTime>,
 }
@@ -0,0 +1,26 @@
+use crate::newtypes::{DbUrl, RemoteImageId};
+#[cfg(feature = "full")]
+use crate::schema::remote_image;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = remote_image))]
+/// An image from another server which the image proxy may serve, and its copy in pict-rs if the
+/// image proxy stored one.
+pub struct RemoteImage {
+  pub id: RemoteImageId,
+  /// The original link of the image.
+  pub link: DbUrl,
+  pub pictrs_alias: Option<String>,
+  pub pictrs_delete_token: Option<String>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = remote_image))]
+pub struct RemoteImageForm {
+  pub link: DbUrl,
+  pub pictrs_alias: Option<String>,
+  pub pictrs_delete_token: Option<String>,
+}
@@ -11,10 +11,12 @@ use deadpool::Runtime;
 use diesel::{
   backend::Backend,
   deserialize::FromSql,
+  dsl::{sql, AsExprOf},
+  expression::{SqlLiteral, UncheckedBind},
   pg::Pg,
   result::{ConnectionError, ConnectionResult, Error as DieselError, Error::QueryBuilderError},
   serialize::{Output, ToSql},
-  sql_types::Text,
+  sql_types::{Bool, Float, Text},
   PgConnection,
 };
 use diesel_async::{
@@ -61,6 +63,36 @@ pub fn fuzzy_search(q: &str) -> String {
   format!("%{replaced}%")
 }
 
+/// An SQL expression on the `search_vector` column of a post or comment, with the search query
+/// bound as parameter.
+pub type FullTextSearch<ST> = SqlLiteral<ST, UncheckedBind<SqlLiteral<ST>, AsExprOf<String, Text>>>;
+
+/// Matches the `search_vector` column of the given table against a query in web search syntax.
+///
+/// The query is parsed with the text search configuration of each row's language, the same one
+/// which its search vector was built with, so that stemming, negation and phrases all behave as
+/// they would for a single language.
+///
+/// The column is removed from `schema.rs` by `schema.patch`, so that it isn't loaded together with
+/// every post and comment. That's why it can only be used through raw SQL.
+pub fn full_text_search(table: &str, q: &str) -> FullTextSearch<Bool> {
+  sql::<Bool>(&format!(
+    "{table}.search_vector @@ websearch_to_tsquery(language_ts_config({table}.language_id), "
+  ))
+  .bind::<Text, _>(q.to_string())
+  .sql(")")
+}
+
+/// How well the given table's `search_vector` matches the query, for ordering by relevance.
+pub fn full_text_search_rank(table: &str, q: &str) -> FullTextSearch<Float> {
+  sql::<Float>(&format!(
+    "ts_rank_cd({table}.search_vector, \
+     websearch_to_tsquery(language_ts_config({table}.language_id), "
+  ))
+  .bind::<Text, _>(q.to_string())
+  .sql("))")
+}
+
 pub fn limit_and_offset(
   page: Option<i64>,
   limit: Option<i64>,
@@ -11,6 +11,7 @@ use diesel::{
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   aggregates::structs::CommentAggregates,
+  impls::mod_note::ModNoteCounts,
   newtypes::{CommentReportId, CommunityId, PersonId},
   schema::{
     comment,
@@ -27,6 +28,7 @@ use lemmy_db_schema::{
     comment::Comment,
     comment_report::CommentReport,
     community::{Community, CommunityPersonBan},
+    mod_note::ModNote,
     person::Person,
     post::Post,
   },
@@ -91,7 +93,15 @@ impl CommentReportView {
       .first::<<CommentReportView as JoinView>::JoinTuple>(conn)
       .await?;
 
-    Ok(Self::from_tuple(res))
+    let mut view = Self::from_tuple(res);
+    view.mod_note_count = ModNote::count_for_community(
+      pool,
+      view.comment_creator.id,
+      view.community.id,
+      my_person_id,
+    )
+    .await?;
+    Ok(view)
   }
 
   /// Returns the current unresolved post report count for the communities you mod
@@ -237,7 +247,19 @@ impl<'a> CommentReportQuery<'a> {
         .await?
     };
 
-    Ok(res.into_iter().map(CommentReportView::from_tuple).collect())
+    let creator_ids = res.iter().map(|r| r.5.id).collect();
+    let mod_note_counts =
+      ModNoteCounts::read(self.pool, creator_ids, self.my_person_id, self.admin).await?;
+    Ok(
+      res
+        .into_iter()
+        .map(CommentReportView::from_tuple)
+        .map(|mut v| {
+          v.mod_note_count = Some(mod_note_counts.get(v.comment_creator.id));
+          v
+        })
+        .collect(),
+    )
   }
 }
 
@@ -267,6 +289,7 @@ impl JoinView for CommentReportView {
       creator_banned_from_community: a.7.is_some(),
       my_vote: a.8,
       resolver: a.9,
+      mod_note_count: None,
     }
   }
 }
@@ -440,6 +463,9 @@ mod tests {
         matrix_user_id: None,
         ban_expires: None,
         instance_id: inserted_instance.id,
+        moved_to: None,
+        also_known_as: None,
+        encryption_public_key: None,
         private_key: inserted_jessica.private_key,
         public_key: inserted_jessica.public_key,
         last_refreshed_at: inserted_jessica.last_refreshed_at,
@@ -464,6 +490,9 @@ mod tests {
         matrix_user_id: None,
         ban_expires: None,
         instance_id: inserted_instance.id,
+        moved_to: None,
+        also_known_as: None,
+        encryption_public_key: None,
         private_key: inserted_timmy.private_key.clone(),
         public_key: inserted_timmy.public_key.clone(),
         last_refreshed_at: inserted_timmy.last_refreshed_at,
@@ -481,6 +510,7 @@ mod tests {
       },
       my_vote: None,
       resolver: None,
+      mod_note_count: Some(0),
     };
 
     assert_eq!(read_jessica_report_view, expected_jessica_report_view);
@@ -507,6 +537,9 @@ mod tests {
       matrix_user_id: None,
       ban_expires: None,
       instance_id: inserted_instance.id,
+      moved_to: None,
+      also_known_as: None,
+      encryption_public_key: None,
       private_key: inserted_sara.private_key,
       public_key: inserted_sara.public_key,
       last_refreshed_at: inserted_sara.last_refreshed_at,
@@ -580,6 +613,9 @@ mod tests {
       matrix_user_id: None,
       ban_expires: None,
       instance_id: inserted_instance.id,
+      moved_to: None,
+      also_known_as: None,
+      encryption_public_key: None,
     });
 
     assert_eq!(
@@ -1,4 +1,5 @@
 use crate::structs::CommentView;
+use chrono::NaiveDateTime;
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -12,7 +13,7 @@ use diesel_async::RunQueryDsl;
 use diesel_ltree::{nlevel, subpath, Ltree, LtreeExtensions};
 use lemmy_db_schema::{
   aggregates::structs::CommentAggregates,
-  newtypes::{CommentId, CommunityId, LocalUserId, PersonId, PostId},
+  newtypes::{CommentId, CommunityId, InstanceId, LocalUserId, PersonId, PostId},
   schema::{
     comment,
     comment_aggregates,
@@ -22,22 +23,26 @@ use lemmy_db_schema::{
     community_block,
     community_follower,
     community_person_ban,
+    instance_block,
     local_user_language,
     person,
     person_block,
+    person_follower,
     post,
   },
   source::{
     comment::{Comment, CommentSaved},
     community::{Community, CommunityFollower, CommunityPersonBan},
     local_user::LocalUser,
+    local_user_keyword_filter::LocalUserKeywordFilter,
     person::Person,
     person_block::PersonBlock,
     post::Post,
   },
   traits::JoinView,
-  utils::{fuzzy_search, get_conn, limit_and_offset_unlimited, DbPool},
+  utils::{full_text_search, full_text_search_rank, get_conn, limit_and_offset_unlimited, DbPool},
   CommentSortType,
+  KeywordFilterAction,
   ListingType,
 };
 use typed_builder::TypedBuilder;
@@ -152,6 +157,7 @@ impl CommentView {
       saved: saved.is_some(),
       creator_blocked: creator_blocked.is_some(),
       my_vote,
+      keyword_filtered: false,
     })
   }
 }
@@ -169,6 +175,12 @@ pub struct CommentQuery<'a> {
   creator_id: Option<PersonId>,
   local_user: Option<&'a LocalUser>,
   search_term: Option<String>,
+  /// Only comments in communities of this instance
+  instance_id: Option<InstanceId>,
+  /// Only comments on NSFW posts if true, or only comments on posts which aren't NSFW if false
+  nsfw: Option<bool>,
+  published_after: Option<NaiveDateTime>,
+  published_before: Option<NaiveDateTime>,
   saved_only: Option<bool>,
   show_deleted_and_removed: Option<bool>,
   page: Option<i64>,
@@ -178,6 +190,13 @@ pub struct CommentQuery<'a> {
 
 impl<'a> CommentQuery<'a> {
   pub async fn list(self) -> Result<Vec<CommentView>, Error> {
+    // Keyword filters don't apply to the user's own comments
+    let keyword_filters = match self.local_user {
+      Some(local_user) if self.creator_id != Some(local_user.person_id) => {
+        LocalUserKeywordFilter::list(self.pool, local_user.id).await?
+      }
+      _ => vec![],
+    };
     let conn = &mut get_conn(self.pool).await?;
 
     // The left join below will return None in this case
@@ -224,6 +243,13 @@ impl<'a> CommentQuery<'a> {
             .and(community_block::person_id.eq(person_id_join)),
         ),
       )
+      .left_join(
+        instance_block::table.on(
+          community::instance_id
+            .eq(instance_block::instance_id)
+            .and(instance_block::local_user_id.eq(local_user_id_join)),
+        ),
+      )
       .left_join(
         comment_like::table.on(
           comment::id
@@ -238,6 +264,14 @@ impl<'a> CommentQuery<'a> {
             .and(local_user_language::local_user_id.eq(local_user_id_join)),
         ),
       )
+      .left_join(
+        person_follower::table.on(
+          comment::creator_id
+            .eq(person_follower::person_id)
+            .and(person_follower::follower_id.eq(person_id_join))
+            .and(person_follower::pending.eq(false)),
+        ),
+      )
       .select((
         comment::all_columns,
         person::all_columns,
@@ -264,19 +298,45 @@ impl<'a> CommentQuery<'a> {
       query = query.filter(comment::path.contained_by(parent_path));
     };
 
-    if let Some(search_term) = self.search_term {
-      query = query.filter(comment::content.ilike(fuzzy_search(&search_term)));
+    if let Some(search_term) = &self.search_term {
+      query = query.filter(full_text_search("comment", search_term));
     };
 
+    // Comments on scheduled posts stay hidden until the post is published
+    query = query.filter(post::scheduled_publish_time.is_null());
+
     if let Some(community_id) = self.community_id {
       query = query.filter(post::community_id.eq(community_id));
     }
 
+    if let Some(instance_id) = self.instance_id {
+      query = query.filter(community::instance_id.eq(instance_id));
+    }
+
+    match self.nsfw {
+      Some(true) => query = query.filter(post::nsfw.eq(true).or(community::nsfw.eq(true))),
+      Some(false) => {
+        query = query
+          .filter(post::nsfw.eq(false))
+          .filter(community::nsfw.eq(false))
+      }
+      None => {}
+    }
+
+    if let Some(published_after) = self.published_after {
+      query = query.filter(comment::published.ge(published_after));
+    }
+
+    if let Some(published_before) = self.published_before {
+      query = query.filter(comment::published.lt(published_before));
+    }
+
     if let Some(listing_type) = self.listing_type {
       match listing_type {
         ListingType::Subscribed => {
           query = query.filter(community_follower::person_id.is_not_null())
         } // TODO could be this: and(community_follower::person_id.eq(person_id_join)),
+        ListingType::Followed => query = query.filter(person_follower::follower_id.is_not_null()),
         ListingType::Local => {
           query = query.filter(community::local.eq(true)).filter(
             community::hidden
@@ -311,9 +371,20 @@ impl<'a> CommentQuery<'a> {
       // Filter out the rows with missing languages
       query = query.filter(local_user_language::language_id.is_not_null());
 
-      // Don't show blocked communities or persons
+      // Don't show blocked communities, instances or persons
       if self.post_id.is_none() {
         query = query.filter(community_block::person_id.is_null());
+        query = query.filter(instance_block::local_user_id.is_null());
+      }
+
+      // Hiding comments inside of a post would break up the comment tree, so they are only
+      // collapsed there
+      if self.post_id.is_none() {
+        for filter in &keyword_filters {
+          if filter.action == KeywordFilterAction::Hide {
+            query = query.filter(comment::content.not_ilike(filter.like_pattern()));
+          }
+        }
       }
       query = query.filter(person_block::person_id.is_null());
     }
@@ -345,11 +416,26 @@ impl<'a> CommentQuery<'a> {
       limit_and_offset_unlimited(self.page, self.limit)
     };
 
-    query = match self.sort.unwrap_or(CommentSortType::Hot) {
+    // Search results are ordered by relevance first, the sort type only decides between equally
+    // relevant results
+    let sort = match &self.search_term {
+      Some(search_term) => {
+        query = query.then_order_by(full_text_search_rank("comment", search_term).desc());
+        self.sort.unwrap_or(CommentSortType::New)
+      }
+      None => self.sort.unwrap_or(CommentSortType::Hot),
+    };
+
+    query = match sort {
       CommentSortType::Hot => query.then_order_by(comment_aggregates::hot_rank.desc()),
       CommentSortType::New => query.then_order_by(comment::published.desc()),
       CommentSortType::Old => query.then_order_by(comment::published.asc()),
-      CommentSortType::Top => query.order_by(comment_aggregates::score.desc()),
+      // Top replaces any previous ordering, including the parent path of tree fetches. Search
+      // results still need to stay ordered by relevance first.
+      CommentSortType::Top if self.search_term.is_none() => {
+        query.order_by(comment_aggregates::score.desc())
+      }
+      CommentSortType::Top => query.then_order_by(comment_aggregates::score.desc()),
     };
 
     // Note: deleted and removed comments are done on the front side
@@ -359,7 +445,18 @@ impl<'a> CommentQuery<'a> {
       .load::<CommentViewTuple>(conn)
       .await?;
 
-    Ok(res.into_iter().map(CommentView::from_tuple).collect())
+    Ok(
+      res
+        .into_iter()
+        .map(CommentView::from_tuple)
+        .map(|mut comment_view| {
+          comment_view.keyword_filtered = keyword_filters.iter().any(|f| {
+            f.action == KeywordFilterAction::Collapse && f.matches_comment(&comment_view.comment)
+          });
+          comment_view
+        })
+        .collect(),
+    )
   }
 }
 
@@ -377,6 +474,7 @@ impl JoinView for CommentView {
       saved: a.7.is_some(),
       creator_blocked: a.8.is_some(),
       my_vote: a.9,
+      keyword_filtered: false,
     }
   }
 }
@@ -395,6 +493,7 @@ mod tests {
     PersonBlock,
     Post,
   };
+  use chrono::Duration;
   use lemmy_db_schema::{
     aggregates::structs::CommentAggregates,
     impls::actor_language::UNDETERMINED_ID,
@@ -408,10 +507,10 @@ mod tests {
       local_user::LocalUserInsertForm,
       person::PersonInsertForm,
       person_block::PersonBlockForm,
-      post::PostInsertForm,
+      post::{PostInsertForm, PostUpdateForm},
     },
     traits::{Blockable, Crud, Likeable},
-    utils::build_db_pool_for_tests,
+    utils::{build_db_pool_for_tests, naive_now},
     SubscribedType,
   };
   use serial_test::serial;
@@ -778,6 +877,32 @@ mod tests {
     cleanup(data, pool).await;
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_scheduled_post() {
+    let pool = &build_db_pool_for_tests().await;
+    let data = init_data(pool).await;
+
+    let form = PostUpdateForm::builder()
+      .scheduled_publish_time(Some(Some(naive_now() + Duration::days(1))))
+      .build();
+    Post::update(pool, data.inserted_post.id, &form)
+      .await
+      .unwrap();
+
+    // Comments on scheduled posts are hidden until the post is published
+    let comments = CommentQuery::builder()
+      .pool(pool)
+      .post_id(Some(data.inserted_post.id))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert!(comments.is_empty());
+
+    cleanup(data, pool).await;
+  }
+
   async fn cleanup(data: Data, pool: &DbPool) {
     CommentLike::remove(pool, data.inserted_person.id, data.inserted_comment_0.id)
       .await
@@ -811,6 +936,7 @@ mod tests {
       subscribed: SubscribedType::NotSubscribed,
       saved: false,
       creator_blocked: false,
+      keyword_filtered: false,
       comment: Comment {
         id: data.inserted_comment_0.id,
         content: "Comment 0".into(),
@@ -846,6 +972,9 @@ mod tests {
         matrix_user_id: None,
         ban_expires: None,
         instance_id: data.inserted_instance.id,
+        moved_to: None,
+        also_known_as: None,
+        encryption_public_key: None,
         private_key: data.inserted_person.private_key.clone(),
         public_key: data.inserted_person.public_key.clone(),
         last_refreshed_at: data.inserted_person.last_refreshed_at,
@@ -872,6 +1001,8 @@ mod tests {
         language_id: Default::default(),
         featured_community: false,
         featured_local: false,
+        scheduled_publish_time: None,
+        url_content_type: None,
       },
       community: Community {
         id: data.inserted_community.id,
@@ -0,0 +1,251 @@
+use crate::structs::ConversationView;
+use chrono::NaiveDateTime;
+use diesel::{
+  dsl::count_star,
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  JoinOnDsl,
+  PgSortExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::{ConversationId, PersonId},
+  schema::{conversation, conversation_participant, person, private_message},
+  source::{
+    person::Person,
+    private_message::{Conversation, PrivateMessage},
+  },
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+use std::collections::HashMap;
+use typed_builder::TypedBuilder;
+
+#[derive(TypedBuilder)]
+#[builder(field_defaults(default))]
+pub struct ConversationQuery<'a> {
+  #[builder(!default)]
+  pool: &'a DbPool,
+  #[builder(!default)]
+  person_id: PersonId,
+  page: Option<i64>,
+  limit: Option<i64>,
+}
+
+impl<'a> ConversationQuery<'a> {
+  /// The conversations of the person, those with the latest messages first.
+  pub async fn list(self) -> Result<Vec<ConversationView>, Error> {
+    let conn = &mut get_conn(self.pool).await?;
+    let (limit, offset) = limit_and_offset(self.page, self.limit)?;
+
+    let conversations = conversation::table
+      .inner_join(conversation_participant::table)
+      .filter(conversation_participant::person_id.eq(self.person_id))
+      .select((
+        conversation::all_columns,
+        conversation_participant::last_read,
+      ))
+      .order_by(conversation::updated.desc().nulls_last())
+      .then_order_by(conversation::id.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<(Conversation, Option<NaiveDateTime>)>(conn)
+      .await?;
+    let conversation_ids: Vec<ConversationId> = conversations.iter().map(|(c, _)| c.id).collect();
+
+    let mut participants: HashMap<ConversationId, Vec<Person>> = HashMap::new();
+    conversation_participant::table
+      .inner_join(person::table)
+      .filter(conversation_participant::conversation_id.eq_any(conversation_ids.clone()))
+      .select((
+        conversation_participant::conversation_id,
+        person::all_columns,
+      ))
+      .order_by(person::id)
+      .load::<(ConversationId, Person)>(conn)
+      .await?
+      .into_iter()
+      .for_each(|(conversation_id, person)| {
+        participants
+          .entry(conversation_id)
+          .or_default()
+          .push(person)
+      });
+
+    let mut last_messages: HashMap<ConversationId, PrivateMessage> = private_message::table
+      .filter(private_message::conversation_id.eq_any(conversation_ids.clone()))
+      .filter(private_message::deleted.eq(false))
+      .distinct_on(private_message::conversation_id)
+      .order_by((
+        private_message::conversation_id,
+        private_message::published.desc(),
+      ))
+      .load::<PrivateMessage>(conn)
+      .await?
+      .into_iter()
+      .map(|pm| (pm.conversation_id, pm))
+      .collect();
+
+    let unread_counts: HashMap<ConversationId, i64> = private_message::table
+      .inner_join(
+        conversation_participant::table.on(
+          private_message::conversation_id
+            .eq(conversation_participant::conversation_id)
+            .and(conversation_participant::person_id.eq(self.person_id)),
+        ),
+      )
+      .filter(private_message::conversation_id.eq_any(conversation_ids))
+      .filter(private_message::creator_id.ne(self.person_id))
+      .filter(private_message::deleted.eq(false))
+      .filter(
+        conversation_participant::last_read.is_null().or(
+          private_message::published
+            .nullable()
+            .gt(conversation_participant::last_read),
+        ),
+      )
+      .group_by(private_message::conversation_id)
+      .select((private_message::conversation_id, count_star()))
+      .load::<(ConversationId, i64)>(conn)
+      .await?
+      .into_iter()
+      .collect();
+
+    Ok(
+      conversations
+        .into_iter()
+        .map(|(conversation, last_read)| {
+          let mut last_message = last_messages.remove(&conversation.id);
+          // Like in the message list, `read` is from our own point of view
+          if let Some(pm) = &mut last_message {
+            pm.read =
+              pm.creator_id == self.person_id || matches!(last_read, Some(l) if pm.published <= l);
+          }
+          ConversationView {
+            participants: participants.remove(&conversation.id).unwrap_or_default(),
+            unread_count: unread_counts.get(&conversation.id).copied().unwrap_or(0),
+            last_message,
+            conversation,
+          }
+        })
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::conversation_view::ConversationQuery;
+  use lemmy_db_schema::{
+    source::{
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      private_message::{
+        Conversation,
+        ConversationParticipant,
+        PrivateMessage,
+        PrivateMessageInsertForm,
+      },
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_conversations() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let mut people = vec![];
+    for name in ["alice_cv", "bob_cv", "carol_cv"] {
+      let form = PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build();
+      people.push(Person::create(pool, &form).await.unwrap());
+    }
+    let (alice, bob, carol) = (&people[0], &people[1], &people[2]);
+
+    // Alice writes to bob alone, and to both bob and carol
+    let direct = Conversation::read_or_create(pool, &[alice.id, bob.id])
+      .await
+      .unwrap();
+    let group = Conversation::read_or_create(pool, &[alice.id, bob.id, carol.id])
+      .await
+      .unwrap();
+    assert_ne!(direct.id, group.id);
+    for (conversation, content) in [(&direct, "hi bob"), (&group, "hi all"), (&group, "hello?")] {
+      let form = PrivateMessageInsertForm::builder()
+        .creator_id(alice.id)
+        .recipient_id(bob.id)
+        .content(content.to_string())
+        .conversation_id(conversation.id)
+        .build();
+      let pm = PrivateMessage::create(pool, &form).await.unwrap();
+      Conversation::update_for_message(pool, conversation.id, pm.published)
+        .await
+        .unwrap();
+    }
+
+    let bob_conversations = ConversationQuery::builder()
+      .pool(pool)
+      .person_id(bob.id)
+      .build()
+      .list()
+      .await
+      .unwrap();
+    // The group conversation has the latest message
+    assert_eq!(2, bob_conversations.len());
+    assert_eq!(group.id, bob_conversations[0].conversation.id);
+    assert_eq!(3, bob_conversations[0].participants.len());
+    assert_eq!(2, bob_conversations[0].unread_count);
+    assert_eq!(
+      Some("hello?".to_string()),
+      bob_conversations[0]
+        .last_message
+        .as_ref()
+        .map(|pm| pm.content.clone())
+    );
+    assert_eq!(1, bob_conversations[1].unread_count);
+
+    // Carol only sees the group conversation, and after reading it nothing is unread
+    let last_published = bob_conversations[0]
+      .last_message
+      .as_ref()
+      .map(|pm| pm.published);
+    ConversationParticipant::update_last_read(pool, group.id, carol.id, last_published)
+      .await
+      .unwrap();
+    let carol_conversations = ConversationQuery::builder()
+      .pool(pool)
+      .person_id(carol.id)
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert_eq!(1, carol_conversations.len());
+    assert_eq!(0, carol_conversations[0].unread_count);
+
+    // Messages sent by oneself are never unread
+    let alice_conversations = ConversationQuery::builder()
+      .pool(pool)
+      .person_id(alice.id)
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert!(alice_conversations.iter().all(|c| c.unread_count == 0));
+
+    for person in &people {
+      Person::delete(pool, person.id).await.unwrap();
+    }
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -6,10 +6,18 @@ pub mod comment_report_view;
 #[cfg(feature = "full")]
 pub mod comment_view;
 #[cfg(feature = "full")]
+pub mod conversation_view;
+#[cfg(feature = "full")]
 pub mod custom_emoji_view;
 #[cfg(feature = "full")]
+pub mod local_image_view;
+#[cfg(feature = "full")]
 pub mod local_user_view;
 #[cfg(feature = "full")]
+pub mod mod_note_view;
+#[cfg(feature = "full")]
+pub mod poll_view;
+#[cfg(feature = "full")]
 pub mod post_report_view;
 #[cfg(feature = "full")]
 pub mod post_view;
@@ -0,0 +1,58 @@
+use crate::structs::LocalImageView;
+use diesel::{result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::LocalUserId,
+  schema::{local_image, local_user, person},
+  source::{local_image::LocalImage, person::Person},
+  traits::JoinView,
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+use typed_builder::TypedBuilder;
+
+type LocalImageViewTuple = (LocalImage, Person);
+
+#[derive(TypedBuilder)]
+#[builder(field_defaults(default))]
+pub struct LocalImageQuery<'a> {
+  #[builder(!default)]
+  pool: &'a DbPool,
+  /// Only the images of this user, otherwise those of all users.
+  local_user_id: Option<LocalUserId>,
+  page: Option<i64>,
+  limit: Option<i64>,
+}
+
+impl<'a> LocalImageQuery<'a> {
+  /// The newest uploads come first.
+  pub async fn list(self) -> Result<Vec<LocalImageView>, Error> {
+    let conn = &mut get_conn(self.pool).await?;
+    let (limit, offset) = limit_and_offset(self.page, self.limit)?;
+
+    let mut query = local_image::table
+      .inner_join(local_user::table.inner_join(person::table))
+      .select((local_image::all_columns, person::all_columns))
+      .into_boxed();
+    if let Some(local_user_id) = self.local_user_id {
+      query = query.filter(local_image::local_user_id.eq(local_user_id));
+    }
+    let res = query
+      .order_by(local_image::published.desc())
+      .then_order_by(local_image::id.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<LocalImageViewTuple>(conn)
+      .await?;
+    Ok(res.into_iter().map(LocalImageView::from_tuple).collect())
+  }
+}
+
+impl JoinView for LocalImageView {
+  type JoinTuple = LocalImageViewTuple;
+  fn from_tuple(a: Self::JoinTuple) -> Self {
+    Self {
+      local_image: a.0,
+      person: a.1,
+    }
+  }
+}
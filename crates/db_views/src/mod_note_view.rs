@@ -0,0 +1,69 @@
+use crate::structs::ModNoteView;
+use diesel::{result::Error, ExpressionMethods, JoinOnDsl, NullableExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::{CommunityId, ModNoteId, PersonId},
+  schema::{community, mod_note, person},
+  source::{community::Community, mod_note::ModNote, person::Person},
+  traits::JoinView,
+  utils::{get_conn, DbPool},
+};
+
+type ModNoteViewTuple = (ModNote, Person, Option<Community>);
+
+impl ModNoteView {
+  pub async fn read(pool: &DbPool, note_id: ModNoteId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let res = mod_note::table
+      .find(note_id)
+      .inner_join(person::table.on(mod_note::author_id.eq(person::id)))
+      .left_join(community::table.on(mod_note::community_id.eq(community::id.nullable())))
+      .select((
+        mod_note::all_columns,
+        person::all_columns,
+        community::all_columns.nullable(),
+      ))
+      .first::<ModNoteViewTuple>(conn)
+      .await?;
+    Ok(Self::from_tuple(res))
+  }
+
+  /// The notes about a person, either those of a single community or all of them. The newest
+  /// ones come first.
+  pub async fn list_for_person(
+    pool: &DbPool,
+    target_person_id: PersonId,
+    community_id: Option<CommunityId>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = mod_note::table
+      .inner_join(person::table.on(mod_note::author_id.eq(person::id)))
+      .left_join(community::table.on(mod_note::community_id.eq(community::id.nullable())))
+      .filter(mod_note::target_person_id.eq(target_person_id))
+      .select((
+        mod_note::all_columns,
+        person::all_columns,
+        community::all_columns.nullable(),
+      ))
+      .into_boxed();
+    if let Some(community_id) = community_id {
+      query = query.filter(mod_note::community_id.eq(community_id));
+    }
+    let res = query
+      .order_by(mod_note::published.desc())
+      .load::<ModNoteViewTuple>(conn)
+      .await?;
+    Ok(res.into_iter().map(Self::from_tuple).collect())
+  }
+}
+
+impl JoinView for ModNoteView {
+  type JoinTuple = ModNoteViewTuple;
+  fn from_tuple(a: Self::JoinTuple) -> Self {
+    Self {
+      mod_note: a.0,
+      author: a.1,
+      community: a.2,
+    }
+  }
+}
@@ -0,0 +1,88 @@
+use crate::structs::{PollOptionView, PollView};
+use diesel::{dsl::count_star, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::{PersonId, PollOptionId, PostId},
+  schema::{poll, poll_option, poll_vote},
+  source::poll::{Poll, PollOption},
+  utils::{get_conn, DbPool},
+};
+
+impl PollView {
+  pub async fn read(
+    pool: &DbPool,
+    post_id: PostId,
+    my_person_id: Option<PersonId>,
+  ) -> Result<Option<Self>, Error> {
+    Ok(
+      Self::list_for_posts(pool, &[post_id], my_person_id)
+        .await?
+        .pop(),
+    )
+  }
+
+  /// Reads the polls of multiple posts at once, for post listings. Posts without a poll are
+  /// skipped.
+  pub async fn list_for_posts(
+    pool: &DbPool,
+    post_ids: &[PostId],
+    my_person_id: Option<PersonId>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let polls = poll::table
+      .filter(poll::post_id.eq_any(post_ids))
+      .load::<Poll>(conn)
+      .await?;
+    if polls.is_empty() {
+      return Ok(vec![]);
+    }
+
+    let options = poll_option::table
+      .filter(poll_option::post_id.eq_any(post_ids))
+      .order_by(poll_option::id)
+      .load::<PollOption>(conn)
+      .await?;
+    let vote_counts = poll_vote::table
+      .filter(poll_vote::post_id.eq_any(post_ids))
+      .group_by(poll_vote::poll_option_id)
+      .select((poll_vote::poll_option_id, count_star()))
+      .load::<(PollOptionId, i64)>(conn)
+      .await?;
+    let my_votes = match my_person_id {
+      Some(my_person_id) => {
+        poll_vote::table
+          .filter(poll_vote::post_id.eq_any(post_ids))
+          .filter(poll_vote::person_id.eq(my_person_id))
+          .select(poll_vote::poll_option_id)
+          .load::<PollOptionId>(conn)
+          .await?
+      }
+      None => vec![],
+    };
+
+    Ok(
+      polls
+        .into_iter()
+        .map(|poll| {
+          let options = options
+            .iter()
+            .filter(|o| o.post_id == poll.post_id)
+            .map(|o| {
+              let local_votes = vote_counts
+                .iter()
+                .find(|(id, _)| *id == o.id)
+                .map(|(_, count)| *count)
+                .unwrap_or(0);
+              PollOptionView {
+                poll_option: o.clone(),
+                vote_count: i64::from(o.remote_vote_count) + local_votes,
+                voted: my_votes.contains(&o.id),
+              }
+            })
+            .collect();
+          PollView { poll, options }
+        })
+        .collect(),
+    )
+  }
+}
@@ -10,6 +10,7 @@ use diesel::{
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   aggregates::structs::PostAggregates,
+  impls::mod_note::ModNoteCounts,
   newtypes::{CommunityId, PersonId, PostReportId},
   schema::{
     community,
@@ -23,6 +24,7 @@ use lemmy_db_schema::{
   },
   source::{
     community::{Community, CommunityPersonBan},
+    mod_note::ModNote,
     person::Person,
     post::Post,
     post_report::PostReport,
@@ -105,6 +107,8 @@ impl PostReportView {
       .await?;
 
     let my_vote = post_like;
+    let mod_note_count =
+      ModNote::count_for_community(pool, post_creator.id, community.id, my_person_id).await?;
 
     Ok(Self {
       post_report,
@@ -116,6 +120,7 @@ impl PostReportView {
       my_vote,
       counts,
       resolver,
+      mod_note_count,
     })
   }
 
@@ -246,7 +251,19 @@ impl<'a> PostReportQuery<'a> {
       query.load::<PostReportViewTuple>(conn).await?
     };
 
-    Ok(res.into_iter().map(PostReportView::from_tuple).collect())
+    let creator_ids = res.iter().map(|r| r.4.id).collect();
+    let mod_note_counts =
+      ModNoteCounts::read(self.pool, creator_ids, self.my_person_id, self.admin).await?;
+    Ok(
+      res
+        .into_iter()
+        .map(PostReportView::from_tuple)
+        .map(|mut v| {
+          v.mod_note_count = Some(mod_note_counts.get(v.post_creator.id));
+          v
+        })
+        .collect(),
+    )
   }
 }
 
@@ -263,6 +280,7 @@ impl JoinView for PostReportView {
       my_vote: a.6,
       counts: a.7,
       resolver: a.8,
+      mod_note_count: None,
     }
   }
 }
@@ -426,6 +444,9 @@ mod tests {
         matrix_user_id: None,
         ban_expires: None,
         instance_id: inserted_instance.id,
+        moved_to: None,
+        also_known_as: None,
+        encryption_public_key: None,
         private_key: inserted_jessica.private_key,
         public_key: inserted_jessica.public_key,
         last_refreshed_at: inserted_jessica.last_refreshed_at,
@@ -450,6 +471,9 @@ mod tests {
         matrix_user_id: None,
         ban_expires: None,
         instance_id: inserted_instance.id,
+        moved_to: None,
+        also_known_as: None,
+        encryption_public_key: None,
         private_key: inserted_timmy.private_key.clone(),
         public_key: inserted_timmy.public_key.clone(),
         last_refreshed_at: inserted_timmy.last_refreshed_at,
@@ -472,6 +496,7 @@ mod tests {
         hot_rank_active: 1728,
       },
       resolver: None,
+      mod_note_count: Some(0),
     };
 
     assert_eq!(read_jessica_report_view, expected_jessica_report_view);
@@ -499,6 +524,9 @@ mod tests {
       matrix_user_id: None,
       ban_expires: None,
       instance_id: inserted_instance.id,
+      moved_to: None,
+      also_known_as: None,
+      encryption_public_key: None,
       private_key: inserted_sara.private_key,
       public_key: inserted_sara.public_key,
       last_refreshed_at: inserted_sara.last_refreshed_at,
@@ -567,6 +595,9 @@ mod tests {
       matrix_user_id: None,
       ban_expires: None,
       instance_id: inserted_instance.id,
+      moved_to: None,
+      also_known_as: None,
+      encryption_public_key: None,
       private_key: inserted_timmy.private_key.clone(),
       public_key: inserted_timmy.public_key.clone(),
       last_refreshed_at: inserted_timmy.last_refreshed_at,
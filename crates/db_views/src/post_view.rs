@@ -1,4 +1,5 @@
-use crate::structs::PostView;
+use crate::structs::{PollView, PostView};
+use chrono::NaiveDateTime;
 use diesel::{
   debug_query,
   dsl::{now, IntervalDsl},
@@ -12,35 +13,42 @@ use diesel::{
   NullableExpressionMethods,
   PgTextExpressionMethods,
   QueryDsl,
+  TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   aggregates::structs::PostAggregates,
-  newtypes::{CommunityId, LocalUserId, PersonId, PostId},
+  newtypes::{CommunityId, CommunityPostTagId, InstanceId, LocalUserId, PersonId, PostId},
   schema::{
     community,
     community_block,
     community_follower,
     community_person_ban,
+    instance_block,
     local_user_language,
     person,
     person_block,
+    person_follower,
     person_post_aggregates,
     post,
     post_aggregates,
     post_like,
     post_read,
     post_saved,
+    post_tag,
   },
   source::{
     community::{Community, CommunityFollower, CommunityPersonBan},
+    community_post_tag::CommunityPostTag,
     local_user::LocalUser,
+    local_user_keyword_filter::LocalUserKeywordFilter,
     person::Person,
     person_block::PersonBlock,
     post::{Post, PostRead, PostSaved},
   },
   traits::JoinView,
-  utils::{fuzzy_search, get_conn, limit_and_offset, DbPool},
+  utils::{full_text_search, full_text_search_rank, get_conn, limit_and_offset, DbPool},
+  KeywordFilterAction,
   ListingType,
   SortType,
 };
@@ -62,6 +70,7 @@ type PostViewTuple = (
 );
 
 sql_function!(fn coalesce(x: sql_types::Nullable<sql_types::BigInt>, y: sql_types::BigInt) -> sql_types::BigInt);
+sql_function!(fn split_part(string: sql_types::Nullable<sql_types::Text>, delimiter: sql_types::Text, n: sql_types::Integer) -> sql_types::Nullable<sql_types::Text>);
 
 impl PostView {
   pub async fn read(
@@ -152,7 +161,13 @@ impl PostView {
         .filter(community::removed.eq(false))
         .filter(community::deleted.eq(false))
         .filter(post::removed.eq(false))
-        .filter(post::deleted.eq(false));
+        .filter(post::deleted.eq(false))
+        // Scheduled posts are only visible to their creator until they are published
+        .filter(
+          post::scheduled_publish_time
+            .is_null()
+            .or(post::creator_id.eq(person_id_join)),
+        );
     }
 
     let (
@@ -189,6 +204,9 @@ impl PostView {
       creator_blocked: creator_blocked.is_some(),
       my_vote,
       unread_comments,
+      keyword_filtered: false,
+      tags: CommunityPostTag::list_for_post(pool, post_id).await?,
+      poll: PollView::read(pool, post_id, my_person_id).await?,
     })
   }
 }
@@ -205,6 +223,14 @@ pub struct PostQuery<'a> {
   local_user: Option<&'a LocalUser>,
   search_term: Option<String>,
   url_search: Option<String>,
+  /// Only posts in communities of this instance
+  instance_id: Option<InstanceId>,
+  /// Only posts with this tag
+  tag_id: Option<CommunityPostTagId>,
+  /// Only NSFW posts if true, or only posts which aren't NSFW if false
+  nsfw: Option<bool>,
+  published_after: Option<NaiveDateTime>,
+  published_before: Option<NaiveDateTime>,
   saved_only: Option<bool>,
   /// Used to show deleted or removed posts for admins
   is_mod_or_admin: Option<bool>,
@@ -214,6 +240,15 @@ pub struct PostQuery<'a> {
 
 impl<'a> PostQuery<'a> {
   pub async fn list(self) -> Result<Vec<PostView>, Error> {
+    let own_posts =
+      self.local_user.is_some() && self.creator_id == self.local_user.map(|l| l.person_id);
+    // Keyword filters don't apply to the user's own posts
+    let keyword_filters = match self.local_user {
+      Some(local_user) if !own_posts => {
+        LocalUserKeywordFilter::list(self.pool, local_user.id).await?
+      }
+      _ => vec![],
+    };
     let conn = &mut get_conn(self.pool).await?;
 
     // The left join below will return None in this case
@@ -266,6 +301,13 @@ impl<'a> PostQuery<'a> {
             .and(community_block::person_id.eq(person_id_join)),
         ),
       )
+      .left_join(
+        instance_block::table.on(
+          community::instance_id
+            .eq(instance_block::instance_id)
+            .and(instance_block::local_user_id.eq(local_user_id_join)),
+        ),
+      )
       .left_join(
         post_like::table.on(
           post::id
@@ -287,6 +329,14 @@ impl<'a> PostQuery<'a> {
             .and(local_user_language::local_user_id.eq(local_user_id_join)),
         ),
       )
+      .left_join(
+        person_follower::table.on(
+          post::creator_id
+            .eq(person_follower::person_id)
+            .and(person_follower::follower_id.eq(person_id_join))
+            .and(person_follower::pending.eq(false)),
+        ),
+      )
       .select((
         post::all_columns,
         person::all_columns,
@@ -315,12 +365,16 @@ impl<'a> PostQuery<'a> {
         .filter(post::deleted.eq(false));
     }
 
-    if self.community_id.is_none() {
-      query = query.then_order_by(post_aggregates::featured_local.desc());
-    } else if let Some(community_id) = self.community_id {
-      query = query
-        .filter(post::community_id.eq(community_id))
-        .then_order_by(post_aggregates::featured_community.desc());
+    if let Some(community_id) = self.community_id {
+      query = query.filter(post::community_id.eq(community_id));
+    }
+
+    // Featured posts stay on top of listings, but search results are ordered by relevance only
+    if self.search_term.is_none() {
+      query = match self.community_id {
+        None => query.then_order_by(post_aggregates::featured_local.desc()),
+        Some(_) => query.then_order_by(post_aggregates::featured_community.desc()),
+      };
     }
 
     if let Some(creator_id) = self.creator_id {
@@ -332,6 +386,7 @@ impl<'a> PostQuery<'a> {
         ListingType::Subscribed => {
           query = query.filter(community_follower::person_id.is_not_null())
         }
+        ListingType::Followed => query = query.filter(person_follower::follower_id.is_not_null()),
         ListingType::Local => {
           query = query.filter(community::local.eq(true)).filter(
             community::hidden
@@ -353,21 +408,64 @@ impl<'a> PostQuery<'a> {
       query = query.filter(post::url.eq(url_search));
     }
 
-    if let Some(search_term) = self.search_term {
-      let searcher = fuzzy_search(&search_term);
+    if let Some(search_term) = &self.search_term {
+      query = query.filter(full_text_search("post", search_term));
+    }
+
+    if let Some(instance_id) = self.instance_id {
+      query = query.filter(community::instance_id.eq(instance_id));
+    }
+
+    if let Some(tag_id) = self.tag_id {
       query = query.filter(
-        post::name
-          .ilike(searcher.clone())
-          .or(post::body.ilike(searcher)),
+        post::id.eq_any(
+          post_tag::table
+            .filter(post_tag::tag_id.eq(tag_id))
+            .select(post_tag::post_id),
+        ),
       );
     }
 
-    if !self.local_user.map(|l| l.show_nsfw).unwrap_or(false) {
+    for filter in &keyword_filters {
+      if filter.action != KeywordFilterAction::Hide {
+        continue;
+      }
+      let pattern = filter.like_pattern();
+      // The host part of the url, without port
+      let domain = || split_part(split_part(post::url, "/", 3), ":", 1);
+      query = query
+        .filter(post::name.not_ilike(pattern.clone()))
+        .filter(post::body.is_null().or(post::body.not_ilike(pattern)))
+        .filter(
+          post::url.is_null().or(
+            domain()
+              .ne(filter.keyword.clone())
+              .and(domain().not_like(filter.subdomain_like_pattern())),
+          ),
+        );
+    }
+
+    if let Some(published_after) = self.published_after {
+      query = query.filter(post::published.ge(published_after));
+    }
+
+    if let Some(published_before) = self.published_before {
+      query = query.filter(post::published.lt(published_before));
+    }
+
+    if !self.local_user.map(|l| l.show_nsfw).unwrap_or(false) || self.nsfw == Some(false) {
       query = query
         .filter(post::nsfw.eq(false))
         .filter(community::nsfw.eq(false));
+    } else if self.nsfw == Some(true) {
+      query = query.filter(post::nsfw.eq(true).or(community::nsfw.eq(true)));
     };
 
+    // Scheduled posts only show up in the creator's own profile until they are published
+    if !own_posts {
+      query = query.filter(post::scheduled_publish_time.is_null());
+    }
+
     if !self.local_user.map(|l| l.show_bot_accounts).unwrap_or(true) {
       query = query.filter(person::bot_account.eq(false));
     };
@@ -381,16 +479,28 @@ impl<'a> PostQuery<'a> {
       query = query.filter(post_read::post_id.is_null());
     }
 
-    if self.local_user.is_some() {
+    // Blocks and language settings don't hide the user's own posts
+    if self.local_user.is_some() && !own_posts {
       // Filter out the rows with missing languages
       query = query.filter(local_user_language::language_id.is_not_null());
 
-      // Don't show blocked communities or persons
+      // Don't show blocked communities, instances or persons
       query = query.filter(community_block::person_id.is_null());
+      query = query.filter(instance_block::local_user_id.is_null());
       query = query.filter(person_block::person_id.is_null());
     }
 
-    query = match self.sort.unwrap_or(SortType::Hot) {
+    // Search results are ordered by relevance first, the sort type only decides between equally
+    // relevant results
+    let sort = match &self.search_term {
+      Some(search_term) => {
+        query = query.then_order_by(full_text_search_rank("post", search_term).desc());
+        self.sort.unwrap_or(SortType::New)
+      }
+      None => self.sort.unwrap_or(SortType::Hot),
+    };
+
+    query = match sort {
       SortType::Active => query.then_order_by(post_aggregates::hot_rank_active.desc()),
       SortType::Hot => query.then_order_by(post_aggregates::hot_rank.desc()),
       SortType::New => query.then_order_by(post_aggregates::published.desc()),
@@ -452,7 +562,32 @@ impl<'a> PostQuery<'a> {
 
     let res = query.load::<PostViewTuple>(conn).await?;
 
-    Ok(res.into_iter().map(PostView::from_tuple).collect())
+    let post_ids: Vec<PostId> = res.iter().map(|p| p.0.id).collect();
+    let tags = CommunityPostTag::list_for_posts(self.pool, &post_ids).await?;
+    let mut polls =
+      PollView::list_for_posts(self.pool, &post_ids, self.local_user.map(|l| l.person_id)).await?;
+
+    Ok(
+      res
+        .into_iter()
+        .map(PostView::from_tuple)
+        .map(|mut post_view| {
+          post_view.keyword_filtered = keyword_filters
+            .iter()
+            .any(|f| f.action == KeywordFilterAction::Collapse && f.matches_post(&post_view.post));
+          post_view.tags = tags
+            .iter()
+            .filter(|(post_id, _)| *post_id == post_view.post.id)
+            .map(|(_, tag)| tag.clone())
+            .collect();
+          post_view.poll = polls
+            .iter()
+            .position(|p| p.poll.post_id == post_view.post.id)
+            .map(|i| polls.swap_remove(i));
+          post_view
+        })
+        .collect(),
+    )
   }
 }
 
@@ -471,6 +606,9 @@ impl JoinView for PostView {
       creator_blocked: a.8.is_some(),
       my_vote: a.9,
       unread_comments: a.10,
+      keyword_filtered: false,
+      tags: vec![],
+      poll: None,
     }
   }
 }
@@ -478,15 +616,18 @@ impl JoinView for PostView {
 #[cfg(test)]
 mod tests {
   use crate::post_view::{PostQuery, PostView};
+  use chrono::Duration;
   use lemmy_db_schema::{
     aggregates::structs::PostAggregates,
     impls::actor_language::UNDETERMINED_ID,
-    newtypes::LanguageId,
+    newtypes::{LanguageId, PostId},
     source::{
       actor_language::LocalUserLanguage,
       community::{Community, CommunityInsertForm},
       community_block::{CommunityBlock, CommunityBlockForm},
+      community_post_tag::{CommunityPostTag, CommunityPostTagInsertForm, PostTag},
       instance::Instance,
+      instance_block::{InstanceBlock, InstanceBlockForm},
       language::Language,
       local_user::{LocalUser, LocalUserInsertForm, LocalUserUpdateForm},
       person::{Person, PersonInsertForm},
@@ -494,7 +635,7 @@ mod tests {
       post::{Post, PostInsertForm, PostLike, PostLikeForm, PostUpdateForm},
     },
     traits::{Blockable, Crud, Likeable},
-    utils::{build_db_pool_for_tests, DbPool},
+    utils::{build_db_pool_for_tests, naive_now, DbPool},
     SortType,
     SubscribedType,
   };
@@ -733,12 +874,168 @@ mod tests {
     // Should be 0 posts after the community block
     assert_eq!(0, read_post_listings_with_person_after_block.len());
 
+    // The block doesn't hide the user's own posts in their profile
+    let own_posts_after_block = PostQuery::builder()
+      .pool(pool)
+      .sort(Some(SortType::New))
+      .creator_id(Some(data.inserted_person.id))
+      .local_user(Some(&data.inserted_local_user))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert!(!own_posts_after_block.is_empty());
+
     CommunityBlock::unblock(pool, &community_block)
       .await
       .unwrap();
     cleanup(data, pool).await;
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_tag() {
+    let pool = &build_db_pool_for_tests().await;
+    let data = init_data(pool).await;
+
+    let tag_form = CommunityPostTagInsertForm {
+      ap_id: None,
+      community_id: data.inserted_community.id,
+      name: "Question".to_string(),
+      updated: None,
+    };
+    let inserted_tag = CommunityPostTag::create(pool, &tag_form).await.unwrap();
+    PostTag::update(pool, data.inserted_post.id, vec![inserted_tag.id])
+      .await
+      .unwrap();
+
+    let read_post_listing = PostQuery::builder()
+      .pool(pool)
+      .sort(Some(SortType::New))
+      .tag_id(Some(inserted_tag.id))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert_eq!(1, read_post_listing.len());
+    assert_eq!(data.inserted_post.id, read_post_listing[0].post.id);
+    assert_eq!(vec![inserted_tag.clone()], read_post_listing[0].tags);
+
+    let read_post = PostView::read(pool, data.inserted_post.id, None, None)
+      .await
+      .unwrap();
+    assert_eq!(vec![inserted_tag.clone()], read_post.tags);
+
+    CommunityPostTag::delete(pool, inserted_tag.id)
+      .await
+      .unwrap();
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_scheduled() {
+    let pool = &build_db_pool_for_tests().await;
+    let data = init_data(pool).await;
+
+    let scheduled_post = PostInsertForm::builder()
+      .name("scheduled post".to_string())
+      .creator_id(data.inserted_person.id)
+      .community_id(data.inserted_community.id)
+      .scheduled_publish_time(Some(naive_now() + Duration::days(1)))
+      .build();
+    let scheduled_post = Post::create(pool, &scheduled_post).await.unwrap();
+
+    let is_listed = |posts: Vec<PostView>| posts.iter().any(|p| p.post.id == scheduled_post.id);
+    let read_post_listing = PostQuery::builder()
+      .pool(pool)
+      .sort(Some(SortType::New))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert!(!is_listed(read_post_listing));
+    assert!(PostView::read(pool, scheduled_post.id, None, None)
+      .await
+      .is_err());
+
+    // The creator sees it in their own profile
+    let read_own_posts = PostQuery::builder()
+      .pool(pool)
+      .sort(Some(SortType::New))
+      .creator_id(Some(data.inserted_person.id))
+      .local_user(Some(&data.inserted_local_user))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert!(is_listed(read_own_posts));
+
+    // Not due yet
+    let to_publish = Post::list_scheduled_to_publish(pool).await.unwrap();
+    assert!(!to_publish.iter().any(|p| p.id == scheduled_post.id));
+
+    let form = PostUpdateForm::builder()
+      .scheduled_publish_time(Some(Some(naive_now() - Duration::minutes(1))))
+      .build();
+    Post::update(pool, scheduled_post.id, &form).await.unwrap();
+    let to_publish = Post::list_scheduled_to_publish(pool).await.unwrap();
+    assert!(to_publish.iter().any(|p| p.id == scheduled_post.id));
+
+    let published_post = Post::publish_scheduled(pool, scheduled_post.id)
+      .await
+      .unwrap();
+    assert_eq!(None, published_post.scheduled_publish_time);
+    let read_post_listing = PostQuery::builder()
+      .pool(pool)
+      .sort(Some(SortType::New))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert!(is_listed(read_post_listing));
+
+    Post::delete(pool, scheduled_post.id).await.unwrap();
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_block_instance() {
+    let pool = &build_db_pool_for_tests().await;
+    let data = init_data(pool).await;
+
+    let instance_block = InstanceBlockForm {
+      local_user_id: data.inserted_local_user.id,
+      instance_id: data.inserted_instance.id,
+    };
+    InstanceBlock::block(pool, &instance_block).await.unwrap();
+
+    let read_post_listings_with_person_after_block = PostQuery::builder()
+      .pool(pool)
+      .sort(Some(SortType::New))
+      .local_user(Some(&data.inserted_local_user))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    // Should be 0 posts after the instance block
+    assert_eq!(0, read_post_listings_with_person_after_block.len());
+
+    // Other users still see the posts
+    let read_post_listings_no_person = PostQuery::builder()
+      .pool(pool)
+      .sort(Some(SortType::New))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert!(!read_post_listings_no_person.is_empty());
+
+    InstanceBlock::unblock(pool, &instance_block).await.unwrap();
+    cleanup(data, pool).await;
+  }
+
   #[tokio::test]
   #[serial]
   async fn post_listing_like() {
@@ -930,6 +1227,92 @@ mod tests {
     cleanup(data, pool).await;
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_search() {
+    let pool = &build_db_pool_for_tests().await;
+    let data = init_data(pool).await;
+
+    let english_id = Language::read_id_from_code(pool, Some("en"))
+      .await
+      .unwrap()
+      .unwrap();
+    let english_post_form = PostInsertForm::builder()
+      .name("Running shoes".to_string())
+      .body(Some("Which ones do you recommend for trails?".to_string()))
+      .creator_id(data.inserted_person.id)
+      .community_id(data.inserted_community.id)
+      .language_id(Some(english_id))
+      .build();
+    let english_post = Post::create(pool, &english_post_form).await.unwrap();
+
+    let search = |search_term: &str| {
+      PostQuery::builder()
+        .pool(pool)
+        .community_id(Some(data.inserted_community.id))
+        .search_term(Some(search_term.to_string()))
+        .build()
+        .list()
+    };
+
+    // English words are stemmed, so other forms of them match too
+    let stemmed = search("runs trail").await.unwrap();
+    assert_eq!(vec![english_post.id], ids(&stemmed));
+
+    // Posts with undetermined language are found too
+    let bot_posts = search("bot").await.unwrap();
+    assert_eq!(1, bot_posts.len());
+    assert_eq!("test bot post", bot_posts[0].post.name);
+
+    // Matches in the title rank higher than matches in the body
+    let body_post_form = PostInsertForm::builder()
+      .name("Hiking".to_string())
+      .body(Some("I just bought new shoes".to_string()))
+      .creator_id(data.inserted_person.id)
+      .community_id(data.inserted_community.id)
+      .language_id(Some(english_id))
+      .build();
+    let body_post = Post::create(pool, &body_post_form).await.unwrap();
+    let ranked = search("shoes").await.unwrap();
+    assert_eq!(vec![english_post.id, body_post.id], ids(&ranked));
+
+    // An explicit sort type doesn't override the relevance
+    let ranked_new = PostQuery::builder()
+      .pool(pool)
+      .sort(Some(SortType::New))
+      .community_id(Some(data.inserted_community.id))
+      .search_term(Some("shoes".to_string()))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert_eq!(vec![english_post.id, body_post.id], ids(&ranked_new));
+
+    // Search syntax of web search engines is supported
+    let phrase = search("\"new shoes\"").await.unwrap();
+    assert_eq!(vec![body_post.id], ids(&phrase));
+    let negated = search("shoes -trails").await.unwrap();
+    assert_eq!(vec![body_post.id], ids(&negated));
+
+    // Featured posts don't come first in search results
+    let featured_form = PostUpdateForm::builder()
+      .featured_community(Some(true))
+      .build();
+    Post::update(pool, body_post.id, &featured_form)
+      .await
+      .unwrap();
+    let ranked_featured = search("shoes").await.unwrap();
+    assert_eq!(vec![english_post.id, body_post.id], ids(&ranked_featured));
+
+    Post::delete(pool, english_post.id).await.unwrap();
+    Post::delete(pool, body_post.id).await.unwrap();
+    cleanup(data, pool).await;
+  }
+
+  fn ids(post_views: &[PostView]) -> Vec<PostId> {
+    post_views.iter().map(|p| p.post.id).collect()
+  }
+
   async fn cleanup(data: Data, pool: &DbPool) {
     let num_deleted = Post::delete(pool, data.inserted_post.id).await.unwrap();
     Community::delete(pool, data.inserted_community.id)
@@ -977,9 +1360,14 @@ mod tests {
         language_id: LanguageId(47),
         featured_community: false,
         featured_local: false,
+        scheduled_publish_time: None,
+        url_content_type: None,
       },
       my_vote: None,
       unread_comments: 0,
+      keyword_filtered: false,
+      tags: vec![],
+      poll: None,
       creator: Person {
         id: inserted_person.id,
         name: inserted_person.name.clone(),
@@ -1000,6 +1388,9 @@ mod tests {
         matrix_user_id: None,
         ban_expires: None,
         instance_id: data.inserted_instance.id,
+        moved_to: None,
+        also_known_as: None,
+        encryption_public_key: None,
         private_key: inserted_person.private_key.clone(),
         public_key: inserted_person.public_key.clone(),
         last_refreshed_at: inserted_person.last_refreshed_at,
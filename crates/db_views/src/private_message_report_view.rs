@@ -154,7 +154,7 @@ mod tests {
     source::{
       instance::Instance,
       person::{Person, PersonInsertForm},
-      private_message::{PrivateMessage, PrivateMessageInsertForm},
+      private_message::{Conversation, PrivateMessage, PrivateMessageInsertForm},
       private_message_report::{PrivateMessageReport, PrivateMessageReportForm},
     },
     traits::{Crud, Reportable},
@@ -186,10 +186,15 @@ mod tests {
     let inserted_jessica = Person::create(pool, &new_person_2).await.unwrap();
 
     // timmy sends private message to jessica
+    let conversation =
+      Conversation::read_or_create(pool, &[inserted_timmy.id, inserted_jessica.id])
+        .await
+        .unwrap();
     let pm_form = PrivateMessageInsertForm::builder()
       .creator_id(inserted_timmy.id)
       .recipient_id(inserted_jessica.id)
       .content("something offensive".to_string())
+      .conversation_id(conversation.id)
       .build();
     let pm = PrivateMessage::create(pool, &pm_form).await.unwrap();
 
@@ -1,4 +1,5 @@
 use crate::structs::PrivateMessageView;
+use chrono::NaiveDateTime;
 use diesel::{
   debug_query,
   pg::Pg,
@@ -10,8 +11,8 @@ use diesel::{
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
-  newtypes::{PersonId, PrivateMessageId},
-  schema::{person, private_message},
+  newtypes::{ConversationId, PersonId, PrivateMessageId},
+  schema::{conversation_participant, person, private_message},
   source::{person::Person, private_message::PrivateMessage},
   traits::JoinView,
   utils::{get_conn, limit_and_offset, DbPool},
@@ -48,13 +49,26 @@ impl PrivateMessageView {
     })
   }
 
-  /// Gets the number of unread messages
+  /// Gets the number of unread messages, in all conversations
   pub async fn get_unread_messages(pool: &DbPool, my_person_id: PersonId) -> Result<i64, Error> {
     use diesel::dsl::count;
     let conn = &mut get_conn(pool).await?;
     private_message::table
-      .filter(private_message::read.eq(false))
-      .filter(private_message::recipient_id.eq(my_person_id))
+      .inner_join(
+        conversation_participant::table.on(
+          private_message::conversation_id
+            .eq(conversation_participant::conversation_id)
+            .and(conversation_participant::person_id.eq(my_person_id)),
+        ),
+      )
+      .filter(private_message::creator_id.ne(my_person_id))
+      .filter(
+        conversation_participant::last_read.is_null().or(
+          private_message::published
+            .nullable()
+            .gt(conversation_participant::last_read),
+        ),
+      )
       .filter(private_message::deleted.eq(false))
       .select(count(private_message::id))
       .first::<i64>(conn)
@@ -69,6 +83,8 @@ pub struct PrivateMessageQuery<'a> {
   pool: &'a DbPool,
   #[builder(!default)]
   recipient_id: PersonId,
+  /// Only the messages of a single conversation
+  conversation_id: Option<ConversationId>,
   unread_only: Option<bool>,
   page: Option<i64>,
   limit: Option<i64>,
@@ -84,26 +100,37 @@ impl<'a> PrivateMessageQuery<'a> {
       .inner_join(
         person_alias_1.on(private_message::recipient_id.eq(person_alias_1.field(person::id))),
       )
+      // Only messages in conversations I am part of, both sent and received
+      .inner_join(
+        conversation_participant::table.on(
+          private_message::conversation_id
+            .eq(conversation_participant::conversation_id)
+            .and(conversation_participant::person_id.eq(self.recipient_id)),
+        ),
+      )
       .select((
         private_message::all_columns,
         person::all_columns,
         person_alias_1.fields(person::all_columns),
+        conversation_participant::last_read,
       ))
       .into_boxed();
 
+    if let Some(conversation_id) = self.conversation_id {
+      query = query.filter(private_message::conversation_id.eq(conversation_id));
+    }
+
     // If its unread, I only want the ones to me
     if self.unread_only.unwrap_or(false) {
       query = query
-        .filter(private_message::read.eq(false))
-        .filter(private_message::recipient_id.eq(self.recipient_id));
-    }
-    // Otherwise, I want the ALL view to show both sent and received
-    else {
-      query = query.filter(
-        private_message::recipient_id
-          .eq(self.recipient_id)
-          .or(private_message::creator_id.eq(self.recipient_id)),
-      )
+        .filter(private_message::creator_id.ne(self.recipient_id))
+        .filter(
+          conversation_participant::last_read.is_null().or(
+            private_message::published
+              .nullable()
+              .gt(conversation_participant::last_read),
+          ),
+        );
     }
 
     let (limit, offset) = limit_and_offset(self.page, self.limit)?;
@@ -119,12 +146,24 @@ impl<'a> PrivateMessageQuery<'a> {
       debug_query::<Pg, _>(&query)
     );
 
-    let res = query.load::<PrivateMessageViewTuple>(conn).await?;
+    let res = query
+      .load::<(PrivateMessage, Person, Person, Option<NaiveDateTime>)>(conn)
+      .await?;
 
     Ok(
       res
         .into_iter()
-        .map(PrivateMessageView::from_tuple)
+        .map(|(mut private_message, creator, recipient, last_read)| {
+          // The `read` column is only for the first recipient. Messages sent by ourselves are
+          // always read, the others depend on when we last read the conversation.
+          private_message.read = private_message.creator_id == self.recipient_id
+            || matches!(last_read, Some(l) if private_message.published <= l);
+          PrivateMessageView {
+            private_message,
+            creator,
+            recipient,
+          }
+        })
         .collect(),
     )
   }
@@ -308,6 +308,9 @@ mod tests {
         shared_inbox_url: None,
         matrix_user_id: None,
         instance_id: inserted_instance.id,
+        moved_to: None,
+        also_known_as: None,
+        encryption_public_key: None,
         private_key: inserted_sara_person.private_key,
         public_key: inserted_sara_person.public_key,
         last_refreshed_at: inserted_sara_person.last_refreshed_at,
@@ -386,6 +389,9 @@ mod tests {
       shared_inbox_url: None,
       matrix_user_id: None,
       instance_id: inserted_instance.id,
+      moved_to: None,
+      also_known_as: None,
+      encryption_public_key: None,
       private_key: inserted_timmy_person.private_key,
       public_key: inserted_timmy_person.public_key,
       last_refreshed_at: inserted_timmy_person.last_refreshed_at,
@@ -4,15 +4,19 @@ use lemmy_db_schema::{
     comment::Comment,
     comment_report::CommentReport,
     community::Community,
+    community_post_tag::CommunityPostTag,
     custom_emoji::CustomEmoji,
     custom_emoji_keyword::CustomEmojiKeyword,
+    local_image::LocalImage,
     local_site::LocalSite,
     local_site_rate_limit::LocalSiteRateLimit,
     local_user::LocalUser,
+    mod_note::ModNote,
     person::Person,
+    poll::{Poll, PollOption},
     post::Post,
     post_report::PostReport,
-    private_message::PrivateMessage,
+    private_message::{Conversation, PrivateMessage},
     private_message_report::PrivateMessageReport,
     registration_application::RegistrationApplication,
     site::Site,
@@ -40,6 +44,8 @@ pub struct CommentReportView {
   pub creator_banned_from_community: bool,
   pub my_vote: Option<i16>,
   pub resolver: Option<Person>,
+  /// The number of mod notes about the comment creator, for moderators and admins.
+  pub mod_note_count: Option<i64>,
 }
 
 #[skip_serializing_none]
@@ -58,6 +64,8 @@ pub struct CommentView {
   pub saved: bool,
   pub creator_blocked: bool,
   pub my_vote: Option<i16>,
+  /// Whether the comment contains a keyword which the user wants to see collapsed.
+  pub keyword_filtered: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -85,6 +93,8 @@ pub struct PostReportView {
   pub my_vote: Option<i16>,
   pub counts: PostAggregates,
   pub resolver: Option<Person>,
+  /// The number of mod notes about the post creator, for moderators and admins.
+  pub mod_note_count: Option<i64>,
 }
 
 #[skip_serializing_none]
@@ -104,6 +114,31 @@ pub struct PostView {
   pub creator_blocked: bool,
   pub my_vote: Option<i16>,
   pub unread_comments: i64,
+  /// Whether the post contains a keyword which the user wants to see collapsed.
+  pub keyword_filtered: bool,
+  pub tags: Vec<CommunityPostTag>,
+  pub poll: Option<PollView>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A poll view.
+pub struct PollView {
+  pub poll: Poll,
+  pub options: Vec<PollOptionView>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A poll option view.
+pub struct PollOptionView {
+  pub poll_option: PollOption,
+  /// Votes counted by the instance of the poll, together with the votes of local users.
+  pub vote_count: i64,
+  /// Whether the current user voted for this option.
+  pub voted: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
@@ -116,6 +151,19 @@ pub struct PrivateMessageView {
   pub recipient: Person,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A private message conversation view.
+pub struct ConversationView {
+  pub conversation: Conversation,
+  /// Everyone in the conversation, including yourself.
+  pub participants: Vec<Person>,
+  pub last_message: Option<PrivateMessage>,
+  pub unread_count: i64,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -160,3 +208,24 @@ pub struct CustomEmojiView {
   pub custom_emoji: CustomEmoji,
   pub keywords: Vec<CustomEmojiKeyword>,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A mod note view.
+pub struct ModNoteView {
+  pub mod_note: ModNote,
+  pub author: Person,
+  /// None for site-wide notes.
+  pub community: Option<Community>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An uploaded image, with its uploader.
+pub struct LocalImageView {
+  pub local_image: LocalImage,
+  pub person: Person,
+}
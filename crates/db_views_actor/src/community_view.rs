@@ -11,8 +11,15 @@ use diesel::{
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   aggregates::structs::CommunityAggregates,
-  newtypes::{CommunityId, PersonId},
-  schema::{community, community_aggregates, community_block, community_follower, local_user},
+  newtypes::{CommunityId, InstanceId, LocalUserId, PersonId},
+  schema::{
+    community,
+    community_aggregates,
+    community_block,
+    community_follower,
+    instance_block,
+    local_user,
+  },
   source::{
     community::{Community, CommunityFollower},
     community_block::CommunityBlock,
@@ -125,6 +132,10 @@ pub struct CommunityQuery<'a> {
   sort: Option<SortType>,
   local_user: Option<&'a LocalUser>,
   search_term: Option<String>,
+  /// Only communities of this instance
+  instance_id: Option<InstanceId>,
+  /// Only NSFW communities if true, or only communities which aren't NSFW if false
+  nsfw: Option<bool>,
   is_mod_or_admin: Option<bool>,
   show_nsfw: Option<bool>,
   page: Option<i64>,
@@ -137,6 +148,7 @@ impl<'a> CommunityQuery<'a> {
 
     // The left join below will return None in this case
     let person_id_join = self.local_user.map(|l| l.person_id).unwrap_or(PersonId(-1));
+    let local_user_id_join = self.local_user.map(|l| l.id).unwrap_or(LocalUserId(-1));
 
     let mut query = community::table
       .inner_join(community_aggregates::table)
@@ -155,6 +167,13 @@ impl<'a> CommunityQuery<'a> {
             .and(community_block::person_id.eq(person_id_join)),
         ),
       )
+      .left_join(
+        instance_block::table.on(
+          community::instance_id
+            .eq(instance_block::instance_id)
+            .and(instance_block::local_user_id.eq(local_user_id_join)),
+        ),
+      )
       .select((
         community::all_columns,
         community_aggregates::all_columns,
@@ -202,6 +221,7 @@ impl<'a> CommunityQuery<'a> {
     // Don't show blocked communities or nsfw communities if not enabled in profile
     if self.local_user.is_some() {
       query = query.filter(community_block::person_id.is_null());
+      query = query.filter(instance_block::local_user_id.is_null());
       query = query.filter(community::nsfw.eq(false).or(local_user::show_nsfw.eq(true)));
     } else {
       // No person in request, only show nsfw communities if show_nsfw is passed into request
@@ -210,6 +230,14 @@ impl<'a> CommunityQuery<'a> {
       }
     }
 
+    if let Some(instance_id) = self.instance_id {
+      query = query.filter(community::instance_id.eq(instance_id));
+    }
+
+    if let Some(nsfw) = self.nsfw {
+      query = query.filter(community::nsfw.eq(nsfw));
+    }
+
     let (limit, offset) = limit_and_offset(self.page, self.limit)?;
     let res = query
       .limit(limit)
@@ -0,0 +1,51 @@
+use crate::structs::InstanceBlockView;
+use diesel::{result::Error, ExpressionMethods, JoinOnDsl, NullableExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::LocalUserId,
+  schema::{instance, instance_block, site},
+  source::{instance::Instance, site::Site},
+  traits::JoinView,
+  utils::{get_conn, DbPool},
+};
+
+type InstanceBlockViewTuple = (Instance, Option<Site>);
+
+impl InstanceBlockView {
+  pub async fn for_local_user(
+    pool: &DbPool,
+    local_user_id: LocalUserId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let res = instance_block::table
+      .inner_join(instance::table)
+      .left_join(site::table.on(site::instance_id.eq(instance::id)))
+      .select((instance::all_columns, site::all_columns.nullable()))
+      .filter(instance_block::local_user_id.eq(local_user_id))
+      .order_by(instance_block::published)
+      .load::<InstanceBlockViewTuple>(conn)
+      .await?;
+
+    Ok(
+      res
+        .into_iter()
+        .map(|(instance, mut site)| {
+          if let Some(site) = &mut site {
+            site.private_key = None;
+          }
+          Self::from_tuple((instance, site))
+        })
+        .collect(),
+    )
+  }
+}
+
+impl JoinView for InstanceBlockView {
+  type JoinTuple = InstanceBlockViewTuple;
+  fn from_tuple(a: Self::JoinTuple) -> Self {
+    Self {
+      instance: a.0,
+      site: a.1,
+    }
+  }
+}
@@ -148,6 +148,7 @@ impl JoinView for PersonView {
     Self {
       person: a.0,
       counts: a.1,
+      mod_note_count: None,
     }
   }
 }
@@ -4,9 +4,11 @@ use lemmy_db_schema::{
     comment::Comment,
     comment_reply::CommentReply,
     community::Community,
+    instance::Instance,
     person::Person,
     person_mention::PersonMention,
     post::Post,
+    site::Site,
   },
   SubscribedType,
 };
@@ -60,6 +62,17 @@ pub struct CommunityView {
   pub counts: CommunityAggregates,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An instance block.
+pub struct InstanceBlockView {
+  pub instance: Instance,
+  /// Missing if the instance doesn't run Lemmy, or wasn't fetched yet.
+  pub site: Option<Site>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -109,6 +122,7 @@ pub struct CommentReplyView {
   pub my_vote: Option<i16>,                // Left join to CommentLike
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -116,4 +130,6 @@ pub struct CommentReplyView {
 pub struct PersonView {
   pub person: Person,
   pub counts: PersonAggregates,
+  /// The number of mod notes about the person, only for moderators and admins.
+  pub mod_note_count: Option<i64>,
 }
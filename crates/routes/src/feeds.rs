@@ -1,9 +1,9 @@
 use actix_web::{error::ErrorBadRequest, web, Error, HttpRequest, HttpResponse, Result};
 use anyhow::anyhow;
 use chrono::{DateTime, NaiveDateTime, Utc};
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{context::LemmyContext, utils::check_login_token};
 use lemmy_db_schema::{
-  newtypes::LocalUserId,
+  newtypes::{LocalUserId, PostId},
   source::{community::Community, local_user::LocalUser, person::Person},
   traits::{ApubActor, Crud},
   utils::DbPool,
@@ -12,24 +12,30 @@ use lemmy_db_schema::{
   SortType,
 };
 use lemmy_db_views::{
+  comment_view::CommentQuery,
   post_view::PostQuery,
-  structs::{PostView, SiteView},
+  structs::{CommentView, PostView, SiteView},
 };
 use lemmy_db_views_actor::{
   comment_reply_view::CommentReplyQuery,
   person_mention_view::PersonMentionQuery,
   structs::{CommentReplyView, PersonMentionView},
 };
-use lemmy_utils::{claims::Claims, error::LemmyError, utils::markdown::markdown_to_html};
+use lemmy_utils::{
+  claims::Claims,
+  error::LemmyError,
+  utils::{image_proxy::markdown_to_html_with_image_proxy, markdown::markdown_to_html},
+};
 use once_cell::sync::Lazy;
 use rss::{
   extension::dublincore::DublinCoreExtensionBuilder,
+  Channel,
   ChannelBuilder,
   GuidBuilder,
   Item,
   ItemBuilder,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, str::FromStr};
 
 const RSS_FETCH_LIMIT: i64 = 20;
@@ -39,6 +45,10 @@ struct Params {
   sort: Option<String>,
   limit: Option<i64>,
   page: Option<i64>,
+  /// Login token, to apply the user's keyword filters and other content settings
+  token: Option<String>,
+  /// The search term for search feeds
+  q: Option<String>,
 }
 
 impl Params {
@@ -49,12 +59,27 @@ impl Params {
       .unwrap_or_else(|| SortType::Hot.to_string());
     SortType::from_str(&sort_query).map_err(ErrorBadRequest)
   }
+  fn comment_sort_type(&self) -> Result<CommentSortType, Error> {
+    let sort_query = self
+      .sort
+      .clone()
+      .unwrap_or_else(|| CommentSortType::New.to_string());
+    CommentSortType::from_str(&sort_query).map_err(ErrorBadRequest)
+  }
   fn get_limit(&self) -> i64 {
     self.limit.unwrap_or(RSS_FETCH_LIMIT)
   }
   fn get_page(&self) -> i64 {
     self.page.unwrap_or(1)
   }
+  async fn local_user(&self, context: &LemmyContext) -> Result<Option<LocalUser>, LemmyError> {
+    match &self.token {
+      Some(token) => Ok(Some(
+        local_user_from_jwt(context.pool(), &context.secret().jwt_secret, token).await?,
+      )),
+      None => Ok(None),
+    }
+  }
 }
 
 enum RequestType {
@@ -62,13 +87,50 @@ enum RequestType {
   User,
   Front,
   Inbox,
+  /// The comments of a single post
+  Post,
+}
+
+/// The feed format, chosen by the extension of the feed url.
+#[derive(Clone, Copy)]
+enum FeedFormat {
+  Rss,
+  Atom,
+  Json,
+}
+
+impl FeedFormat {
+  fn from_extension(extension: &str) -> Result<Self, Error> {
+    match extension {
+      "xml" => Ok(FeedFormat::Rss),
+      "atom" => Ok(FeedFormat::Atom),
+      "json" => Ok(FeedFormat::Json),
+      _ => Err(ErrorBadRequest(LemmyError::from(anyhow!(
+        "wrong_feed_format"
+      )))),
+    }
+  }
+
+  /// All feeds are built as RSS channels, and converted to the other formats at the end.
+  fn response(self, channel: &Channel) -> Result<HttpResponse, LemmyError> {
+    let (content_type, body) = match self {
+      FeedFormat::Rss => ("application/rss+xml", channel.to_string()),
+      FeedFormat::Atom => ("application/atom+xml", channel_to_atom(channel)),
+      FeedFormat::Json => (
+        "application/feed+json",
+        serde_json::to_string(&JsonFeed::from(channel))?,
+      ),
+    };
+    Ok(HttpResponse::Ok().content_type(content_type).body(body))
+  }
 }
 
 pub fn config(cfg: &mut web::ServiceConfig) {
   cfg
-    .route("/feeds/{type}/{name}.xml", web::get().to(get_feed))
-    .route("/feeds/all.xml", web::get().to(get_all_feed))
-    .route("/feeds/local.xml", web::get().to(get_local_feed));
+    .route("/feeds/{type}/{name}.{format}", web::get().to(get_feed))
+    .route("/feeds/all.{format}", web::get().to(get_all_feed))
+    .route("/feeds/local.{format}", web::get().to(get_local_feed))
+    .route("/feeds/search.{format}", web::get().to(get_search_feed));
 }
 
 static RSS_NAMESPACE: Lazy<BTreeMap<String, String>> = Lazy::new(|| {
@@ -82,52 +144,95 @@ static RSS_NAMESPACE: Lazy<BTreeMap<String, String>> = Lazy::new(|| {
 
 #[tracing::instrument(skip_all)]
 async fn get_all_feed(
+  format: web::Path<String>,
   info: web::Query<Params>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  Ok(
-    get_feed_data(
-      &context,
-      ListingType::All,
-      info.sort_type()?,
-      info.get_limit(),
-      info.get_page(),
-    )
-    .await?,
+  let format = FeedFormat::from_extension(&format)?;
+  let local_user = info.local_user(&context).await?;
+  let channel = get_feed_data(
+    &context,
+    ListingType::All,
+    local_user.as_ref(),
+    info.sort_type()?,
+    info.get_limit(),
+    info.get_page(),
+    None,
   )
+  .await?;
+  Ok(format.response(&channel)?)
 }
 
 #[tracing::instrument(skip_all)]
 async fn get_local_feed(
+  format: web::Path<String>,
   info: web::Query<Params>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  Ok(
-    get_feed_data(
-      &context,
-      ListingType::Local,
-      info.sort_type()?,
-      info.get_limit(),
-      info.get_page(),
-    )
-    .await?,
+  let format = FeedFormat::from_extension(&format)?;
+  let local_user = info.local_user(&context).await?;
+  let channel = get_feed_data(
+    &context,
+    ListingType::Local,
+    local_user.as_ref(),
+    info.sort_type()?,
+    info.get_limit(),
+    info.get_page(),
+    None,
   )
+  .await?;
+  Ok(format.response(&channel)?)
+}
+
+/// Posts matching the search term in the `q` param, from all communities.
+#[tracing::instrument(skip_all)]
+async fn get_search_feed(
+  format: web::Path<String>,
+  info: web::Query<Params>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  let format = FeedFormat::from_extension(&format)?;
+  let search_term = match info.q.as_deref().map(str::trim) {
+    Some(q) if !q.is_empty() => q.to_string(),
+    _ => return Err(ErrorBadRequest(LemmyError::from(anyhow!("no_search_term")))),
+  };
+  let local_user = info.local_user(&context).await?;
+  let channel = get_feed_data(
+    &context,
+    ListingType::All,
+    local_user.as_ref(),
+    info.sort_type()?,
+    info.get_limit(),
+    info.get_page(),
+    Some(search_term),
+  )
+  .await?;
+  Ok(format.response(&channel)?)
 }
 
 #[tracing::instrument(skip_all)]
 async fn get_feed_data(
   context: &LemmyContext,
   listing_type: ListingType,
+  local_user: Option<&LocalUser>,
   sort_type: SortType,
   limit: i64,
   page: i64,
-) -> Result<HttpResponse, LemmyError> {
+  search_term: Option<String>,
+) -> Result<Channel, LemmyError> {
   let site_view = SiteView::read_local(context.pool()).await?;
 
+  let title = match &search_term {
+    Some(search_term) => format!("{} - Search: {}", site_view.site.name, search_term),
+    None => format!("{} - {}", site_view.site.name, listing_type),
+  };
+
   let posts = PostQuery::builder()
     .pool(context.pool())
     .listing_type(Some(listing_type))
+    .local_user(local_user)
     .sort(Some(sort_type))
+    .search_term(search_term)
     .limit(Some(limit))
     .page(Some(page))
     .build()
@@ -139,7 +244,7 @@ async fn get_feed_data(
   let mut channel_builder = ChannelBuilder::default();
   channel_builder
     .namespaces(RSS_NAMESPACE.clone())
-    .title(&format!("{} - {}", site_view.site.name, listing_type))
+    .title(&title)
     .link(context.settings().get_protocol_and_hostname())
     .items(items);
 
@@ -147,12 +252,7 @@ async fn get_feed_data(
     channel_builder.description(&site_desc);
   }
 
-  let rss = channel_builder.build().to_string();
-  Ok(
-    HttpResponse::Ok()
-      .content_type("application/rss+xml")
-      .body(rss),
-  )
+  Ok(channel_builder.build())
 }
 
 #[tracing::instrument(skip_all)]
@@ -163,22 +263,31 @@ async fn get_feed(
 ) -> Result<HttpResponse, Error> {
   let req_type: String = req.match_info().get("type").unwrap_or("none").parse()?;
   let param: String = req.match_info().get("name").unwrap_or("none").parse()?;
+  let format = FeedFormat::from_extension(req.match_info().get("format").unwrap_or("none"))?;
 
   let request_type = match req_type.as_str() {
     "u" => RequestType::User,
     "c" => RequestType::Community,
     "front" => RequestType::Front,
     "inbox" => RequestType::Inbox,
+    "post" => RequestType::Post,
     _ => return Err(ErrorBadRequest(LemmyError::from(anyhow!("wrong_type")))),
   };
 
   let jwt_secret = context.secret().jwt_secret.clone();
   let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  let local_user = match request_type {
+    RequestType::User | RequestType::Community | RequestType::Post => {
+      info.local_user(&context).await?
+    }
+    RequestType::Front | RequestType::Inbox => None,
+  };
 
   let builder = match request_type {
     RequestType::User => {
       get_feed_user(
         context.pool(),
+        local_user.as_ref(),
         &info.sort_type()?,
         &info.get_limit(),
         &info.get_page(),
@@ -190,6 +299,7 @@ async fn get_feed(
     RequestType::Community => {
       get_feed_community(
         context.pool(),
+        local_user.as_ref(),
         &info.sort_type()?,
         &info.get_limit(),
         &info.get_page(),
@@ -213,21 +323,28 @@ async fn get_feed(
     RequestType::Inbox => {
       get_feed_inbox(context.pool(), &jwt_secret, &param, &protocol_and_hostname).await
     }
+    RequestType::Post => {
+      get_feed_post(
+        context.pool(),
+        local_user.as_ref(),
+        &info.comment_sort_type()?,
+        &info.get_limit(),
+        &info.get_page(),
+        &param,
+        &protocol_and_hostname,
+      )
+      .await
+    }
   }
   .map_err(ErrorBadRequest)?;
 
-  let rss = builder.build().to_string();
-
-  Ok(
-    HttpResponse::Ok()
-      .content_type("application/rss+xml")
-      .body(rss),
-  )
+  Ok(format.response(&builder.build())?)
 }
 
 #[tracing::instrument(skip_all)]
 async fn get_feed_user(
   pool: &DbPool,
+  local_user: Option<&LocalUser>,
   sort_type: &SortType,
   limit: &i64,
   page: &i64,
@@ -242,6 +359,7 @@ async fn get_feed_user(
     .listing_type(Some(ListingType::All))
     .sort(Some(*sort_type))
     .creator_id(Some(person.id))
+    .local_user(local_user)
     .limit(Some(*limit))
     .page(Some(*page))
     .build()
@@ -263,6 +381,7 @@ async fn get_feed_user(
 #[tracing::instrument(skip_all)]
 async fn get_feed_community(
   pool: &DbPool,
+  local_user: Option<&LocalUser>,
   sort_type: &SortType,
   limit: &i64,
   page: &i64,
@@ -276,6 +395,7 @@ async fn get_feed_community(
     .pool(pool)
     .sort(Some(*sort_type))
     .community_id(Some(community.id))
+    .local_user(local_user)
     .limit(Some(*limit))
     .page(Some(*page))
     .build()
@@ -309,8 +429,7 @@ async fn get_feed_front(
   protocol_and_hostname: &str,
 ) -> Result<ChannelBuilder, LemmyError> {
   let site_view = SiteView::read_local(pool).await?;
-  let local_user_id = LocalUserId(Claims::decode(jwt, jwt_secret)?.claims.sub);
-  let local_user = LocalUser::read(pool, local_user_id).await?;
+  let local_user = local_user_from_jwt(pool, jwt_secret, jwt).await?;
 
   let posts = PostQuery::builder()
     .pool(pool)
@@ -347,8 +466,7 @@ async fn get_feed_inbox(
   protocol_and_hostname: &str,
 ) -> Result<ChannelBuilder, LemmyError> {
   let site_view = SiteView::read_local(pool).await?;
-  let local_user_id = LocalUserId(Claims::decode(jwt, jwt_secret)?.claims.sub);
-  let local_user = LocalUser::read(pool, local_user_id).await?;
+  let local_user = local_user_from_jwt(pool, jwt_secret, jwt).await?;
   let person_id = local_user.person_id;
   let show_bot_accounts = local_user.show_bot_accounts;
 
@@ -392,6 +510,61 @@ async fn get_feed_inbox(
   Ok(channel_builder)
 }
 
+#[tracing::instrument(skip_all)]
+async fn get_feed_post(
+  pool: &DbPool,
+  local_user: Option<&LocalUser>,
+  sort_type: &CommentSortType,
+  limit: &i64,
+  page: &i64,
+  post_id: &str,
+  protocol_and_hostname: &str,
+) -> Result<ChannelBuilder, LemmyError> {
+  let site_view = SiteView::read_local(pool).await?;
+  let post_id = PostId(
+    post_id
+      .parse()
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_post"))?,
+  );
+  // Fails for deleted and removed posts
+  let post_view = PostView::read(pool, post_id, local_user.map(|l| l.person_id), None).await?;
+
+  let comments = CommentQuery::builder()
+    .pool(pool)
+    .post_id(Some(post_id))
+    .local_user(local_user)
+    .sort(Some(*sort_type))
+    .limit(Some(*limit))
+    .page(Some(*page))
+    .build()
+    .list()
+    .await?;
+
+  let items = create_comment_items(comments, protocol_and_hostname)?;
+
+  let mut channel_builder = ChannelBuilder::default();
+  channel_builder
+    .namespaces(RSS_NAMESPACE.clone())
+    .title(&format!(
+      "{} - {}",
+      site_view.site.name, post_view.post.name
+    ))
+    .link(format!("{}/post/{}", protocol_and_hostname, post_id.0))
+    .items(items);
+
+  Ok(channel_builder)
+}
+
+async fn local_user_from_jwt(
+  pool: &DbPool,
+  jwt_secret: &str,
+  jwt: &str,
+) -> Result<LocalUser, LemmyError> {
+  let local_user_id = LocalUserId(Claims::decode(jwt, jwt_secret)?.claims.sub);
+  check_login_token(local_user_id, jwt, pool).await?;
+  Ok(LocalUser::read(pool, local_user_id).await?)
+}
+
 #[tracing::instrument(skip_all)]
 fn create_reply_and_mention_items(
   replies: Vec<CommentReplyView>,
@@ -403,10 +576,12 @@ fn create_reply_and_mention_items(
     .map(|r| {
       let reply_url = format!("{}/comment/{}", protocol_and_hostname, r.comment.id);
       build_item(
+        format!("Reply from {}", r.creator.name),
         &r.creator.name,
         &r.comment.published,
         &reply_url,
         &r.comment.content,
+        r.comment.local,
         protocol_and_hostname,
       )
     })
@@ -417,10 +592,12 @@ fn create_reply_and_mention_items(
     .map(|m| {
       let mention_url = format!("{}/comment/{}", protocol_and_hostname, m.comment.id);
       build_item(
+        format!("Reply from {}", m.creator.name),
         &m.creator.name,
         &m.comment.published,
         &mention_url,
         &m.comment.content,
+        m.comment.local,
         protocol_and_hostname,
       )
     })
@@ -430,16 +607,42 @@ fn create_reply_and_mention_items(
   Ok(reply_items)
 }
 
+#[tracing::instrument(skip_all)]
+fn create_comment_items(
+  comments: Vec<CommentView>,
+  protocol_and_hostname: &str,
+) -> Result<Vec<Item>, LemmyError> {
+  comments
+    .iter()
+    // Like for posts, filtered and deleted comments are left out entirely
+    .filter(|c| !c.keyword_filtered && !c.comment.deleted && !c.comment.removed)
+    .map(|c| {
+      let comment_url = format!("{}/comment/{}", protocol_and_hostname, c.comment.id);
+      build_item(
+        format!("Comment from {}", c.creator.name),
+        &c.creator.name,
+        &c.comment.published,
+        &comment_url,
+        &c.comment.content,
+        c.comment.local,
+        protocol_and_hostname,
+      )
+    })
+    .collect()
+}
+
 #[tracing::instrument(skip_all)]
 fn build_item(
+  title: String,
   creator_name: &str,
   published: &NaiveDateTime,
   url: &str,
   content: &str,
+  local: bool,
   protocol_and_hostname: &str,
 ) -> Result<Item, LemmyError> {
   let mut i = ItemBuilder::default();
-  i.title(format!("Reply from {creator_name}"));
+  i.title(title);
   let author_url = format!("{protocol_and_hostname}/u/{creator_name}");
   i.author(format!(
     "/u/{creator_name} <a href=\"{author_url}\">(link)</a>"
@@ -451,7 +654,7 @@ fn build_item(
   i.guid(guid);
   i.link(url.to_owned());
   // TODO add images
-  let html = markdown_to_html(content);
+  let html = render_markdown(content, local);
   i.description(html);
   Ok(i.build())
 }
@@ -463,7 +666,8 @@ fn create_post_items(
 ) -> Result<Vec<Item>, LemmyError> {
   let mut items: Vec<Item> = Vec::new();
 
-  for p in posts {
+  // Feed readers can't collapse posts, so leave them out entirely
+  for p in posts.into_iter().filter(|p| !p.keyword_filtered) {
     let mut i = ItemBuilder::default();
     let mut dc_extension = DublinCoreExtensionBuilder::default();
 
@@ -504,7 +708,7 @@ fn create_post_items(
     }
 
     if let Some(body) = p.post.body {
-      let html = markdown_to_html(&body);
+      let html = render_markdown(&body, p.post.local);
       description.push_str(&html);
     }
 
@@ -516,3 +720,206 @@ fn create_post_items(
 
   Ok(items)
 }
+
+/// Renders the markdown of a post or comment for a feed. Inline images of content from other
+/// instances are loaded through the image proxy, which serves them because their links were
+/// recorded on receipt.
+fn render_markdown(markdown: &str, local: bool) -> String {
+  if local {
+    markdown_to_html(markdown)
+  } else {
+    markdown_to_html_with_image_proxy(markdown)
+  }
+}
+
+/// The publish date of an item, which is stored in RFC 2822 format for RSS.
+fn item_published(item: &Item) -> Option<DateTime<Utc>> {
+  item
+    .pub_date()
+    .and_then(|d| DateTime::parse_from_rfc2822(d).ok())
+    .map(|d| d.with_timezone(&Utc))
+}
+
+/// Post items name their creator in the Dublin Core extension, other items in the author field.
+fn item_author(item: &Item) -> Option<String> {
+  item
+    .dublin_core_ext()
+    .and_then(|dc| dc.creators().first().cloned())
+    .or_else(|| item.author().map(ToString::to_string))
+}
+
+fn escape_xml(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+    .replace('\'', "&apos;")
+}
+
+/// Converts an RSS channel to an Atom feed. Atom requires an id, an update time and an author for
+/// every entry, so they are filled in from the guid, publish date and creator of the items.
+fn channel_to_atom(channel: &Channel) -> String {
+  let updated = channel
+    .items()
+    .iter()
+    .filter_map(item_published)
+    .max()
+    .unwrap_or_else(Utc::now);
+
+  let mut atom = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+  atom.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">");
+  atom.push_str(&format!(
+    "<title>{}</title><id>{}</id><link href=\"{}\"/><updated>{}</updated>",
+    escape_xml(channel.title()),
+    escape_xml(channel.link()),
+    escape_xml(channel.link()),
+    updated.to_rfc3339()
+  ));
+  if !channel.description().is_empty() {
+    atom.push_str(&format!(
+      "<subtitle>{}</subtitle>",
+      escape_xml(channel.description())
+    ));
+  }
+  atom.push_str(&format!(
+    "<author><name>{}</name></author>",
+    escape_xml(channel.title())
+  ));
+
+  for item in channel.items() {
+    let link = item.link().unwrap_or_else(|| channel.link());
+    let id = item.guid().map(|g| g.value()).unwrap_or(link);
+    let published = item_published(item).unwrap_or(updated).to_rfc3339();
+    atom.push_str("<entry>");
+    atom.push_str(&format!(
+      "<title>{}</title><id>{}</id><link href=\"{}\"/><published>{}</published><updated>{}</updated>",
+      escape_xml(item.title().unwrap_or_default()),
+      escape_xml(id),
+      escape_xml(link),
+      published,
+      published
+    ));
+    if let Some(author) = item_author(item) {
+      atom.push_str(&format!(
+        "<author><name>{}</name></author>",
+        escape_xml(&author)
+      ));
+    }
+    if let Some(description) = item.description() {
+      atom.push_str(&format!(
+        "<content type=\"html\">{}</content>",
+        escape_xml(description)
+      ));
+    }
+    atom.push_str("</entry>");
+  }
+
+  atom.push_str("</feed>");
+  atom
+}
+
+/// A feed in the JSON Feed 1.1 format, see https://www.jsonfeed.org/version/1.1/
+#[derive(Serialize)]
+struct JsonFeed {
+  version: &'static str,
+  title: String,
+  home_page_url: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  description: Option<String>,
+  items: Vec<JsonFeedItem>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem {
+  id: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  url: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  title: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  content_html: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  date_published: Option<String>,
+  authors: Vec<JsonFeedAuthor>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAuthor {
+  name: String,
+}
+
+impl From<&Channel> for JsonFeed {
+  fn from(channel: &Channel) -> Self {
+    let items = channel
+      .items()
+      .iter()
+      .map(|item| {
+        let url = item.link().map(ToString::to_string);
+        JsonFeedItem {
+          id: item
+            .guid()
+            .map(|g| g.value().to_string())
+            .or_else(|| url.clone())
+            .unwrap_or_default(),
+          url,
+          title: item.title().map(ToString::to_string),
+          content_html: item.description().map(ToString::to_string),
+          date_published: item_published(item).map(|d| d.to_rfc3339()),
+          authors: item_author(item)
+            .map(|name| JsonFeedAuthor { name })
+            .into_iter()
+            .collect(),
+        }
+      })
+      .collect();
+    JsonFeed {
+      version: "https://jsonfeed.org/version/1.1",
+      title: channel.title().to_string(),
+      home_page_url: channel.link().to_string(),
+      description: Some(channel.description().to_string()).filter(|d| !d.is_empty()),
+      items,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::feeds::{channel_to_atom, JsonFeed};
+  use rss::{ChannelBuilder, GuidBuilder, ItemBuilder};
+
+  #[test]
+  fn test_feed_formats() {
+    let item = ItemBuilder::default()
+      .title(Some("Cats & dogs".to_string()))
+      .link(Some("https://example.com/post/1".to_string()))
+      .guid(Some(
+        GuidBuilder::default()
+          .permalink(true)
+          .value("https://example.com/post/1")
+          .build(),
+      ))
+      .pub_date(Some("Mon, 10 Jul 2023 09:00:00 +0000".to_string()))
+      .description(Some("<p>hello</p>".to_string()))
+      .build();
+    let channel = ChannelBuilder::default()
+      .title("Lemmy - All")
+      .link("https://example.com")
+      .items(vec![item])
+      .build();
+
+    let atom = channel_to_atom(&channel);
+    assert!(atom.contains("<title>Cats &amp; dogs</title>"));
+    assert!(atom.contains("<updated>2023-07-10T09:00:00+00:00</updated>"));
+    assert!(atom.contains("<content type=\"html\">&lt;p&gt;hello&lt;/p&gt;</content>"));
+
+    let json = serde_json::to_value(JsonFeed::from(&channel)).unwrap();
+    assert_eq!("https://example.com/post/1", json["items"][0]["id"]);
+    assert_eq!("Cats & dogs", json["items"][0]["title"]);
+    assert_eq!(
+      "2023-07-10T09:00:00+00:00",
+      json["items"][0]["date_published"]
+    );
+    assert!(json.get("description").is_none());
+  }
+}
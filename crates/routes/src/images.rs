@@ -1,22 +1,35 @@
 use actix_web::{
   body::BodyStream,
-  error,
+  error::{self, PayloadError},
   http::{
-    header::{HeaderName, ACCEPT_ENCODING, HOST},
+    header::{HeaderName, ACCEPT_ENCODING, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, HOST},
     StatusCode,
   },
-  web,
+  web::{self, Bytes},
   Error,
   HttpRequest,
   HttpResponse,
 };
 use futures::stream::{Stream, StreamExt};
-use lemmy_api_common::{context::LemmyContext, utils::local_user_view_from_jwt};
-use lemmy_db_schema::source::local_site::LocalSite;
-use lemmy_utils::{claims::Claims, rate_limit::RateLimitCell, REQWEST_TIMEOUT};
+use lemmy_api_common::{
+  context::LemmyContext,
+  request::delete_image_from_pictrs,
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::{
+  local_image::{LocalImage, LocalImageForm},
+  local_site::LocalSite,
+  remote_image::RemoteImage,
+};
+use lemmy_utils::{
+  rate_limit::RateLimitCell,
+  settings::structs::{PictrsConfig, PictrsImageMode},
+  REQWEST_TIMEOUT,
+};
 use reqwest::Body;
 use reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
 use serde::{Deserialize, Serialize};
+use url::Url;
 
 pub fn config(
   cfg: &mut web::ServiceConfig,
@@ -30,8 +43,16 @@ pub fn config(
         .wrap(rate_limit.image())
         .route(web::post().to(upload)),
     )
+    // Videos and audio are uploaded through the same pictrs endpoint, but the response says which
+    // kind of media each file is
+    .service(
+      web::resource("/pictrs/media")
+        .wrap(rate_limit.image())
+        .route(web::post().to(upload)),
+    )
     // This has optional query params: /image/{filename}?format=jpg&thumbnail=256
     .service(web::resource("/pictrs/image/{filename}").route(web::get().to(full_res)))
+    .service(web::resource("/pictrs/media/{filename}").route(web::get().to(full_media)))
     .service(web::resource("/pictrs/image/delete/{token}/{filename}").route(web::get().to(delete)));
 }
 
@@ -39,6 +60,35 @@ pub fn config(
 struct Image {
   file: String,
   delete_token: String,
+  details: Option<ImageDetails>,
+  /// Filled in by lemmy from the details, so that clients know how to show the file.
+  media_type: Option<MediaType>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum MediaType {
+  Image,
+  Video,
+  Audio,
+}
+
+impl MediaType {
+  fn from_content_type(content_type: &str) -> Option<Self> {
+    match content_type.split('/').next() {
+      Some("image") => Some(MediaType::Image),
+      Some("video") => Some(MediaType::Video),
+      Some("audio") => Some(MediaType::Audio),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ImageDetails {
+  width: u32,
+  height: u32,
+  content_type: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -47,12 +97,33 @@ struct Images {
   files: Option<Vec<Image>>,
 }
 
+/// The answer of the image classifier configured in the local site.
+#[derive(Deserialize)]
+struct ClassifierResponse {
+  action: ClassifierAction,
+  reason: Option<String>,
+}
+
+#[derive(Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum ClassifierAction {
+  Accept,
+  Reject,
+  /// Accept the image, but remember the reason so that admins can review it.
+  Flag,
+}
+
 #[derive(Deserialize)]
 struct PictrsParams {
   format: Option<String>,
   thumbnail: Option<i32>,
 }
 
+#[derive(Deserialize)]
+pub struct ImageProxyParams {
+  url: String,
+}
+
 #[derive(Deserialize)]
 enum PictrsPurgeParams {
   #[serde(rename = "file")]
@@ -85,21 +156,59 @@ fn adapt_request(
     })
 }
 
+/// The JWT of the uploader, either from the `jwt` cookie or from an `Authorization: Bearer` header.
+fn request_jwt(req: &HttpRequest) -> Option<String> {
+  if let Some(jwt) = req.cookie("jwt") {
+    return Some(jwt.value().to_string());
+  }
+  req
+    .headers()
+    .get(AUTHORIZATION)?
+    .to_str()
+    .ok()?
+    .strip_prefix("Bearer ")
+    .map(|jwt| jwt.trim().to_string())
+}
+
 async fn upload(
   req: HttpRequest,
   body: web::Payload,
   client: web::Data<ClientWithMiddleware>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  // TODO: check rate limit here
-  let jwt = req
-    .cookie("jwt")
-    .expect("No auth header for picture upload");
+  // Rate limiting is done by the `rate_limit.image()` middleware on this route
+  let jwt = match request_jwt(&req) {
+    Some(jwt) => jwt,
+    None => return Ok(HttpResponse::Unauthorized().finish()),
+  };
 
-  if Claims::decode(jwt.value(), &context.secret().jwt_secret).is_err() {
-    return Ok(HttpResponse::Unauthorized().finish());
+  let local_user_view = match local_user_view_from_jwt(&jwt, &context).await {
+    Ok(local_user_view) => local_user_view,
+    Err(_) => return Ok(HttpResponse::Unauthorized().finish()),
   };
 
+  let local_site = LocalSite::read(context.pool())
+    .await
+    .map_err(error::ErrorBadRequest)?;
+
+  // Refuse uploads which announce that they are too large before sending anything to pictrs
+  let max_bytes = local_site
+    .image_upload_max_bytes
+    .and_then(|max| usize::try_from(max).ok());
+  if let Some(max_bytes) = max_bytes {
+    let content_length = req
+      .headers()
+      .get(CONTENT_LENGTH)
+      .and_then(|l| l.to_str().ok())
+      .and_then(|l| l.parse::<usize>().ok());
+    if matches!(content_length, Some(l) if l > max_bytes) {
+      return Ok(HttpResponse::PayloadTooLarge().json(Images {
+        msg: "image_too_large".to_string(),
+        files: None,
+      }));
+    }
+  }
+
   let pictrs_config = context.settings().pictrs_config()?;
   let image_url = format!("{}image", pictrs_config.url);
 
@@ -110,15 +219,176 @@ async fn upload(
   };
 
   let res = client_req
-    .body(Body::wrap_stream(make_send(body)))
+    .body(Body::wrap_stream(make_send(limit_size(body, max_bytes))))
     .send()
     .await
     .map_err(error::ErrorBadRequest)?;
 
   let status = res.status();
   let images = res.json::<Images>().await.map_err(error::ErrorBadRequest)?;
+  if images.msg != "ok" {
+    return Ok(HttpResponse::build(status).json(images));
+  }
+  let mut files = images.files.unwrap_or_default();
+  for image in &mut files {
+    image.media_type = image
+      .details
+      .as_ref()
+      .and_then(|d| MediaType::from_content_type(&d.content_type));
+  }
 
-  Ok(HttpResponse::build(status).json(images))
+  // If any of the images breaks the site policy, the whole upload is rejected
+  let mut flag_reasons = vec![];
+  for image in &files {
+    let checked = match check_upload_policy(&local_site, image) {
+      Ok(()) => classify_image(&client, &pictrs_config, &local_site, image).await,
+      Err(e) => Err(e),
+    };
+    match checked {
+      Ok(flag_reason) => flag_reasons.push(flag_reason),
+      Err(msg) => {
+        for image in &files {
+          delete_image_from_pictrs(
+            &client,
+            context.settings(),
+            &image.file,
+            &image.delete_token,
+          )
+          .await
+          .ok();
+        }
+        return Ok(HttpResponse::BadRequest().json(Images { msg, files: None }));
+      }
+    }
+  }
+
+  // Remember who uploaded the images, so that they can be deleted along with the user
+  for (image, flag_reason) in files.iter().zip(flag_reasons) {
+    let form = LocalImageForm {
+      local_user_id: local_user_view.local_user.id,
+      pictrs_alias: image.file.clone(),
+      pictrs_delete_token: image.delete_token.clone(),
+      flag_reason,
+    };
+    LocalImage::create(context.pool(), &form)
+      .await
+      .map_err(error::ErrorBadRequest)?;
+  }
+
+  Ok(HttpResponse::build(status).json(Images {
+    msg: images.msg,
+    files: Some(files),
+  }))
+}
+
+/// Fails the upload stream once it gets larger than the limit, for clients which don't send a
+/// correct content length.
+fn limit_size<S>(
+  stream: S,
+  max_bytes: Option<usize>,
+) -> impl Stream<Item = Result<Bytes, PayloadError>> + Unpin + 'static
+where
+  S: Stream<Item = Result<Bytes, PayloadError>> + Unpin + 'static,
+{
+  let mut total_bytes = 0;
+  stream.map(move |chunk| {
+    let chunk = chunk?;
+    total_bytes += chunk.len();
+    match max_bytes {
+      Some(max_bytes) if total_bytes > max_bytes => Err(PayloadError::Overflow),
+      _ => Ok(chunk),
+    }
+  })
+}
+
+/// Checks the type and dimensions which pictrs reported for an uploaded image against the limits
+/// of the local site. Returns the error message if the image isn't allowed.
+fn check_upload_policy(local_site: &LocalSite, image: &Image) -> Result<(), String> {
+  let allowed_mime_types: Vec<String> = local_site
+    .image_upload_allowed_mime_types
+    .iter()
+    .flat_map(|types| types.split(','))
+    .map(|t| t.trim().to_lowercase())
+    .filter(|t| !t.is_empty())
+    .collect();
+  let max_dimension = local_site
+    .image_upload_max_dimension
+    .and_then(|max| u32::try_from(max).ok());
+  if allowed_mime_types.is_empty() && max_dimension.is_none() {
+    return Ok(());
+  }
+
+  // Without details the image can't be checked, so it isn't allowed
+  let details = image
+    .details
+    .as_ref()
+    .ok_or_else(|| "image_type_not_allowed".to_string())?;
+  if !allowed_mime_types.is_empty()
+    && !allowed_mime_types.contains(&details.content_type.to_lowercase())
+  {
+    return Err("image_type_not_allowed".to_string());
+  }
+  if let Some(max_dimension) = max_dimension {
+    if details.width > max_dimension || details.height > max_dimension {
+      return Err("image_too_large".to_string());
+    }
+  }
+  Ok(())
+}
+
+/// Sends an uploaded image to the classifier of the local site, if there is one. Returns the
+/// reason if the image should be flagged, or the error message if it should be rejected. If the
+/// classifier can't be reached the image is rejected, so that a broken classifier doesn't let
+/// everything through.
+async fn classify_image(
+  client: &ClientWithMiddleware,
+  pictrs_config: &PictrsConfig,
+  local_site: &LocalSite,
+  image: &Image,
+) -> Result<Option<String>, String> {
+  let classifier_url = match &local_site.image_upload_classifier_url {
+    Some(classifier_url) => classifier_url,
+    None => return Ok(None),
+  };
+  let classifier_failed = |e: reqwest_middleware::Error| {
+    tracing::warn!("Image classifier failed: {e}");
+    "image_classifier_failed".to_string()
+  };
+
+  let original_url = format!("{}image/original/{}", pictrs_config.url, image.file);
+  let res = client
+    .get(original_url)
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await
+    .map_err(classifier_failed)?;
+  let content_type = res.headers().get(CONTENT_TYPE).cloned();
+  let bytes = res.bytes().await.map_err(|e| classifier_failed(e.into()))?;
+
+  let mut classifier_req = client.post(classifier_url).timeout(REQWEST_TIMEOUT);
+  if let Some(content_type) = content_type {
+    classifier_req = classifier_req.header(CONTENT_TYPE, content_type);
+  }
+  let classification = classifier_req
+    .body(bytes)
+    .send()
+    .await
+    .map_err(classifier_failed)?
+    .error_for_status()
+    .map_err(|e| classifier_failed(e.into()))?
+    .json::<ClassifierResponse>()
+    .await
+    .map_err(|e| classifier_failed(e.into()))?;
+
+  match classification.action {
+    ClassifierAction::Accept => Ok(None),
+    ClassifierAction::Reject => Err("image_rejected".to_string()),
+    ClassifierAction::Flag => Ok(Some(
+      classification
+        .reason
+        .unwrap_or_else(|| "flagged_by_classifier".to_string()),
+    )),
+  }
 }
 
 async fn full_res(
@@ -166,6 +436,174 @@ async fn full_res(
   image(url, req, client).await
 }
 
+/// Serves uploaded videos and audio unchanged. Unlike images they can't be processed by pictrs,
+/// and the range header is passed on so that players can seek.
+async fn full_media(
+  filename: web::Path<String>,
+  req: HttpRequest,
+  client: web::Data<ClientWithMiddleware>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  let local_site = LocalSite::read(context.pool())
+    .await
+    .map_err(error::ErrorBadRequest)?;
+  if local_site.private_instance {
+    let logged_in = match request_jwt(&req) {
+      Some(jwt) => local_user_view_from_jwt(&jwt, &context).await.is_ok(),
+      None => false,
+    };
+    if !logged_in {
+      return Ok(HttpResponse::Unauthorized().finish());
+    }
+  }
+
+  let pictrs_config = context.settings().pictrs_config()?;
+  let url = format!(
+    "{}image/original/{}",
+    pictrs_config.url,
+    filename.into_inner()
+  );
+  image(url, req, client).await
+}
+
+/// Serves an image from another server through pictrs, so that clients don't need to contact
+/// the remote server. Remote image links are rewritten to this route when the image proxy is
+/// enabled in the pictrs config. Only links which were received from other instances are served.
+pub async fn image_proxy(
+  web::Query(params): web::Query<ImageProxyParams>,
+  req: HttpRequest,
+  client: web::Data<ClientWithMiddleware>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  let url = Url::parse(&params.url).map_err(error::ErrorBadRequest)?;
+  if !matches!(url.scheme(), "http" | "https") {
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+  let local_site = LocalSite::read(context.pool())
+    .await
+    .map_err(error::ErrorBadRequest)?;
+  if local_site.private_instance {
+    let logged_in = match req.cookie("jwt") {
+      Some(jwt) => local_user_view_from_jwt(jwt.value(), &context)
+        .await
+        .is_ok(),
+      None => false,
+    };
+    if !logged_in {
+      return Ok(HttpResponse::Unauthorized().finish());
+    }
+  }
+
+  let pictrs_config = context.settings().pictrs_config()?;
+  if pictrs_config.image_mode == PictrsImageMode::None {
+    return Ok(HttpResponse::NotFound().finish());
+  }
+  // Only images whose links were received from other instances are served, otherwise anyone could
+  // make this instance download arbitrary urls
+  let remote_image = RemoteImage::read_for_link(context.pool(), &url.clone().into())
+    .await
+    .map_err(error::ErrorBadRequest)?;
+  let remote_image = match remote_image {
+    Some(remote_image) => remote_image,
+    None => return Ok(HttpResponse::NotFound().finish()),
+  };
+
+  match pictrs_config.image_mode {
+    PictrsImageMode::None => Ok(HttpResponse::NotFound().finish()),
+    PictrsImageMode::Proxy => {
+      let image = download_image(&client, &pictrs_config, &url).await?;
+      let original_url = format!("{}image/original/{}", pictrs_config.url, image.file);
+      let res = client
+        .get(original_url)
+        .timeout(REQWEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(error::ErrorBadRequest)?;
+      let status = res.status();
+      let content_type = res.headers().get(CONTENT_TYPE).cloned();
+      // Read the whole image, so that pictrs can forget it before we respond
+      let bytes = res.bytes().await.map_err(error::ErrorBadRequest)?;
+      delete_image_from_pictrs(
+        &client,
+        context.settings(),
+        &image.file,
+        &image.delete_token,
+      )
+      .await
+      .ok();
+
+      let mut client_res = HttpResponse::build(status);
+      if let Some(content_type) = content_type {
+        client_res.insert_header((CONTENT_TYPE, content_type));
+      }
+      Ok(client_res.body(bytes))
+    }
+    PictrsImageMode::CacheAndStore => {
+      let alias = match remote_image.pictrs_alias {
+        Some(alias) => alias,
+        None => {
+          let image = download_image(&client, &pictrs_config, &url).await?;
+          let stored = RemoteImage::store_copy(
+            context.pool(),
+            remote_image.id,
+            &image.file,
+            &image.delete_token,
+          )
+          .await
+          .map_err(error::ErrorBadRequest)?;
+          match stored {
+            Some(_) => image.file,
+            None => {
+              // Another request stored a copy first, so ours isn't needed
+              delete_image_from_pictrs(
+                &client,
+                context.settings(),
+                &image.file,
+                &image.delete_token,
+              )
+              .await
+              .ok();
+              RemoteImage::read_for_link(context.pool(), &remote_image.link)
+                .await
+                .map_err(error::ErrorBadRequest)?
+                .and_then(|i| i.pictrs_alias)
+                .ok_or_else(|| error::ErrorNotFound("remote_image_not_found"))?
+            }
+          }
+        }
+      };
+      let original_url = format!("{}image/original/{}", pictrs_config.url, alias);
+      image(original_url, req, client).await
+    }
+  }
+}
+
+/// Lets pictrs download a remote image, and returns its alias in pictrs.
+async fn download_image(
+  client: &ClientWithMiddleware,
+  pictrs_config: &PictrsConfig,
+  url: &Url,
+) -> Result<Image, Error> {
+  let download_url = format!("{}image/download", pictrs_config.url);
+  let images = client
+    .get(download_url)
+    .query(&[("url", url.as_str())])
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await
+    .map_err(error::ErrorBadRequest)?
+    .json::<Images>()
+    .await
+    .map_err(error::ErrorBadRequest)?;
+  if images.msg != "ok" {
+    return Err(error::ErrorBadRequest(images.msg));
+  }
+  images
+    .files
+    .and_then(|files| files.into_iter().next())
+    .ok_or_else(|| error::ErrorBadRequest("no_image_downloaded"))
+}
+
 async fn image(
   url: String,
   req: HttpRequest,
@@ -215,6 +653,12 @@ async fn delete(
 
   let res = client_req.send().await.map_err(error::ErrorBadRequest)?;
 
+  if res.status().is_success() {
+    LocalImage::delete_by_alias(context.pool(), &file)
+      .await
+      .map_err(error::ErrorBadRequest)?;
+  }
+
   Ok(HttpResponse::build(res.status()).body(BodyStream::new(res.bytes_stream())))
 }
 
@@ -12,6 +12,10 @@ pub struct Claims {
   pub iss: String,
   /// Time when this token was issued as UNIX-timestamp in seconds
   pub iat: i64,
+  /// Random id, so that each login gets a distinct token even if they happen within the same
+  /// second. Tokens issued before this was added don't have it.
+  #[serde(default)]
+  pub jti: String,
 }
 
 impl Claims {
@@ -28,9 +32,26 @@ impl Claims {
       sub: local_user_id,
       iss: hostname.to_string(),
       iat: Utc::now().timestamp(),
+      jti: uuid::Uuid::new_v4().to_string(),
     };
 
     let key = EncodingKey::from_secret(jwt_secret.as_ref());
     Ok(encode(&Header::default(), &my_claims, &key)?)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::claims::Claims;
+
+  #[test]
+  fn test_jwt_is_unique() {
+    let first = Claims::jwt(1, "secret", "lemmy.tld").unwrap();
+    let second = Claims::jwt(1, "secret", "lemmy.tld").unwrap();
+    assert_ne!(first, second);
+
+    let claims = Claims::decode(&first, "secret").unwrap().claims;
+    assert_eq!(1, claims.sub);
+    assert!(!claims.jti.is_empty());
+  }
+}
@@ -5,6 +5,7 @@ extern crate smart_default;
 
 pub mod apub;
 pub mod email;
+pub mod metrics;
 pub mod rate_limit;
 pub mod settings;
 
@@ -0,0 +1,100 @@
+//! Prometheus metrics which are collected throughout the different crates. They are registered
+//! in the default registry, and served by the server binary if enabled in the config.
+use once_cell::sync::Lazy;
+use prometheus::{
+  register_histogram_vec,
+  register_int_counter,
+  register_int_counter_vec,
+  HistogramVec,
+  IntCounter,
+  IntCounterVec,
+};
+
+pub static HTTP_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+  register_int_counter_vec!(
+    "lemmy_http_requests_total",
+    "Number of handled http requests",
+    &["endpoint", "method", "status"]
+  )
+  .expect("register metric")
+});
+
+pub static HTTP_REQUEST_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+  register_histogram_vec!(
+    "lemmy_http_request_duration_seconds",
+    "Time taken to handle http requests",
+    &["endpoint", "method"]
+  )
+  .expect("register metric")
+});
+
+pub static RATE_LIMIT_REJECTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+  register_int_counter_vec!(
+    "lemmy_rate_limit_rejections_total",
+    "Number of http requests which were rejected by the rate limit",
+    &["type"]
+  )
+  .expect("register metric")
+});
+
+pub static ACTIVITIES_SENT: Lazy<IntCounter> = Lazy::new(|| {
+  register_int_counter!(
+    "lemmy_federation_activities_sent_total",
+    "Number of outgoing activities"
+  )
+  .expect("register metric")
+});
+
+pub static ACTIVITY_DELIVERIES: Lazy<IntCounter> = Lazy::new(|| {
+  register_int_counter!(
+    "lemmy_federation_activity_deliveries_total",
+    "Number of inboxes which outgoing activities were addressed to"
+  )
+  .expect("register metric")
+});
+
+pub static ACTIVITIES_RECEIVED: Lazy<IntCounterVec> = Lazy::new(|| {
+  register_int_counter_vec!(
+    "lemmy_federation_activities_received_total",
+    "Number of incoming activities, by inbox type and whether they were accepted",
+    &["inbox", "result"]
+  )
+  .expect("register metric")
+});
+
+pub static SCHEDULED_TASK_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+  register_histogram_vec!(
+    "lemmy_scheduled_task_duration_seconds",
+    "Time taken by each run of a scheduled task",
+    &["task"],
+    vec![0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0, 900.0, 3600.0]
+  )
+  .expect("register metric")
+});
+
+/// Counts an incoming activity for the given type of inbox.
+pub fn record_activity_received<T, E>(inbox: &str, result: &Result<T, E>) {
+  let result = if result.is_ok() { "success" } else { "error" };
+  ACTIVITIES_RECEIVED
+    .with_label_values(&[inbox, result])
+    .inc();
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{record_activity_received, ACTIVITIES_RECEIVED};
+
+  #[test]
+  fn test_record_activity_received() {
+    record_activity_received::<(), ()>("metrics_test", &Ok(()));
+    record_activity_received::<(), ()>("metrics_test", &Err(()));
+    record_activity_received::<(), ()>("metrics_test", &Err(()));
+    let count = |result| {
+      ACTIVITIES_RECEIVED
+        .with_label_values(&["metrics_test", result])
+        .get()
+    };
+    assert_eq!(1, count("success"));
+    assert_eq!(2, count("error"));
+  }
+}
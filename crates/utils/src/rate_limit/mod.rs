@@ -1,4 +1,4 @@
-use crate::error::LemmyError;
+use crate::{error::LemmyError, metrics::RATE_LIMIT_REJECTIONS};
 use actix_web::dev::{ConnectionInfo, Service, ServiceRequest, ServiceResponse, Transform};
 use enum_map::enum_map;
 use futures::future::{ok, Ready};
@@ -238,12 +238,16 @@ where
     let ip_addr = get_ip(&req.connection_info());
 
     let rate_limited = self.rate_limited.clone();
+    let type_ = rate_limited.type_;
     let service = self.service.clone();
 
     Box::pin(async move {
       if rate_limited.check(ip_addr) {
         service.call(req).await
       } else {
+        RATE_LIMIT_REJECTIONS
+          .with_label_values(&[type_.as_ref()])
+          .inc();
         let (http_req, _) = req.into_parts();
         Ok(ServiceResponse::from_err(
           LemmyError::from_message("rate_limit_error"),
@@ -1,7 +1,7 @@
 use crate::{
   error::LemmyError,
   location_info,
-  settings::structs::{PictrsConfig, Settings},
+  settings::structs::{OidcProviderConfig, PictrsConfig, Settings},
 };
 use anyhow::{anyhow, Context};
 use deser_hjson::from_str;
@@ -108,4 +108,13 @@ impl Settings {
       .clone()
       .ok_or_else(|| anyhow!("images_disabled").into())
   }
+
+  /// Returns the configured OpenID Connect provider with the given name.
+  pub fn oidc_provider(&self, name: &str) -> Result<&OidcProviderConfig, LemmyError> {
+    self
+      .oidc_providers
+      .iter()
+      .find(|p| p.name == name)
+      .ok_or_else(|| LemmyError::from_message("oauth_provider_not_found"))
+  }
 }
@@ -39,12 +39,21 @@ pub struct Settings {
   #[default(None)]
   #[doku(skip)]
   pub opentelemetry_url: Option<Url>,
-  /// The number of activitypub federation workers that can be in-flight concurrently
+  /// Deprecated and ignored. Outgoing activities are delivered by a persistent queue instead, which
+  /// only runs in the process that also runs the scheduled tasks (started without
+  /// `--disable-scheduled-tasks`).
   #[default(0)]
   pub worker_count: usize,
-  /// The number of activitypub federation retry workers that can be in-flight concurrently
+  /// Deprecated and ignored, failed deliveries are retried by the persistent queue.
   #[default(0)]
   pub retry_count: usize,
+  /// Serves prometheus metrics on a separate address. Disabled if not set
+  #[default(None)]
+  #[doku(example = "Some(Default::default())")]
+  pub prometheus: Option<PrometheusConfig>,
+  /// OpenID Connect providers which can be used to log in
+  #[default(Vec::new())]
+  pub oidc_providers: Vec<OidcProviderConfig>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
@@ -58,6 +67,25 @@ pub struct PictrsConfig {
   /// Set a custom pictrs API key. ( Required for deleting images )
   #[default(None)]
   pub api_key: Option<String>,
+
+  /// How images from other servers are loaded. With `None` clients load them directly from the
+  /// remote servers, which reveals the IP addresses of users to them. `Proxy` loads them through
+  /// pictrs instead, and `CacheAndStore` additionally keeps a copy of each image in pictrs, so that
+  /// it stays available when the remote server goes away.
+  #[default(PictrsImageMode::None)]
+  pub image_mode: PictrsImageMode,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, SmartDefault, Document)]
+pub enum PictrsImageMode {
+  /// Leave links to remote images unchanged.
+  #[default]
+  None,
+  /// Rewrite links to remote images to the image proxy, which fetches them through pictrs without
+  /// keeping them.
+  Proxy,
+  /// Like `Proxy`, but the image proxy keeps each image in pictrs after the first request.
+  CacheAndStore,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
@@ -157,3 +185,52 @@ pub struct SetupConfig {
   #[default(None)]
   pub admin_email: Option<String>,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrometheusConfig {
+  /// Address where the metrics endpoint should listen. It shouldn't be publicly reachable
+  #[default(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))]
+  #[doku(as = "String")]
+  pub bind: IpAddr,
+  /// Port where the metrics endpoint should listen
+  #[default(10002)]
+  pub port: u16,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(deny_unknown_fields)]
+pub struct OidcProviderConfig {
+  /// Short name of the provider, used in the authorize and callback urls
+  #[doku(example = "keycloak")]
+  pub name: String,
+  /// Issuer url of the provider, the discovery document is fetched from
+  /// `{issuer}/.well-known/openid-configuration`
+  #[default(Url::parse("http://localhost:8080").expect("parse issuer url"))]
+  #[doku(example = "https://auth.example.com/realms/lemmy")]
+  pub issuer: Url,
+  /// Client id which is registered with the provider
+  #[doku(example = "lemmy")]
+  pub client_id: String,
+  /// Client secret which is registered with the provider
+  pub client_secret: String,
+  /// Scopes to request during login. Must include `openid`
+  #[default(default_oidc_scopes())]
+  #[serde(default = "default_oidc_scopes")]
+  pub scopes: Vec<String>,
+  /// Automatically create a new account if no existing user could be linked to the external
+  /// account. Respects the registration mode of the site.
+  #[default(false)]
+  #[serde(default)]
+  pub auto_register: bool,
+  /// Link the external account to an existing user with the same email, if the provider marks the
+  /// email as verified. Only enable this if the provider doesn't let users set arbitrary emails,
+  /// otherwise it allows taking over accounts.
+  #[default(false)]
+  #[serde(default)]
+  pub link_by_email: bool,
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+  vec!["openid".into(), "email".into(), "profile".into()]
+}
@@ -0,0 +1,130 @@
+use crate::{
+  settings::{
+    structs::{PictrsImageMode, Settings},
+    SETTINGS,
+  },
+  utils::markdown::markdown_to_html_rewrite_images,
+};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use url::Url;
+
+/// Whether the link points to an image on another server.
+pub fn is_remote_image_link(link: &Url) -> bool {
+  is_remote_image_link_for(link, &SETTINGS)
+}
+
+/// Rewrites a link to an image on another server to the image proxy of this instance, if it is
+/// enabled. Local links are returned unchanged.
+pub fn proxy_image_link(link: Url) -> Url {
+  proxy_image_link_for(link, &SETTINGS)
+}
+
+/// Renders markdown to HTML, with inline images on other servers loaded through the image proxy
+/// if it is enabled. Only use this for content which was received from other instances, as the
+/// image proxy only serves the links which were recorded then.
+pub fn markdown_to_html_with_image_proxy(text: &str) -> String {
+  markdown_to_html_with_image_proxy_for(text, &SETTINGS)
+}
+
+fn image_proxy_enabled_for(settings: &Settings) -> bool {
+  settings
+    .pictrs
+    .as_ref()
+    .map(|p| p.image_mode != PictrsImageMode::None)
+    .unwrap_or(false)
+}
+
+fn is_remote_image_link_for(link: &Url, settings: &Settings) -> bool {
+  if !matches!(link.scheme(), "http" | "https") {
+    return false;
+  }
+  let authority = match (link.host_str(), link.port()) {
+    (Some(host), Some(port)) => format!("{host}:{port}"),
+    (Some(host), None) => host.to_string(),
+    (None, _) => return false,
+  };
+  authority != settings.hostname
+}
+
+fn proxy_image_link_for(link: Url, settings: &Settings) -> Url {
+  if !image_proxy_enabled_for(settings) || !is_remote_image_link_for(&link, settings) {
+    return link;
+  }
+  let proxied = format!(
+    "{}/api/v3/image_proxy?url={}",
+    settings.get_protocol_and_hostname(),
+    utf8_percent_encode(link.as_str(), NON_ALPHANUMERIC)
+  );
+  Url::parse(&proxied).unwrap_or(link)
+}
+
+fn markdown_to_html_with_image_proxy_for(text: &str, settings: &Settings) -> String {
+  markdown_to_html_rewrite_images(text, |link| {
+    Some(proxy_image_link_for(link.clone(), settings))
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    settings::structs::{PictrsConfig, PictrsImageMode, Settings},
+    utils::{
+      image_proxy::{markdown_to_html_with_image_proxy_for, proxy_image_link_for},
+      markdown::markdown_to_html,
+    },
+  };
+  use url::Url;
+
+  fn settings(image_mode: PictrsImageMode) -> Settings {
+    Settings {
+      hostname: "lemmy.example".to_string(),
+      pictrs: Some(PictrsConfig {
+        image_mode,
+        ..Default::default()
+      }),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn test_proxy_image_link() {
+    let remote = Url::parse("https://remote.example/pictrs/image/a b.png?x=1").unwrap();
+    let local = Url::parse("https://lemmy.example/pictrs/image/abc.png").unwrap();
+
+    let proxied = proxy_image_link_for(remote.clone(), &settings(PictrsImageMode::Proxy));
+    assert_eq!(
+      "https://lemmy.example/api/v3/image_proxy?url=https%3A%2F%2Fremote%2Eexample%2Fpictrs%2Fimage%2Fa%2520b%2Epng%3Fx%3D1",
+      proxied.as_str()
+    );
+    let stored = proxy_image_link_for(remote.clone(), &settings(PictrsImageMode::CacheAndStore));
+    assert_eq!(proxied, stored);
+
+    // Local images and disabled proxying leave links unchanged
+    let unchanged = proxy_image_link_for(local.clone(), &settings(PictrsImageMode::Proxy));
+    assert_eq!(local, unchanged);
+    let unchanged = proxy_image_link_for(remote.clone(), &settings(PictrsImageMode::None));
+    assert_eq!(remote, unchanged);
+  }
+
+  #[test]
+  fn test_markdown_to_html_with_image_proxy() {
+    let text = "![a](https://remote.example/a.png) ![b](https://lemmy.example/b.png)";
+
+    let proxied = proxy_image_link_for(
+      Url::parse("https://remote.example/a.png").unwrap(),
+      &settings(PictrsImageMode::Proxy),
+    );
+    let html = markdown_to_html_with_image_proxy_for(text, &settings(PictrsImageMode::Proxy));
+    assert_eq!(
+      format!(
+        "<p><img src=\"{proxied}\" alt=\"a\" /> \
+         <img src=\"https://lemmy.example/b.png\" alt=\"b\" /></p>\n"
+      ),
+      html
+    );
+
+    // Disabled proxying renders the original links
+    let html = markdown_to_html_with_image_proxy_for(text, &settings(PictrsImageMode::None));
+    assert_eq!(markdown_to_html(text), html);
+  }
+}
@@ -1,5 +1,6 @@
-use markdown_it::MarkdownIt;
+use markdown_it::{plugins::cmark::inline::image::Image, MarkdownIt};
 use once_cell::sync::Lazy;
+use url::Url;
 
 mod spoiler_rule;
 
@@ -16,9 +17,56 @@ pub fn markdown_to_html(text: &str) -> String {
   MARKDOWN_PARSER.parse(text).xrender()
 }
 
+/// Returns the links of inline images like `![alt](https://example.com/image.png)` in the
+/// markdown.
+pub fn markdown_image_links(text: &str) -> Vec<Url> {
+  let mut links = image_links(text)
+    .into_iter()
+    .map(|(_, link)| link)
+    .collect::<Vec<_>>();
+  links.sort_unstable();
+  links.dedup();
+  links
+}
+
+/// Renders the markdown like [`markdown_to_html`], with the links of inline images replaced.
+/// Links for which `rewrite` returns None are left unchanged.
+pub fn markdown_to_html_rewrite_images(
+  text: &str,
+  rewrite: impl Fn(&Url) -> Option<Url>,
+) -> String {
+  let mut root = MARKDOWN_PARSER.parse(text);
+  root.walk_mut(|node, _| {
+    if let Some(image) = node.cast_mut::<Image>() {
+      if let Some(new_link) = Url::parse(&image.url).ok().as_ref().and_then(&rewrite) {
+        image.url = new_link.into();
+      }
+    }
+  });
+  root.xrender()
+}
+
+/// The links of inline images, as written in the markdown and parsed.
+fn image_links(text: &str) -> Vec<(String, Url)> {
+  let mut links = vec![];
+  MARKDOWN_PARSER.parse(text).walk(|node, _| {
+    if let Some(image) = node.cast::<Image>() {
+      if let Ok(link) = Url::parse(&image.url) {
+        links.push((image.url.clone(), link));
+      }
+    }
+  });
+  links
+}
+
 #[cfg(test)]
 mod tests {
-  use crate::utils::markdown::markdown_to_html;
+  use crate::utils::markdown::{
+    markdown_image_links,
+    markdown_to_html,
+    markdown_to_html_rewrite_images,
+  };
+  use url::Url;
 
   #[test]
   fn test_basic_markdown() {
@@ -80,4 +128,24 @@ mod tests {
       );
     });
   }
+
+  #[test]
+  fn test_markdown_image_links() {
+    let text = "![a](https://remote.example/a.png) [link](https://remote.example/page) \
+                ![b](https://lemmy.example/b.png) ![a again](https://remote.example/a.png)";
+    let remote = Url::parse("https://remote.example/a.png").unwrap();
+    let local = Url::parse("https://lemmy.example/b.png").unwrap();
+    assert_eq!(vec![local, remote.clone()], markdown_image_links(text));
+
+    let html = markdown_to_html_rewrite_images(text, |link| {
+      (link == &remote).then(|| Url::parse("https://lemmy.example/proxied.png").unwrap())
+    });
+    assert_eq!(
+      "<p><img src=\"https://lemmy.example/proxied.png\" alt=\"a\" /> \
+       <a href=\"https://remote.example/page\">link</a> \
+       <img src=\"https://lemmy.example/b.png\" alt=\"b\" /> \
+       <img src=\"https://lemmy.example/proxied.png\" alt=\"a again\" /></p>\n",
+      html
+    );
+  }
 }
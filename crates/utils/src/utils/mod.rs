@@ -1,5 +1,8 @@
+pub mod image_proxy;
 pub mod markdown;
 pub mod mention;
+pub mod schedule;
+pub mod search;
 pub mod slurs;
 pub mod time;
 pub mod validation;
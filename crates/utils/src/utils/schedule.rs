@@ -0,0 +1,206 @@
+use crate::error::{LemmyError, LemmyResult};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+
+/// How many days ahead to look for the next matching time.
+const MAX_DAYS_AHEAD: i64 = 366 * 5;
+
+/// A cron-like schedule with the fields `minute hour day-of-month month day-of-week`, for example
+/// `0 9 * * 1` for every Monday at 09:00 UTC. Fields can be `*`, numbers, ranges like `1-5`, lists
+/// like `1,15` and steps like `*/2`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Schedule {
+  minutes: Vec<u32>,
+  hours: Vec<u32>,
+  days_of_month: Vec<u32>,
+  months: Vec<u32>,
+  /// Sunday is 0.
+  days_of_week: Vec<u32>,
+  /// Like in cron, if both the day of month and the day of week are restricted, a day matching
+  /// either of them is enough.
+  day_of_month_restricted: bool,
+  day_of_week_restricted: bool,
+}
+
+impl Schedule {
+  pub fn parse(schedule: &str) -> LemmyResult<Self> {
+    let fields: Vec<&str> = schedule.split_whitespace().collect();
+    if fields.len() != 5 {
+      return Err(LemmyError::from_message("invalid_schedule"));
+    }
+
+    let mut days_of_week = parse_field(fields[4], 0, 7)?;
+    // Both 0 and 7 mean Sunday
+    if days_of_week.last() == Some(&7) {
+      days_of_week.pop();
+      if days_of_week.first() != Some(&0) {
+        days_of_week.insert(0, 0);
+      }
+    }
+
+    Ok(Schedule {
+      minutes: parse_field(fields[0], 0, 59)?,
+      hours: parse_field(fields[1], 0, 23)?,
+      days_of_month: parse_field(fields[2], 1, 31)?,
+      months: parse_field(fields[3], 1, 12)?,
+      days_of_week,
+      day_of_month_restricted: fields[2] != "*",
+      day_of_week_restricted: fields[4] != "*",
+    })
+  }
+
+  /// The first time matching the schedule which is later than the given time. None if the
+  /// schedule never matches, like for February 30.
+  pub fn next_after(&self, time: NaiveDateTime) -> Option<NaiveDateTime> {
+    let start = time.date();
+    for day_offset in 0..MAX_DAYS_AHEAD {
+      let date = start + Duration::days(day_offset);
+      if !self.matches_date(date) {
+        continue;
+      }
+      for hour in &self.hours {
+        for minute in &self.minutes {
+          let candidate = date.and_hms_opt(*hour, *minute, 0)?;
+          if candidate > time {
+            return Some(candidate);
+          }
+        }
+      }
+    }
+    None
+  }
+
+  fn matches_date(&self, date: NaiveDate) -> bool {
+    if !self.months.contains(&date.month()) {
+      return false;
+    }
+    let day_of_month = self.days_of_month.contains(&date.day());
+    let day_of_week = self
+      .days_of_week
+      .contains(&date.weekday().num_days_from_sunday());
+    if self.day_of_month_restricted && self.day_of_week_restricted {
+      day_of_month || day_of_week
+    } else {
+      day_of_month && day_of_week
+    }
+  }
+}
+
+/// Parses a single field of the schedule into the sorted list of values it matches.
+fn parse_field(field: &str, min: u32, max: u32) -> LemmyResult<Vec<u32>> {
+  let mut values = vec![];
+  for part in field.split(',') {
+    let (range, step) = match part.split_once('/') {
+      Some((range, step)) => (range, parse_number(step)?),
+      None => (part, 1),
+    };
+    let (start, end) = if range == "*" {
+      (min, max)
+    } else if let Some((start, end)) = range.split_once('-') {
+      (parse_number(start)?, parse_number(end)?)
+    } else {
+      let value = parse_number(range)?;
+      // `5/15` means every 15 starting at 5
+      (value, if step > 1 { max } else { value })
+    };
+    if step == 0 || start < min || end > max || start > end {
+      return Err(LemmyError::from_message("invalid_schedule"));
+    }
+    values.extend((start..=end).step_by(step as usize));
+  }
+  values.sort_unstable();
+  values.dedup();
+  Ok(values)
+}
+
+fn parse_number(value: &str) -> LemmyResult<u32> {
+  value
+    .parse()
+    .map_err(|_| LemmyError::from_message("invalid_schedule"))
+}
+
+/// Replaces `{date}` (like 2023-07-10), `{year}`, `{month}`, `{month_name}`, `{day}` and `{week}`
+/// (the ISO week number) in the title of a recurring post.
+pub fn replace_date_placeholders(template: &str, time: NaiveDateTime) -> String {
+  template
+    .replace("{date}", &time.format("%Y-%m-%d").to_string())
+    .replace("{year}", &time.format("%Y").to_string())
+    .replace("{month_name}", &time.format("%B").to_string())
+    .replace("{month}", &time.format("%m").to_string())
+    .replace("{day}", &time.format("%d").to_string())
+    .replace("{week}", &time.iso_week().week().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::schedule::{replace_date_placeholders, Schedule};
+  use chrono::{NaiveDate, NaiveDateTime};
+
+  fn time(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, day)
+      .and_then(|d| d.and_hms_opt(hour, minute, 0))
+      .unwrap()
+  }
+
+  #[test]
+  fn test_next_after() {
+    // Every Monday at 09:00, starting from Wednesday 2023-07-05
+    let weekly = Schedule::parse("0 9 * * 1").unwrap();
+    let next = weekly.next_after(time(2023, 7, 5, 12, 0));
+    assert_eq!(Some(time(2023, 7, 10, 9, 0)), next);
+    let next = weekly.next_after(time(2023, 7, 10, 9, 0));
+    assert_eq!(Some(time(2023, 7, 17, 9, 0)), next);
+
+    // Twice a day on weekdays
+    let weekdays = Schedule::parse("30 8,20 * * 1-5").unwrap();
+    let next = weekdays.next_after(time(2023, 7, 7, 21, 0));
+    assert_eq!(Some(time(2023, 7, 10, 8, 30)), next);
+
+    // The first of the month, or any Sunday
+    let either = Schedule::parse("0 0 1 * 7").unwrap();
+    let next = either.next_after(time(2023, 7, 26, 0, 0));
+    assert_eq!(Some(time(2023, 7, 30, 0, 0)), next);
+    let next = either.next_after(time(2023, 7, 30, 0, 0));
+    assert_eq!(Some(time(2023, 8, 1, 0, 0)), next);
+
+    // Every other hour
+    let steps = Schedule::parse("15 */2 * * *").unwrap();
+    let next = steps.next_after(time(2023, 7, 5, 1, 0));
+    assert_eq!(Some(time(2023, 7, 5, 2, 15)), next);
+
+    let never = Schedule::parse("0 0 30 2 *").unwrap();
+    assert_eq!(None, never.next_after(time(2023, 7, 5, 1, 0)));
+  }
+
+  #[test]
+  fn test_parse_invalid() {
+    for schedule in [
+      "",
+      "0 9 * *",
+      "0 9 * * 1 2",
+      "60 9 * * 1",
+      "0 24 * * 1",
+      "0 9 0 * *",
+      "0 9 * 13 *",
+      "0 9 * * 8",
+      "0 9 * * 5-1",
+      "*/0 9 * * 1",
+      "0 9 * * monday",
+    ] {
+      let error = Schedule::parse(schedule).unwrap_err();
+      assert_eq!(
+        Some("invalid_schedule".to_string()),
+        error.message,
+        "{schedule}"
+      );
+    }
+  }
+
+  #[test]
+  fn test_replace_date_placeholders() {
+    let title = replace_date_placeholders(
+      "Megathread {date} / week {week}, {month_name} {day}, {year}",
+      time(2023, 7, 10, 9, 0),
+    );
+    assert_eq!("Megathread 2023-07-10 / week 28, July 10, 2023", title);
+  }
+}
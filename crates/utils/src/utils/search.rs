@@ -0,0 +1,160 @@
+use crate::error::{LemmyError, LemmyResult};
+use chrono::{NaiveDate, NaiveDateTime};
+
+/// A search query, split into the text to search for and the filters given with operators like
+/// `author:alice@example.com` or `before:2023-06-01`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+  /// The query without the operators.
+  pub text: String,
+  /// Name of the creator, optionally with the domain of their instance.
+  pub author: Option<String>,
+  /// Name of the community, optionally with the domain of its instance.
+  pub community: Option<String>,
+  /// Domain of the instance which the community belongs to.
+  pub instance: Option<String>,
+  pub url: Option<String>,
+  /// Only NSFW content if true, or only content which isn't NSFW if false.
+  pub nsfw: Option<bool>,
+  /// Content published before the start of the given day.
+  pub before: Option<NaiveDateTime>,
+  /// Content published on or after the given day.
+  pub after: Option<NaiveDateTime>,
+}
+
+impl SearchQuery {
+  /// Words with an unknown operator (like `https://example.com`) are kept as part of the search
+  /// text, but known operators with an invalid value are rejected.
+  pub fn parse(q: &str) -> LemmyResult<Self> {
+    let mut query = SearchQuery::default();
+    let mut text = vec![];
+
+    for word in split_words(q) {
+      let (operator, value) = match word.split_once(':') {
+        Some((operator, value)) if !word.starts_with('"') => (operator.to_lowercase(), value),
+        _ => {
+          text.push(word);
+          continue;
+        }
+      };
+      let known_operator = matches!(
+        operator.as_str(),
+        "author" | "community" | "instance" | "url" | "nsfw" | "before" | "after"
+      );
+      if !known_operator {
+        text.push(word);
+        continue;
+      }
+      if value.is_empty() {
+        return Err(LemmyError::from_message("invalid_search_operator"));
+      }
+
+      match operator.as_str() {
+        "author" => query.author = Some(value.to_string()),
+        "community" => query.community = Some(value.to_string()),
+        "instance" => query.instance = Some(value.to_lowercase()),
+        "url" => query.url = Some(value.to_string()),
+        "nsfw" => query.nsfw = Some(parse_yes_no(value)?),
+        "before" => query.before = Some(parse_date(value)?),
+        _ => query.after = Some(parse_date(value)?),
+      }
+    }
+
+    query.text = text.join(" ");
+    Ok(query)
+  }
+}
+
+/// Splits the query at whitespace, but keeps quoted phrases together.
+fn split_words(q: &str) -> Vec<&str> {
+  let mut words = vec![];
+  let mut start = None;
+  let mut quoted = false;
+  for (i, c) in q.char_indices() {
+    if c == '"' {
+      quoted = !quoted;
+    }
+    if c.is_whitespace() && !quoted {
+      if let Some(s) = start.take() {
+        words.push(&q[s..i]);
+      }
+    } else if start.is_none() {
+      start = Some(i);
+    }
+  }
+  if let Some(s) = start {
+    words.push(&q[s..]);
+  }
+  words
+}
+
+fn parse_yes_no(value: &str) -> LemmyResult<bool> {
+  match value.to_lowercase().as_str() {
+    "yes" | "true" => Ok(true),
+    "no" | "false" => Ok(false),
+    _ => Err(LemmyError::from_message("invalid_search_operator")),
+  }
+}
+
+fn parse_date(value: &str) -> LemmyResult<NaiveDateTime> {
+  NaiveDate::parse_from_str(value, "%Y-%m-%d")
+    .ok()
+    .and_then(|d| d.and_hms_opt(0, 0, 0))
+    .ok_or_else(|| LemmyError::from_message("invalid_search_date"))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::search::SearchQuery;
+  use chrono::NaiveDate;
+
+  #[test]
+  fn test_parse_operators() {
+    let query = SearchQuery::parse(
+      "rust author:alice@example.com community:rust instance:Example.org nsfw:no \
+       before:2023-06-01 after:2023-01-01 async",
+    )
+    .unwrap();
+    assert_eq!(
+      SearchQuery {
+        text: "rust async".to_string(),
+        author: Some("alice@example.com".to_string()),
+        community: Some("rust".to_string()),
+        instance: Some("example.org".to_string()),
+        url: None,
+        nsfw: Some(false),
+        before: NaiveDate::from_ymd_opt(2023, 6, 1).and_then(|d| d.and_hms_opt(0, 0, 0)),
+        after: NaiveDate::from_ymd_opt(2023, 1, 1).and_then(|d| d.and_hms_opt(0, 0, 0)),
+      },
+      query
+    );
+  }
+
+  #[test]
+  fn test_parse_keeps_text() {
+    let query = SearchQuery::parse("\"note: this\" https://example.com  foo:bar").unwrap();
+    assert_eq!("\"note: this\" https://example.com foo:bar", query.text);
+    assert_eq!(None, query.url);
+
+    let query = SearchQuery::parse("url:https://example.com/a?b=c").unwrap();
+    assert_eq!(Some("https://example.com/a?b=c".to_string()), query.url);
+    assert!(query.text.is_empty());
+  }
+
+  #[test]
+  fn test_parse_invalid() {
+    for q in [
+      "author:",
+      "nsfw:maybe",
+      "before:yesterday",
+      "after:2023-13-01",
+    ] {
+      let error = SearchQuery::parse(q).unwrap_err();
+      assert!(error.message.is_some(), "{q}");
+    }
+    assert_eq!(
+      Some("invalid_search_date".to_string()),
+      SearchQuery::parse("before:2023-02-30").unwrap_err().message
+    );
+  }
+}
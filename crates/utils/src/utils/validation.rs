@@ -1,4 +1,7 @@
-use crate::error::{LemmyError, LemmyResult};
+use crate::{
+  error::{LemmyError, LemmyResult},
+  utils::schedule::replace_date_placeholders,
+};
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use regex::{Regex, RegexBuilder};
@@ -12,6 +15,11 @@ static VALID_POST_TITLE_REGEX: Lazy<Regex> =
 static VALID_MATRIX_ID_REGEX: Lazy<Regex> = Lazy::new(|| {
   Regex::new(r"^@[A-Za-z0-9._=-]+:[A-Za-z0-9.-]+\.[A-Za-z]{2,}$").expect("compile regex")
 });
+// Type and subtype names as restricted by RFC 6838, which also limits both to 127 characters
+static VALID_CONTENT_TYPE_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"^[a-z0-9][a-z0-9!#$&^_.+-]{0,126}/[a-z0-9][a-z0-9!#$&^_.+-]{0,126}$")
+    .expect("compile regex")
+});
 // taken from https://en.wikipedia.org/wiki/UTM_parameters
 static CLEAN_URL_PARAMS_REGEX: Lazy<Regex> = Lazy::new(|| {
   Regex::new(r"^utm_source|utm_medium|utm_campaign|utm_term|utm_content|gclid|gclsrc|dclid|fbclid$")
@@ -24,6 +32,14 @@ const BIO_MAX_LENGTH: usize = 300;
 const SITE_NAME_MAX_LENGTH: usize = 20;
 const SITE_NAME_MIN_LENGTH: usize = 1;
 const SITE_DESCRIPTION_MAX_LENGTH: usize = 150;
+const KEYWORD_FILTER_MAX_LENGTH: usize = 100;
+pub const POST_TITLE_MAX_LENGTH: usize = 200;
+const POST_TAG_NAME_MAX_LENGTH: usize = 50;
+const POLL_OPTION_MAX_LENGTH: usize = 200;
+const POLL_MAX_OPTIONS: usize = 20;
+const ENCRYPTION_PUBLIC_KEY_MAX_LENGTH: usize = 2000;
+const ENCRYPTED_CONTENT_MAX_LENGTH: usize = 100000;
+const MOD_NOTE_MAX_LENGTH: usize = 1000;
 
 fn has_newline(name: &str) -> bool {
   name.contains('\n')
@@ -115,6 +131,96 @@ pub fn site_description_length_check(description: &str) -> LemmyResult<()> {
   )
 }
 
+/// Checks the keyword filter length, the limit as defined in the DB.
+pub fn keyword_filter_length_check(keyword: &str) -> LemmyResult<()> {
+  min_max_length_check(
+    keyword,
+    1,
+    KEYWORD_FILTER_MAX_LENGTH,
+    String::from("keyword_filter_required"),
+    String::from("keyword_filter_length_overflow"),
+  )
+}
+
+/// Checks the community post tag name length, the limit as defined in the DB.
+pub fn post_tag_name_length_check(name: &str) -> LemmyResult<()> {
+  min_max_length_check(
+    name,
+    1,
+    POST_TAG_NAME_MAX_LENGTH,
+    String::from("post_tag_name_required"),
+    String::from("post_tag_name_length_overflow"),
+  )
+}
+
+/// Checks the title template of a recurring post, both as written and with the date placeholders
+/// filled in.
+pub fn is_valid_recurring_post_title(template: &str) -> LemmyResult<()> {
+  let title = replace_date_placeholders(template, chrono::Utc::now().naive_utc());
+  is_valid_post_title(&title)?;
+  for item in [template, title.as_str()] {
+    max_length_check(
+      item,
+      POST_TITLE_MAX_LENGTH,
+      String::from("post_title_length_overflow"),
+    )?;
+  }
+  Ok(())
+}
+
+/// Checks the number of poll options, and the length of each one.
+pub fn is_valid_poll_options(options: &[String]) -> LemmyResult<()> {
+  if options.len() < 2 {
+    Err(LemmyError::from_message("poll_needs_two_options"))
+  } else if options.len() > POLL_MAX_OPTIONS {
+    Err(LemmyError::from_message("poll_too_many_options"))
+  } else {
+    options.iter().try_for_each(|option| {
+      min_max_length_check(
+        option.trim(),
+        1,
+        POLL_OPTION_MAX_LENGTH,
+        String::from("poll_option_required"),
+        String::from("poll_option_length_overflow"),
+      )
+    })
+  }
+}
+
+/// Checks the public key for encrypted private messages. The key format is up to the clients, so
+/// only its length is limited.
+pub fn encryption_public_key_length_check(key: &str) -> LemmyResult<()> {
+  min_max_length_check(
+    key,
+    1,
+    ENCRYPTION_PUBLIC_KEY_MAX_LENGTH,
+    String::from("encryption_public_key_required"),
+    String::from("encryption_public_key_length_overflow"),
+  )
+}
+
+/// Checks the length of an encrypted private message, which the server can't look into.
+pub fn encrypted_content_length_check(content: &str) -> LemmyResult<()> {
+  min_max_length_check(
+    content,
+    1,
+    ENCRYPTED_CONTENT_MAX_LENGTH,
+    String::from("encrypted_content_required"),
+    String::from("encrypted_content_length_overflow"),
+  )
+}
+
+/// Checks the text of a moderator note about a user.
+pub fn mod_note_length_check(content: &str) -> LemmyResult<()> {
+  min_max_length_check(
+    content,
+    1,
+    MOD_NOTE_MAX_LENGTH,
+    String::from("mod_note_required"),
+    String::from("mod_note_length_overflow"),
+  )
+}
+
 fn max_length_check(item: &str, max_length: usize, msg: String) -> LemmyResult<()> {
   if item.len() > max_length {
     Err(LemmyError::from_message(&msg))
@@ -169,6 +275,24 @@ pub fn build_and_check_regex(regex_str_opt: &Option<&str>) -> LemmyResult<Option
   )
 }
 
+/// Checks that a lowercase MIME type like `image/png` is well-formed, without any parameters.
+pub fn is_valid_content_type(content_type: &str) -> LemmyResult<()> {
+  if VALID_CONTENT_TYPE_REGEX.is_match(content_type) {
+    Ok(())
+  } else {
+    Err(LemmyError::from_message("invalid_content_type"))
+  }
+}
+
+/// Avatars and banners need to be web links, so that other users can load them.
+pub fn is_valid_image_url(url: &Url) -> LemmyResult<()> {
+  if url.scheme() == "http" || url.scheme() == "https" {
+    Ok(())
+  } else {
+    Err(LemmyError::from_message("invalid_url_scheme"))
+  }
+}
+
 pub fn clean_url_params(url: &Url) -> Url {
   let mut url_out = url.clone();
   if url.query().is_some() {
@@ -253,15 +377,26 @@ mod tests {
     build_and_check_regex,
     check_site_visibility_valid,
     clean_url_params,
+    encrypted_content_length_check,
+    encryption_public_key_length_check,
     generate_totp_2fa_secret,
     is_valid_actor_name,
     is_valid_bio_field,
+    is_valid_content_type,
     is_valid_display_name,
+    is_valid_image_url,
     is_valid_matrix_id,
+    is_valid_poll_options,
     is_valid_post_title,
+    is_valid_recurring_post_title,
     site_description_length_check,
     site_name_length_check,
     BIO_MAX_LENGTH,
+    ENCRYPTED_CONTENT_MAX_LENGTH,
+    ENCRYPTION_PUBLIC_KEY_MAX_LENGTH,
+    POLL_MAX_OPTIONS,
+    POLL_OPTION_MAX_LENGTH,
+    POST_TITLE_MAX_LENGTH,
     SITE_DESCRIPTION_MAX_LENGTH,
     SITE_NAME_MAX_LENGTH,
   };
@@ -279,6 +414,26 @@ mod tests {
     assert_eq!(url.to_string(), cleaned.to_string());
   }
 
+  #[test]
+  fn test_valid_content_type() {
+    assert!(is_valid_content_type("image/png").is_ok());
+    assert!(is_valid_content_type("application/vnd.api+json").is_ok());
+    assert!(is_valid_content_type("image").is_err());
+    assert!(is_valid_content_type("text/html; charset=utf-8").is_err());
+    assert!(is_valid_content_type("<script>/x").is_err());
+    assert!(is_valid_content_type(&format!("image/{}", "a".repeat(200))).is_err());
+  }
+
+  #[test]
+  fn test_valid_image_url() {
+    let url = Url::parse("https://example.com/pictrs/image/abc.png").unwrap();
+    assert!(is_valid_image_url(&url).is_ok());
+    let url = Url::parse("javascript:alert(1)").unwrap();
+    assert!(is_valid_image_url(&url).is_err());
+    let url = Url::parse("file:///etc/passwd").unwrap();
+    assert!(is_valid_image_url(&url).is_err());
+  }
+
   #[test]
   fn regex_checks() {
     assert!(is_valid_post_title("hi").is_err());
@@ -317,6 +472,15 @@ mod tests {
     assert!(is_valid_post_title("\n \n \n \n    		").is_err()); // tabs/spaces/newlines
   }
 
+  #[test]
+  fn test_valid_recurring_post_title() {
+    assert!(is_valid_recurring_post_title("Megathread {date}").is_ok());
+    // The week number is shorter than its placeholder
+    assert!(is_valid_recurring_post_title("{week}").is_err());
+    let too_long = (0..POST_TITLE_MAX_LENGTH).map(|_| 'A').collect::<String>() + "{day}";
+    assert!(is_valid_recurring_post_title(&too_long).is_err());
+  }
+
   #[test]
   fn test_valid_matrix_id() {
     assert!(is_valid_matrix_id("@dess:matrix.org").is_ok());
@@ -415,6 +579,64 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_valid_encryption_fields() {
+    assert!(encryption_public_key_length_check("MCowBQYDK2VuAyEA").is_ok());
+    assert!(encryption_public_key_length_check("").is_err());
+    assert!(encryption_public_key_length_check(
+      &(0..ENCRYPTION_PUBLIC_KEY_MAX_LENGTH + 1)
+        .map(|_| 'A')
+        .collect::<String>()
+    )
+    .is_err());
+
+    assert!(encrypted_content_length_check(
+      &(0..ENCRYPTED_CONTENT_MAX_LENGTH)
+        .map(|_| 'A')
+        .collect::<String>()
+    )
+    .is_ok());
+    let invalid_result = encrypted_content_length_check(
+      &(0..ENCRYPTED_CONTENT_MAX_LENGTH + 1)
+        .map(|_| 'A')
+        .collect::<String>(),
+    );
+    assert_eq!(
+      Some(String::from("encrypted_content_length_overflow")),
+      invalid_result.unwrap_err().message
+    );
+  }
+
+  #[test]
+  fn test_valid_poll_options() {
+    let options = |n: usize, len: usize| -> Vec<String> {
+      (0..n)
+        .map(|_| (0..len).map(|_| 'A').collect::<String>())
+        .collect()
+    };
+    assert!(is_valid_poll_options(&options(2, POLL_OPTION_MAX_LENGTH)).is_ok());
+    assert!(is_valid_poll_options(&options(POLL_MAX_OPTIONS, 1)).is_ok());
+
+    let invalid = [
+      (options(1, 1), "poll_needs_two_options"),
+      (options(POLL_MAX_OPTIONS + 1, 1), "poll_too_many_options"),
+      (
+        vec!["Yes".to_string(), " ".to_string()],
+        "poll_option_required",
+      ),
+      (
+        options(2, POLL_OPTION_MAX_LENGTH + 1),
+        "poll_option_length_overflow",
+      ),
+    ];
+    for (invalid_options, expected_err) in invalid {
+      assert_eq!(
+        Some(expected_err.to_string()),
+        is_valid_poll_options(&invalid_options).unwrap_err().message
+      );
+    }
+  }
+
   #[test]
   fn test_valid_slur_regex() {
     let valid_regexes = [&None, &Some(""), &Some("(foo|bar)")];
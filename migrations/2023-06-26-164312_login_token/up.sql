@@ -0,0 +1,13 @@
+-- Every issued jwt is stored here, so that sessions can be listed and invalidated individually.
+-- Tokens issued before this migration are no longer accepted, so all users need to log in again.
+create table login_token (
+  id serial primary key,
+  token_encrypted text not null unique,
+  user_id int references local_user on update cascade on delete cascade not null,
+  published timestamp without time zone default now() not null,
+  last_used timestamp without time zone default now() not null,
+  ip text,
+  user_agent text
+);
+
+create index idx_login_token_user on login_token (user_id);
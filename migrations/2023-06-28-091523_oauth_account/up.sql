@@ -0,0 +1,11 @@
+-- Links a local user to the subject of an external OpenID Connect provider.
+create table oauth_account (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  provider text not null,
+  subject text not null,
+  published timestamp without time zone default now() not null,
+  unique (provider, subject)
+);
+
+create index idx_oauth_account_local_user on oauth_account (local_user_id);
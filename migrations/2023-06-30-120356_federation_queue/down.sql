@@ -0,0 +1,3 @@
+drop table activity_send_inbox;
+
+drop table federation_queue_state;
@@ -0,0 +1,21 @@
+-- Inboxes which local activities still need to be delivered to. Rows are removed once the
+-- activity was delivered.
+create table activity_send_inbox (
+  id serial primary key,
+  activity_id int references activity on update cascade on delete cascade not null,
+  instance_id int references instance on update cascade on delete cascade not null,
+  inbox text not null
+);
+
+create index idx_activity_send_inbox_instance on activity_send_inbox (instance_id, activity_id);
+
+-- Delivery progress for each remote instance
+create table federation_queue_state (
+  id serial primary key,
+  instance_id int references instance on update cascade on delete cascade not null unique,
+  -- Number of failed attempts since the last successful delivery
+  fail_count int not null default 0,
+  last_retry timestamp without time zone,
+  -- Set after too many consecutive failures, delivery is then only attempted once a day
+  dead_since timestamp without time zone
+);
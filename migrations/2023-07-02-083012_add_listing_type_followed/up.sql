@@ -0,0 +1 @@
+alter type listing_type_enum add value 'Followed';
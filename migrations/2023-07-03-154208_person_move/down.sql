@@ -0,0 +1,2 @@
+alter table person drop column moved_to;
+alter table person drop column also_known_as;
@@ -0,0 +1,4 @@
+-- the account which this person moved to
+alter table person add column moved_to varchar(255);
+-- an account which is allowed to move to this person
+alter table person add column also_known_as varchar(255);
@@ -0,0 +1,9 @@
+drop trigger post_search_vector on post;
+drop trigger comment_search_vector on comment;
+drop function post_search_vector_update, comment_search_vector_update;
+
+alter table post drop column search_vector;
+alter table comment drop column search_vector;
+
+drop function post_search_vector, comment_search_vector;
+drop function language_ts_config;
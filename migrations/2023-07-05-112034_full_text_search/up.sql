@@ -0,0 +1,87 @@
+-- The text search configuration which matches the language of a post or comment. Languages without
+-- a configuration (including undetermined) are only split into words, without stemming.
+create function language_ts_config(language_id int)
+returns regconfig language sql stable parallel safe
+as $$
+  select coalesce((
+    select case code
+      when 'ar' then 'arabic'
+      when 'da' then 'danish'
+      when 'de' then 'german'
+      when 'el' then 'greek'
+      when 'en' then 'english'
+      when 'es' then 'spanish'
+      when 'fi' then 'finnish'
+      when 'fr' then 'french'
+      when 'ga' then 'irish'
+      when 'hu' then 'hungarian'
+      when 'id' then 'indonesian'
+      when 'it' then 'italian'
+      when 'lt' then 'lithuanian'
+      when 'nb' then 'norwegian'
+      when 'ne' then 'nepali'
+      when 'nl' then 'dutch'
+      when 'nn' then 'norwegian'
+      when 'no' then 'norwegian'
+      when 'pt' then 'portuguese'
+      when 'ro' then 'romanian'
+      when 'ru' then 'russian'
+      when 'sv' then 'swedish'
+      when 'ta' then 'tamil'
+      when 'tr' then 'turkish'
+    end
+    from language where id = language_id
+  ), 'simple')::regconfig
+$$;
+
+create function post_search_vector(name text, body text, language_id int)
+returns tsvector language sql stable parallel safe
+as $$
+  select setweight(to_tsvector(language_ts_config(language_id), name), 'A')
+    || setweight(to_tsvector(language_ts_config(language_id), coalesce(body, '')), 'B')
+$$;
+
+create function comment_search_vector(content text, language_id int)
+returns tsvector language sql stable parallel safe
+as $$
+  select to_tsvector(language_ts_config(language_id), content)
+$$;
+
+-- The search vectors can't be generated columns, as those can't look up the language code.
+alter table post add column search_vector tsvector;
+alter table comment add column search_vector tsvector;
+
+update post set search_vector = post_search_vector(name, body, language_id);
+update comment set search_vector = comment_search_vector(content, language_id);
+
+alter table post alter column search_vector set not null;
+alter table comment alter column search_vector set not null;
+
+create function post_search_vector_update()
+returns trigger language plpgsql
+as $$
+begin
+  NEW.search_vector := post_search_vector(NEW.name, NEW.body, NEW.language_id);
+  return NEW;
+end $$;
+
+create function comment_search_vector_update()
+returns trigger language plpgsql
+as $$
+begin
+  NEW.search_vector := comment_search_vector(NEW.content, NEW.language_id);
+  return NEW;
+end $$;
+
+create trigger post_search_vector
+before insert or update of name, body, language_id on post
+for each row
+execute function post_search_vector_update();
+
+create trigger comment_search_vector
+before insert or update of content, language_id on comment
+for each row
+execute function comment_search_vector_update();
+
+create index idx_post_search_vector on post using gin (search_vector);
+create index idx_comment_search_vector on comment using gin (search_vector);
@@ -0,0 +1,2 @@
+drop table local_user_keyword_filter;
+drop type keyword_filter_action_enum;
@@ -0,0 +1,11 @@
+create type keyword_filter_action_enum as enum ('Hide', 'Collapse');
+
+create table local_user_keyword_filter (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  keyword varchar(100) not null,
+  action keyword_filter_action_enum not null default 'Hide',
+  published timestamp not null default now(),
+  updated timestamp,
+  unique (local_user_id, keyword)
+);
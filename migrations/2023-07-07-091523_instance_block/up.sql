@@ -0,0 +1,7 @@
+create table instance_block (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  instance_id int references instance on update cascade on delete cascade not null,
+  published timestamp not null default now(),
+  unique (local_user_id, instance_id)
+);
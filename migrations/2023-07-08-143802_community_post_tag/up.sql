@@ -0,0 +1,17 @@
+create table community_post_tag (
+  id serial primary key,
+  ap_id varchar(255) not null unique default generate_unique_changeme(),
+  community_id int references community on update cascade on delete cascade not null,
+  name varchar(50) not null,
+  published timestamp not null default now(),
+  updated timestamp,
+  unique (community_id, name)
+);
+
+create table post_tag (
+  post_id int references post on update cascade on delete cascade not null,
+  tag_id int references community_post_tag on update cascade on delete cascade not null,
+  primary key (post_id, tag_id)
+);
+
+create index idx_post_tag_tag on post_tag (tag_id);
@@ -0,0 +1,27 @@
+create table poll (
+  post_id int primary key references post on update cascade on delete cascade,
+  multiple_choice boolean not null default false,
+  end_time timestamp,
+  published timestamp not null default now(),
+  updated timestamp
+);
+
+create table poll_option (
+  id serial primary key,
+  post_id int references poll on update cascade on delete cascade not null,
+  name varchar(200) not null,
+  -- Votes which were counted by the instance of a remote poll
+  remote_vote_count int not null default 0
+);
+
+create index idx_poll_option_post on poll_option (post_id);
+
+create table poll_vote (
+  poll_option_id int references poll_option on update cascade on delete cascade not null,
+  person_id int references person on update cascade on delete cascade not null,
+  post_id int references poll on update cascade on delete cascade not null,
+  published timestamp not null default now(),
+  primary key (poll_option_id, person_id)
+);
+
+create index idx_poll_vote_post_person on poll_vote (post_id, person_id);
@@ -0,0 +1 @@
+alter table post drop column scheduled_publish_time;
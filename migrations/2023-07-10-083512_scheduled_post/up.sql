@@ -0,0 +1,4 @@
+-- Posts with a publish time in the future are hidden until a scheduled task publishes them
+alter table post add column scheduled_publish_time timestamp;
+
+create index idx_post_scheduled_publish_time on post (scheduled_publish_time) where scheduled_publish_time is not null;
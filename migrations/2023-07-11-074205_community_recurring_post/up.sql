@@ -0,0 +1,18 @@
+-- Posts which are created by a scheduled task according to a cron-like schedule, like weekly
+-- megathreads
+create table community_recurring_post (
+  id serial primary key,
+  community_id int references community on update cascade on delete cascade not null,
+  creator_id int references person on update cascade on delete cascade not null,
+  name varchar(200) not null,
+  body text,
+  schedule varchar(100) not null,
+  featured boolean default false not null,
+  last_post_id int references post on update cascade on delete set null,
+  next_publish_time timestamp,
+  published timestamp not null default now(),
+  updated timestamp
+);
+
+create index idx_community_recurring_post_community on community_recurring_post (community_id);
+create index idx_community_recurring_post_next_publish_time on community_recurring_post (next_publish_time);
@@ -0,0 +1,2 @@
+drop table post_edit;
+drop table comment_edit;
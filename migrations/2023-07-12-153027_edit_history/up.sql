@@ -0,0 +1,19 @@
+-- Previous versions of edited posts and comments, so that moderators can see what was changed
+create table post_edit (
+  id serial primary key,
+  post_id int references post on update cascade on delete cascade not null,
+  name varchar(200) not null,
+  url varchar(512),
+  body text,
+  published timestamp not null
+);
+
+create table comment_edit (
+  id serial primary key,
+  comment_id int references comment on update cascade on delete cascade not null,
+  content text not null,
+  published timestamp not null
+);
+
+create index idx_post_edit_post on post_edit (post_id);
+create index idx_comment_edit_comment on comment_edit (comment_id);
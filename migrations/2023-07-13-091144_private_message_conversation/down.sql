@@ -0,0 +1,3 @@
+alter table private_message drop column conversation_id;
+drop table conversation_participant;
+drop table conversation;
@@ -0,0 +1,68 @@
+-- Private messages are grouped into conversations between a fixed set of people
+create table conversation (
+  id serial primary key,
+  published timestamp not null default now(),
+  -- The time of the latest message, used for sorting
+  updated timestamp
+);
+
+create table conversation_participant (
+  conversation_id int references conversation on update cascade on delete cascade not null,
+  person_id int references person on update cascade on delete cascade not null,
+  -- Messages published after this time are unread for the participant
+  last_read timestamp,
+  primary key (conversation_id, person_id)
+);
+
+create index idx_conversation_participant_person on conversation_participant (person_id);
+
+alter table private_message add column conversation_id int references conversation on update cascade on delete cascade;
+
+-- Create a conversation for every pair of people who have exchanged messages
+create temporary table pair_conversation as
+select
+  person_1,
+  person_2,
+  nextval('conversation_id_seq')::int as conversation_id,
+  published,
+  updated
+from (
+  select
+    least(creator_id, recipient_id) as person_1,
+    greatest(creator_id, recipient_id) as person_2,
+    min(published) as published,
+    max(published) as updated
+  from private_message
+  group by 1, 2
+) as pairs;
+
+insert into conversation (id, published, updated)
+select conversation_id, published, updated from pair_conversation;
+
+-- Everything is read up to the oldest unread message the participant received
+insert into conversation_participant (conversation_id, person_id, last_read)
+select
+  c.conversation_id,
+  p.person_id,
+  coalesce(
+    (select min(pm.published) - interval '1 microsecond'
+      from private_message pm
+      where pm.recipient_id = p.person_id
+        and pm.creator_id = p.other_id
+        and not pm.read),
+    now()
+  )
+from pair_conversation c,
+  lateral (values (c.person_1, c.person_2), (c.person_2, c.person_1)) as p(person_id, other_id)
+-- Messages to oneself only have a single participant
+on conflict do nothing;
+
+update private_message pm
+set conversation_id = c.conversation_id
+from pair_conversation c
+where least(pm.creator_id, pm.recipient_id) = c.person_1
+  and greatest(pm.creator_id, pm.recipient_id) = c.person_2;
+
+alter table private_message alter column conversation_id set not null;
+
+create index idx_private_message_conversation on private_message (conversation_id, published desc);
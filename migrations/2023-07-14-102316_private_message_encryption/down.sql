@@ -0,0 +1,2 @@
+alter table person drop column encryption_public_key;
+alter table private_message drop column encrypted_content;
@@ -0,0 +1,7 @@
+-- Public key for end-to-end encrypted private messages. It is generated by the client, which
+-- keeps the private key to itself.
+alter table person add column encryption_public_key text;
+
+-- The encrypted message, which is stored and federated as is. The server can't read it, so
+-- `content` only holds a plaintext notice for clients without encryption support.
+alter table private_message add column encrypted_content text;
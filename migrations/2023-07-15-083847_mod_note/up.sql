@@ -0,0 +1,12 @@
+-- Notes which moderators and admins keep about people, like the reason for a warning
+create table mod_note (
+  id serial primary key,
+  target_person_id int references person on update cascade on delete cascade not null,
+  -- Notes without a community are site-wide, and only visible to admins
+  community_id int references community on update cascade on delete cascade,
+  author_id int references person on update cascade on delete cascade not null,
+  content text not null,
+  published timestamp not null default now()
+);
+
+create index idx_mod_note_target_person on mod_note (target_person_id, community_id);
@@ -0,0 +1,11 @@
+-- Images uploaded to pict-rs through lemmy, so that they can be listed and deleted along with
+-- their uploader.
+create table local_image (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  pictrs_alias text not null unique,
+  pictrs_delete_token text not null,
+  published timestamp without time zone default now() not null
+);
+
+create index idx_local_image_local_user on local_image (local_user_id);
@@ -0,0 +1,9 @@
+-- Remote images which were downloaded to pict-rs by the image proxy, when it is configured to keep
+-- them.
+create table remote_image (
+  id serial primary key,
+  link text not null unique,
+  pictrs_alias text not null,
+  pictrs_delete_token text not null,
+  published timestamp without time zone default now() not null
+);
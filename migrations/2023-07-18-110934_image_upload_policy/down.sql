@@ -0,0 +1,6 @@
+alter table local_site drop column image_upload_max_bytes;
+alter table local_site drop column image_upload_max_dimension;
+alter table local_site drop column image_upload_allowed_mime_types;
+alter table local_site drop column image_upload_classifier_url;
+
+alter table local_image drop column flag_reason;
@@ -0,0 +1,11 @@
+-- Limits for image uploads, which are checked before an upload is accepted. Null means no limit
+-- besides those of pict-rs.
+alter table local_site add column image_upload_max_bytes int;
+alter table local_site add column image_upload_max_dimension int;
+-- Comma separated, like 'image/png,image/jpeg'
+alter table local_site add column image_upload_allowed_mime_types text;
+-- An external service which is asked to accept, reject or flag every uploaded image
+alter table local_site add column image_upload_classifier_url text;
+
+-- Why the classifier flagged the image, if it did
+alter table local_image add column flag_reason text;
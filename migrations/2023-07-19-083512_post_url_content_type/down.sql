@@ -0,0 +1 @@
+alter table post drop column url_content_type;
@@ -0,0 +1,2 @@
+-- The MIME type of the post url, so that clients can show videos and audio directly
+alter table post add column url_content_type text;
@@ -0,0 +1,5 @@
+delete from remote_image where pictrs_alias is null or pictrs_delete_token is null;
+
+alter table remote_image alter column pictrs_alias set not null;
+
+alter table remote_image alter column pictrs_delete_token set not null;
@@ -0,0 +1,17 @@
+-- The image proxy only serves images whose links were received from other instances, so the links
+-- are stored before a copy of the image exists.
+alter table remote_image alter column pictrs_alias drop not null;
+
+alter table remote_image alter column pictrs_delete_token drop not null;
+
+insert into remote_image (link)
+select avatar from person where not local and avatar like 'http%'
+union
+select banner from person where not local and banner like 'http%'
+union
+select icon from community where not local and icon like 'http%'
+union
+select banner from community where not local and banner like 'http%'
+union
+select thumbnail_url from post where not local and thumbnail_url like 'http%'
+on conflict (link) do nothing;
@@ -1,5 +1,6 @@
+use crate::prometheus_metrics::record_request_metrics;
 use actix_web::{guard, web, Error, HttpResponse, Result};
-use lemmy_api::Perform;
+use lemmy_api::{local_user::login::login, Perform};
 use lemmy_api_common::{
   comment::{
     CreateComment,
@@ -9,6 +10,7 @@ use lemmy_api_common::{
     DistinguishComment,
     EditComment,
     GetComment,
+    GetCommentHistory,
     GetComments,
     ListCommentReports,
     RemoveComment,
@@ -20,12 +22,19 @@ use lemmy_api_common::{
     BanFromCommunity,
     BlockCommunity,
     CreateCommunity,
+    CreateCommunityPostTag,
+    CreateCommunityRecurringPost,
     DeleteCommunity,
+    DeleteCommunityPostTag,
+    DeleteCommunityRecurringPost,
     EditCommunity,
+    EditCommunityPostTag,
+    EditCommunityRecurringPost,
     FollowCommunity,
     GetCommunity,
     HideCommunity,
     ListCommunities,
+    ListCommunityRecurringPosts,
     RemoveCommunity,
     TransferCommunity,
   },
@@ -36,7 +45,14 @@ use lemmy_api_common::{
     BanPerson,
     BlockPerson,
     ChangePassword,
+    CreateKeywordFilter,
+    CreateModNote,
     DeleteAccount,
+    DeleteKeywordFilter,
+    DeleteModNote,
+    EditKeywordFilter,
+    ExportSettings,
+    FollowPerson,
     GetBannedPersons,
     GetCaptcha,
     GetPersonDetails,
@@ -44,10 +60,16 @@ use lemmy_api_common::{
     GetReplies,
     GetReportCount,
     GetUnreadCount,
-    Login,
+    ImportSettings,
+    ListKeywordFilters,
+    ListLogins,
+    ListModNotes,
+    ListMyImages,
+    Logout,
     MarkAllAsRead,
     MarkCommentReplyAsRead,
     MarkPersonMentionAsRead,
+    MoveAccount,
     PasswordChangeAfterReset,
     PasswordReset,
     Register,
@@ -62,6 +84,7 @@ use lemmy_api_common::{
     EditPost,
     FeaturePost,
     GetPost,
+    GetPostHistory,
     GetPosts,
     GetSiteMetadata,
     ListPostReports,
@@ -70,6 +93,7 @@ use lemmy_api_common::{
     RemovePost,
     ResolvePostReport,
     SavePost,
+    VotePoll,
   },
   private_message::{
     CreatePrivateMessage,
@@ -77,12 +101,14 @@ use lemmy_api_common::{
     DeletePrivateMessage,
     EditPrivateMessage,
     GetPrivateMessages,
+    ListConversations,
     ListPrivateMessageReports,
     MarkPrivateMessageAsRead,
     ResolvePrivateMessageReport,
   },
   site::{
     ApproveRegistrationApplication,
+    BlockInstance,
     CreateSite,
     EditSite,
     GetFederatedInstances,
@@ -90,6 +116,7 @@ use lemmy_api_common::{
     GetSite,
     GetUnreadRegistrationApplicationCount,
     LeaveAdmin,
+    ListAllImages,
     ListRegistrationApplications,
     PurgeComment,
     PurgeCommunity,
@@ -99,19 +126,33 @@ use lemmy_api_common::{
     Search,
   },
 };
-use lemmy_api_crud::PerformCrud;
+use lemmy_api_crud::{
+  user::{
+    export_archive::export_archive,
+    oauth::{oauth_authorize, oauth_callback},
+  },
+  PerformCrud,
+};
 use lemmy_apub::{api::PerformApub, SendActivity};
+use lemmy_routes::images::image_proxy;
 use lemmy_utils::rate_limit::RateLimitCell;
 use serde::Deserialize;
 
 pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
   cfg.service(
     web::scope("/api/v3")
+      .wrap_fn(record_request_metrics)
+      .service(
+        web::resource("/image_proxy")
+          .wrap(rate_limit.message())
+          .route(web::get().to(image_proxy)),
+      )
       // Site
       .service(
         web::scope("/site")
           .wrap(rate_limit.message())
           .route("", web::get().to(route_get_crud::<GetSite>))
+          .route("/block", web::post().to(route_post::<BlockInstance>))
           // Admin Actions
           .route("", web::post().to(route_post_crud::<CreateSite>))
           .route("", web::put().to(route_post_crud::<EditSite>)),
@@ -158,7 +199,35 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           )
           .route("/transfer", web::post().to(route_post::<TransferCommunity>))
           .route("/ban_user", web::post().to(route_post::<BanFromCommunity>))
-          .route("/mod", web::post().to(route_post::<AddModToCommunity>)),
+          .route("/mod", web::post().to(route_post::<AddModToCommunity>))
+          .route(
+            "/post_tag",
+            web::post().to(route_post_crud::<CreateCommunityPostTag>),
+          )
+          .route(
+            "/post_tag",
+            web::put().to(route_post_crud::<EditCommunityPostTag>),
+          )
+          .route(
+            "/post_tag/delete",
+            web::post().to(route_post_crud::<DeleteCommunityPostTag>),
+          )
+          .route(
+            "/recurring_post/list",
+            web::get().to(route_get_crud::<ListCommunityRecurringPosts>),
+          )
+          .route(
+            "/recurring_post",
+            web::post().to(route_post_crud::<CreateCommunityRecurringPost>),
+          )
+          .route(
+            "/recurring_post",
+            web::put().to(route_post_crud::<EditCommunityRecurringPost>),
+          )
+          .route(
+            "/recurring_post/delete",
+            web::post().to(route_post_crud::<DeleteCommunityRecurringPost>),
+          ),
       )
       .service(
         web::scope("/federated_instances")
@@ -178,6 +247,7 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.message())
           .route("", web::get().to(route_get_crud::<GetPost>))
           .route("", web::put().to(route_post_crud::<EditPost>))
+          .route("/history", web::get().to(route_get::<GetPostHistory>))
           .route("/delete", web::post().to(route_post_crud::<DeletePost>))
           .route("/remove", web::post().to(route_post_crud::<RemovePost>))
           .route(
@@ -189,6 +259,7 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/list", web::get().to(route_get_apub::<GetPosts>))
           .route("/like", web::post().to(route_post::<CreatePostLike>))
           .route("/save", web::put().to(route_post::<SavePost>))
+          .route("/poll_vote", web::post().to(route_post::<VotePoll>))
           .route("/report", web::post().to(route_post::<CreatePostReport>))
           .route(
             "/report/resolve",
@@ -213,6 +284,7 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.message())
           .route("", web::get().to(route_get_crud::<GetComment>))
           .route("", web::put().to(route_post_crud::<EditComment>))
+          .route("/history", web::get().to(route_get::<GetCommentHistory>))
           .route("/delete", web::post().to(route_post_crud::<DeleteComment>))
           .route("/remove", web::post().to(route_post_crud::<RemoveComment>))
           .route(
@@ -241,6 +313,10 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
         web::scope("/private_message")
           .wrap(rate_limit.message())
           .route("/list", web::get().to(route_get_crud::<GetPrivateMessages>))
+          .route(
+            "/conversation/list",
+            web::get().to(route_get::<ListConversations>),
+          )
           .route("", web::post().to(route_post_crud::<CreatePrivateMessage>))
           .route("", web::put().to(route_post_crud::<EditPrivateMessage>))
           .route(
@@ -279,6 +355,25 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.post())
           .route(web::get().to(route_get::<GetCaptcha>)),
       )
+      // Login via OpenID Connect providers
+      .service(
+        web::resource("/oauth/{provider}/authorize")
+          .wrap(rate_limit.message())
+          .route(web::get().to(oauth_authorize)),
+      )
+      .service(
+        // Uses the register rate limit, as this may create a new account
+        web::resource("/oauth/{provider}/callback")
+          .wrap(rate_limit.register())
+          .route(web::get().to(oauth_callback)),
+      )
+      .service(
+        // Imports fetch many objects from other instances, so they use the strict register limit
+        web::resource("/user/import_settings")
+          .guard(guard::Post())
+          .wrap(rate_limit.register())
+          .route(web::post().to(route_post_apub::<ImportSettings>)),
+      )
       // User actions
       .service(
         web::scope("/user")
@@ -294,8 +389,12 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/ban", web::post().to(route_post::<BanPerson>))
           .route("/banned", web::get().to(route_get::<GetBannedPersons>))
           .route("/block", web::post().to(route_post::<BlockPerson>))
+          .route("/follow", web::post().to(route_post::<FollowPerson>))
           // Account actions. I don't like that they're in /user maybe /accounts
-          .route("/login", web::post().to(route_post::<Login>))
+          .route("/login", web::post().to(login))
+          .route("/logout", web::post().to(route_post::<Logout>))
+          .route("/list_logins", web::get().to(route_get::<ListLogins>))
+          .route("/list_images", web::get().to(route_get::<ListMyImages>))
           .route(
             "/delete_account",
             web::post().to(route_post_crud::<DeleteAccount>),
@@ -321,6 +420,40 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
             "/change_password",
             web::put().to(route_post::<ChangePassword>),
           )
+          .route(
+            "/export_settings",
+            web::get().to(route_get_apub::<ExportSettings>),
+          )
+          .route("/export_archive", web::get().to(export_archive))
+          .route("/move", web::post().to(route_post_apub::<MoveAccount>))
+          .route(
+            "/keyword_filter",
+            web::get().to(route_get_crud::<ListKeywordFilters>),
+          )
+          .route(
+            "/keyword_filter",
+            web::post().to(route_post_crud::<CreateKeywordFilter>),
+          )
+          .route(
+            "/keyword_filter",
+            web::put().to(route_post_crud::<EditKeywordFilter>),
+          )
+          .route(
+            "/keyword_filter/delete",
+            web::post().to(route_post_crud::<DeleteKeywordFilter>),
+          )
+          .route(
+            "/mod_note",
+            web::post().to(route_post_crud::<CreateModNote>),
+          )
+          .route(
+            "/mod_note/list",
+            web::get().to(route_get_crud::<ListModNotes>),
+          )
+          .route(
+            "/mod_note/delete",
+            web::post().to(route_post_crud::<DeleteModNote>),
+          )
           .route("/report_count", web::get().to(route_get::<GetReportCount>))
           .route("/unread_count", web::get().to(route_get::<GetUnreadCount>))
           .route("/verify_email", web::post().to(route_post::<VerifyEmail>))
@@ -331,6 +464,7 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
         web::scope("/admin")
           .wrap(rate_limit.message())
           .route("/add", web::post().to(route_post::<AddAdmin>))
+          .route("/list_images", web::get().to(route_get::<ListAllImages>))
           .route(
             "/registration_application/count",
             web::get().to(route_get::<GetUnreadRegistrationApplicationCount>),
@@ -415,6 +549,23 @@ where
   Ok(HttpResponse::Ok().json(res))
 }
 
+async fn route_post_apub<'a, Data>(
+  data: web::Json<Data>,
+  context: activitypub_federation::config::Data<LemmyContext>,
+) -> Result<HttpResponse, Error>
+where
+  Data: PerformApub
+    + SendActivity<Response = <Data as PerformApub>::Response>
+    + Clone
+    + Deserialize<'a>
+    + Send
+    + 'static,
+{
+  let res = data.perform(&context).await?;
+  SendActivity::send_activity(&data.0, &res, &context).await?;
+  Ok(HttpResponse::Ok().json(res))
+}
+
 async fn route_post<'a, Data>(
   data: web::Json<Data>,
   context: web::Data<LemmyContext>,
@@ -1,11 +1,16 @@
 pub mod api_routes_http;
 pub mod code_migrations;
+pub mod prometheus_metrics;
 pub mod root_span_builder;
 pub mod scheduled_tasks;
 #[cfg(feature = "console")]
 pub mod telemetry;
 
-use crate::{code_migrations::run_advanced_migrations, root_span_builder::QuieterRootSpanBuilder};
+use crate::{
+  code_migrations::run_advanced_migrations,
+  prometheus_metrics::serve_prometheus,
+  root_span_builder::QuieterRootSpanBuilder,
+};
 use activitypub_federation::config::{FederationConfig, FederationMiddleware};
 use actix_cors::Cors;
 use actix_web::{middleware, web::Data, App, HttpServer, Result};
@@ -18,7 +23,7 @@ use lemmy_api_common::{
     local_site_rate_limit_to_rate_limit_config,
   },
 };
-use lemmy_apub::{VerifyUrlData, FEDERATION_HTTP_FETCH_LIMIT};
+use lemmy_apub::{send_queue::run_send_queue, VerifyUrlData, FEDERATION_HTTP_FETCH_LIMIT};
 use lemmy_db_schema::{
   source::secret::Secret,
   utils::{build_db_pool, get_database_url, run_migrations},
@@ -109,14 +114,12 @@ pub async fn start_lemmy_server() -> Result<(), LemmyError> {
   );
 
   if scheduled_tasks_enabled {
-    // Schedules various cleanup tasks for the DB
-    thread::spawn({
-      let context = context.clone();
-      move || {
-        scheduled_tasks::setup(db_url, user_agent, context)
-          .expect("Couldn't set up scheduled_tasks");
-      }
-    });
+    // Delivers outgoing activities. Like the scheduled tasks, this must only run in one process.
+    actix_web::rt::spawn(run_send_queue(context.clone()));
+  }
+
+  if let Some(prometheus) = &settings.prometheus {
+    serve_prometheus(prometheus, pool.clone())?;
   }
 
   let settings_bind = settings.clone();
@@ -126,14 +129,24 @@ pub async fn start_lemmy_server() -> Result<(), LemmyError> {
     .app_data(context.clone())
     .client(client.clone())
     .http_fetch_limit(FEDERATION_HTTP_FETCH_LIMIT)
-    .worker_count(settings.worker_count)
-    .retry_count(settings.retry_count)
     .debug(cfg!(debug_assertions))
     .http_signature_compat(true)
     .url_verifier(Box::new(VerifyUrlData(context.pool().clone())))
     .build()
     .await?;
 
+  if scheduled_tasks_enabled {
+    // Schedules various cleanup tasks for the DB, and publishes scheduled posts
+    thread::spawn({
+      let context = federation_config.to_request_data();
+      let runtime = tokio::runtime::Handle::current();
+      move || {
+        scheduled_tasks::setup(db_url, user_agent, context, runtime)
+          .expect("Couldn't set up scheduled_tasks");
+      }
+    });
+  }
+
   // Create Http server with websocket support
   HttpServer::new(move || {
     let cors_config = if cfg!(debug_assertions) {
@@ -0,0 +1,160 @@
+use actix_web::{
+  body::MessageBody,
+  dev::{Service, ServiceRequest, ServiceResponse},
+  web,
+  App,
+  Error,
+  HttpResponse,
+  HttpServer,
+};
+use lemmy_db_schema::utils::DbPool;
+use lemmy_utils::{
+  error::LemmyError,
+  metrics::{HTTP_REQUESTS, HTTP_REQUEST_DURATION},
+  settings::structs::PrometheusConfig,
+};
+use once_cell::sync::Lazy;
+use prometheus::{register_int_gauge, Encoder, IntGauge, TextEncoder};
+use std::{future::Future, time::Instant};
+
+static DB_POOL_MAX_SIZE: Lazy<IntGauge> = Lazy::new(|| {
+  register_int_gauge!(
+    "lemmy_db_pool_max_size",
+    "Maximum number of database connections"
+  )
+  .expect("register metric")
+});
+
+static DB_POOL_SIZE: Lazy<IntGauge> = Lazy::new(|| {
+  register_int_gauge!(
+    "lemmy_db_pool_size",
+    "Number of currently open database connections"
+  )
+  .expect("register metric")
+});
+
+static DB_POOL_AVAILABLE: Lazy<IntGauge> = Lazy::new(|| {
+  register_int_gauge!(
+    "lemmy_db_pool_available",
+    "Number of idle database connections, negative if requests are waiting for a connection"
+  )
+  .expect("register metric")
+});
+
+/// Starts a separate http server which serves the metrics at `/metrics`.
+pub fn serve_prometheus(config: &PrometheusConfig, pool: DbPool) -> Result<(), LemmyError> {
+  println!(
+    "Serving prometheus metrics at {}:{}",
+    config.bind, config.port
+  );
+  let server = HttpServer::new(move || {
+    App::new()
+      .app_data(web::Data::new(pool.clone()))
+      .route("/metrics", web::get().to(metrics))
+  })
+  .workers(1)
+  .bind((config.bind, config.port))?
+  .run();
+  actix_web::rt::spawn(server);
+  Ok(())
+}
+
+async fn metrics(pool: web::Data<DbPool>) -> Result<HttpResponse, LemmyError> {
+  // Pool usage is only read when metrics are scraped
+  let status = pool.status();
+  DB_POOL_MAX_SIZE.set(status.max_size as i64);
+  DB_POOL_SIZE.set(status.size as i64);
+  DB_POOL_AVAILABLE.set(status.available as i64);
+
+  let encoder = TextEncoder::new();
+  let mut buffer = Vec::new();
+  encoder.encode(&prometheus::gather(), &mut buffer)?;
+  Ok(
+    HttpResponse::Ok()
+      .content_type(encoder.format_type())
+      .body(buffer),
+  )
+}
+
+/// Middleware which counts http requests and their duration, labeled by the route pattern.
+pub fn record_request_metrics<S, B>(
+  req: ServiceRequest,
+  srv: &S,
+) -> impl Future<Output = Result<ServiceResponse<B>, Error>>
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+  B: MessageBody,
+{
+  let endpoint = req.match_pattern().unwrap_or_else(|| "unknown".into());
+  let method = req.method().to_string();
+  let start = Instant::now();
+  let fut = srv.call(req);
+
+  async move {
+    let res = fut.await;
+    let status = match &res {
+      Ok(res) => res.status(),
+      Err(e) => e.as_response_error().status_code(),
+    };
+    HTTP_REQUESTS
+      .with_label_values(&[&endpoint, &method, status.as_str()])
+      .inc();
+    HTTP_REQUEST_DURATION
+      .with_label_values(&[&endpoint, &method])
+      .observe(start.elapsed().as_secs_f64());
+    res
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::record_request_metrics;
+  use actix_web::{
+    test::{call_service, init_service, TestRequest},
+    web,
+    App,
+    HttpResponse,
+  };
+  use lemmy_utils::metrics::{HTTP_REQUESTS, HTTP_REQUEST_DURATION};
+
+  #[actix_web::test]
+  async fn test_request_metrics_labeled_by_route() {
+    let app = init_service(
+      App::new().service(
+        web::scope("/metrics_test")
+          .wrap_fn(record_request_metrics)
+          .route("/post/{id}", web::get().to(HttpResponse::Ok)),
+      ),
+    )
+    .await;
+    for id in [1, 2] {
+      let req = TestRequest::get()
+        .uri(&format!("/metrics_test/post/{id}"))
+        .to_request();
+      let res = call_service(&app, req).await;
+      assert!(res.status().is_success());
+    }
+
+    // Both requests share the label of the route pattern, not of the actual path
+    let pattern = "/metrics_test/post/{id}";
+    assert_eq!(
+      2,
+      HTTP_REQUESTS
+        .with_label_values(&[pattern, "GET", "200"])
+        .get()
+    );
+    assert_eq!(
+      2,
+      HTTP_REQUEST_DURATION
+        .with_label_values(&[pattern, "GET"])
+        .get_sample_count()
+    );
+    let raw_path_labels = prometheus::gather()
+      .iter()
+      .flat_map(|family| family.get_metric())
+      .flat_map(|metric| metric.get_label())
+      .filter(|label| label.get_value().starts_with("/metrics_test/post/1"))
+      .count();
+    assert_eq!(0, raw_path_labels);
+  }
+}
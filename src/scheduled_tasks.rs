@@ -1,3 +1,4 @@
+use activitypub_federation::config::Data;
 use chrono::NaiveDateTime;
 use clokwerk::{Scheduler, TimeUnits as CTimeUnits};
 use diesel::{
@@ -11,75 +12,148 @@ use diesel::{
 };
 // Import week days and WeekDay
 use diesel::{sql_query, PgConnection, RunQueryDsl};
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{
+  context::LemmyContext,
+  utils::{
+    check_community_deleted_or_removed,
+    generate_local_apub_endpoint,
+    is_mod_or_admin,
+    EndpointType,
+  },
+};
+use lemmy_apub::{
+  activities::{
+    community::collection_remove::CollectionRemove,
+    create_or_update::post::send_new_post,
+  },
+  objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
+};
 use lemmy_db_schema::{
-  schema::{activity, captcha_answer, comment, community_person_ban, instance, person, post},
-  source::instance::{Instance, InstanceForm},
+  aggregates::structs::PostAggregates,
+  schema::{
+    activity,
+    captcha_answer,
+    comment,
+    comment_edit,
+    community_person_ban,
+    instance,
+    person,
+    post,
+    post_edit,
+  },
+  source::{
+    community::Community,
+    community_recurring_post::CommunityRecurringPost,
+    instance::{Instance, InstanceForm},
+    person::Person,
+    post::{Post, PostInsertForm},
+  },
+  traits::Crud,
   utils::{naive_now, DELETED_REPLACEMENT_TEXT},
 };
 use lemmy_routes::nodeinfo::NodeInfo;
-use lemmy_utils::{error::LemmyError, REQWEST_TIMEOUT};
+use lemmy_utils::{
+  error::LemmyError,
+  metrics::SCHEDULED_TASK_DURATION,
+  utils::{
+    schedule::{replace_date_placeholders, Schedule},
+    validation::POST_TITLE_MAX_LENGTH,
+  },
+  REQWEST_TIMEOUT,
+};
 use reqwest::blocking::Client;
 use std::{thread, time::Duration};
+use tokio::runtime::Handle;
 use tracing::{error, info};
 
 /// Schedules various cleanup tasks for lemmy in a background thread
 pub fn setup(
   db_url: String,
   user_agent: String,
-  context_1: LemmyContext,
+  context: Data<LemmyContext>,
+  runtime: Handle,
 ) -> Result<(), LemmyError> {
   // Setup the connections
   let mut scheduler = Scheduler::new();
 
   startup_jobs(&db_url);
 
+  // Publish scheduled and recurring posts every minute. These send activities, so they run on
+  // the async runtime of the server.
+  let context_1 = context.clone();
+  scheduler.every(CTimeUnits::minutes(1)).run(move || {
+    run_timed("publish_scheduled_posts", || {
+      if let Err(e) = runtime.block_on(publish_scheduled_posts(&context_1)) {
+        error!("Failed to publish scheduled posts: {}", e);
+      }
+    });
+    run_timed("publish_recurring_posts", || {
+      if let Err(e) = runtime.block_on(publish_recurring_posts(&context_1)) {
+        error!("Failed to publish recurring posts: {}", e);
+      }
+    });
+  });
+
   // Update active counts every hour
   let url = db_url.clone();
   scheduler.every(CTimeUnits::hour(1)).run(move || {
-    let mut conn = PgConnection::establish(&url).expect("could not establish connection");
-    active_counts(&mut conn);
-    update_banned_when_expired(&mut conn);
+    run_timed("active_counts", || {
+      let mut conn = PgConnection::establish(&url).expect("could not establish connection");
+      active_counts(&mut conn);
+      update_banned_when_expired(&mut conn);
+    });
   });
 
   // Update hot ranks every 15 minutes
   let url = db_url.clone();
   scheduler.every(CTimeUnits::minutes(15)).run(move || {
-    let mut conn = PgConnection::establish(&url).expect("could not establish connection");
-    update_hot_ranks(&mut conn, true);
+    run_timed("update_hot_ranks", || {
+      let mut conn = PgConnection::establish(&url).expect("could not establish connection");
+      update_hot_ranks(&mut conn, true);
+    });
   });
 
   // Delete any captcha answers older than ten minutes, every ten minutes
   let url = db_url.clone();
   scheduler.every(CTimeUnits::minutes(10)).run(move || {
-    let mut conn = PgConnection::establish(&url).expect("could not establish connection");
-    delete_expired_captcha_answers(&mut conn);
+    run_timed("delete_expired_captcha_answers", || {
+      let mut conn = PgConnection::establish(&url).expect("could not establish connection");
+      delete_expired_captcha_answers(&mut conn);
+    });
   });
 
   // Clear old activities every week
   let url = db_url.clone();
   scheduler.every(CTimeUnits::weeks(1)).run(move || {
-    let mut conn = PgConnection::establish(&url).expect("could not establish connection");
-    clear_old_activities(&mut conn);
+    run_timed("clear_old_activities", || {
+      let mut conn = PgConnection::establish(&url).expect("could not establish connection");
+      clear_old_activities(&mut conn);
+    });
   });
 
   // Remove old rate limit buckets after 1 to 2 hours of inactivity
   scheduler.every(CTimeUnits::hour(1)).run(move || {
-    let hour = Duration::from_secs(3600);
-    context_1.settings_updated_channel().remove_older_than(hour);
+    run_timed("remove_old_rate_limit_buckets", || {
+      let hour = Duration::from_secs(3600);
+      context.settings_updated_channel().remove_older_than(hour);
+    });
   });
 
   // Overwrite deleted & removed posts and comments every day
   let url = db_url.clone();
   scheduler.every(CTimeUnits::days(1)).run(move || {
-    let mut conn = PgConnection::establish(&url).expect("could not establish connection");
-    overwrite_deleted_posts_and_comments(&mut conn);
+    run_timed("overwrite_deleted_posts_and_comments", || {
+      let mut conn = PgConnection::establish(&url).expect("could not establish connection");
+      overwrite_deleted_posts_and_comments(&mut conn);
+    });
   });
 
   // Update the Instance Software
   scheduler.every(CTimeUnits::days(1)).run(move || {
-    let mut conn = PgConnection::establish(&db_url).expect("could not establish connection");
-    update_instance_software(&mut conn, &user_agent);
+    run_timed("update_instance_software", || {
+      let mut conn = PgConnection::establish(&db_url).expect("could not establish connection");
+      update_instance_software(&mut conn, &user_agent);
+    });
   });
 
   // Manually run the scheduler in an event loop
@@ -89,6 +163,14 @@ pub fn setup(
   }
 }
 
+/// Runs a scheduled task, and records its duration for the metrics endpoint
+fn run_timed(task: &str, job: impl FnOnce()) {
+  let _timer = SCHEDULED_TASK_DURATION
+    .with_label_values(&[task])
+    .start_timer();
+  job();
+}
+
 /// Run these on server startup
 fn startup_jobs(db_url: &str) {
   let mut conn = PgConnection::establish(db_url).expect("could not establish connection");
@@ -241,6 +323,22 @@ fn overwrite_deleted_posts_and_comments(conn: &mut PgConnection) {
     }
   }
 
+  info!("Removing edit history of deleted posts...");
+  let deleted_posts = post::table
+    .filter(post::deleted.eq(true))
+    .filter(post::updated.lt(now.nullable() - 1.months()))
+    .select(post::id);
+  match diesel::delete(post_edit::table.filter(post_edit::post_id.eq_any(deleted_posts)))
+    .execute(conn)
+  {
+    Ok(_) => {
+      info!("Done.");
+    }
+    Err(e) => {
+      error!("Failed to remove edit history of deleted posts: {}", e)
+    }
+  }
+
   info!("Overwriting deleted comments...");
   match diesel::update(
     comment::table
@@ -258,6 +356,24 @@ fn overwrite_deleted_posts_and_comments(conn: &mut PgConnection) {
       error!("Failed to overwrite deleted comments: {}", e)
     }
   }
+
+  info!("Removing edit history of deleted comments...");
+  let deleted_comments = comment::table
+    .filter(comment::deleted.eq(true))
+    .filter(comment::updated.lt(now.nullable() - 1.months()))
+    .select(comment::id);
+  match diesel::delete(
+    comment_edit::table.filter(comment_edit::comment_id.eq_any(deleted_comments)),
+  )
+  .execute(conn)
+  {
+    Ok(_) => {
+      info!("Done.");
+    }
+    Err(e) => {
+      error!("Failed to remove edit history of deleted comments: {}", e)
+    }
+  }
 }
 
 /// Re-calculate the site and community active counts every 12 hours
@@ -381,11 +497,138 @@ fn update_instance_software(conn: &mut PgConnection, user_agent: &str) {
   }
 }
 
+/// Publishes scheduled posts once their time has come
+async fn publish_scheduled_posts(context: &Data<LemmyContext>) -> Result<(), LemmyError> {
+  let scheduled_posts = Post::list_scheduled_to_publish(context.pool()).await?;
+  for post in scheduled_posts {
+    info!("Publishing scheduled post {}", post.ap_id);
+    let post = Post::publish_scheduled(context.pool(), post.id).await?;
+    PostAggregates::update_hot_rank(context.pool(), post.id).await?;
+    // A failure to federate shouldn't hold back the other posts
+    if let Err(e) = send_new_post(&post, context).await {
+      error!("Failed to send scheduled post {}: {}", post.ap_id, e);
+    }
+  }
+  Ok(())
+}
+
+/// Creates the posts of recurring post templates which are due, and sends them out.
+async fn publish_recurring_posts(context: &Data<LemmyContext>) -> Result<(), LemmyError> {
+  let recurring_posts = CommunityRecurringPost::list_due(context.pool()).await?;
+  for recurring_post in recurring_posts {
+    // A failure shouldn't hold back the other templates
+    match create_recurring_post(&recurring_post, context).await {
+      Ok(Some((post, unfeatured_post))) => {
+        if let Err(e) = send_recurring_post(&post, unfeatured_post, context).await {
+          error!("Failed to send recurring post {}: {}", post.ap_id, e);
+        }
+      }
+      Ok(None) => {}
+      Err(e) => error!(
+        "Failed to create recurring post \"{}\": {}",
+        recurring_post.name, e
+      ),
+    }
+  }
+  Ok(())
+}
+
+/// Creates a post from the template like `CreatePost` does, and moves the template to its next
+/// publish time. Returns the new post, and the previous post if it was unfeatured. If the creator
+/// can't post in the community anymore, no post is created.
+async fn create_recurring_post(
+  recurring_post: &CommunityRecurringPost,
+  context: &Data<LemmyContext>,
+) -> Result<Option<(Post, Option<Post>)>, LemmyError> {
+  let pool = context.pool();
+  // Posts which are due for a while are created once, not for every missed time
+  let next_publish_time = Schedule::parse(&recurring_post.schedule)?.next_after(naive_now());
+
+  let can_post = check_community_deleted_or_removed(recurring_post.community_id, pool)
+    .await
+    .is_ok()
+    && is_mod_or_admin(pool, recurring_post.creator_id, recurring_post.community_id)
+      .await
+      .is_ok();
+  if !can_post {
+    info!(
+      "Skipping recurring post \"{}\", its creator can't post in the community",
+      recurring_post.name
+    );
+    CommunityRecurringPost::advance(pool, recurring_post, None, next_publish_time).await?;
+    return Ok(None);
+  }
+
+  // The placeholders are filled with the scheduled time, even if the post is late
+  let publish_time = recurring_post.next_publish_time.unwrap_or_else(naive_now);
+  let name: String = replace_date_placeholders(&recurring_post.name, publish_time)
+    .chars()
+    .take(POST_TITLE_MAX_LENGTH)
+    .collect();
+  let post_form = PostInsertForm::builder()
+    .name(name)
+    .body(recurring_post.body.clone())
+    .creator_id(recurring_post.creator_id)
+    .community_id(recurring_post.community_id)
+    .featured_community(Some(recurring_post.featured))
+    .build();
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  let (post, unfeatured_post) = CommunityRecurringPost::create_post(
+    pool,
+    recurring_post,
+    &post_form,
+    move |post_id| {
+      generate_local_apub_endpoint(
+        EndpointType::Post,
+        &post_id.to_string(),
+        &protocol_and_hostname,
+      )
+    },
+    next_publish_time,
+  )
+  .await?;
+  Ok(Some((post, unfeatured_post)))
+}
+
+/// Sends the new post, which also features it if necessary, and unfeatures the previous post.
+async fn send_recurring_post(
+  post: &Post,
+  unfeatured_post: Option<Post>,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  send_new_post(post, context).await?;
+  if let Some(unfeatured_post) = unfeatured_post {
+    let community: ApubCommunity = Community::read(context.pool(), post.community_id)
+      .await?
+      .into();
+    let person: ApubPerson = Person::read(context.pool(), post.creator_id).await?.into();
+    let unfeatured_post: ApubPost = unfeatured_post.into();
+    CollectionRemove::send_remove_featured_post(&community, &unfeatured_post, &person, context)
+      .await?;
+  }
+  Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+  use super::run_timed;
   use lemmy_routes::nodeinfo::NodeInfo;
+  use lemmy_utils::metrics::SCHEDULED_TASK_DURATION;
   use reqwest::Client;
 
+  #[test]
+  fn test_run_timed() {
+    let mut ran = false;
+    run_timed("test_task", || ran = true);
+    assert!(ran);
+    assert_eq!(
+      1,
+      SCHEDULED_TASK_DURATION
+        .with_label_values(&["test_task"])
+        .get_sample_count()
+    );
+  }
+
   #[tokio::test]
   #[ignore]
   async fn test_nodeinfo() {